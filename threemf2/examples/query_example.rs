@@ -225,13 +225,13 @@ fn section_5_mesh_objects(package: &ThreemfPackage) {
 
         // Access mesh data
         let mesh = mesh_ref.mesh();
-        println!("  Vertices: {}", mesh.vertices.vertex.len());
-        println!("  Triangles: {}", mesh.triangles.triangle.len());
+        println!("  Vertices: {}", mesh_ref.vertex_count());
+        println!("  Triangles: {}", mesh_ref.triangle_count());
 
         // Check for beam lattice extension
         if let Some(beamlattice) = &mesh.beamlattice {
             println!("  Has beam lattice:");
-            println!("    Beams: {}", beamlattice.beams.beam.len());
+            println!("    Beams: {}", mesh_ref.beam_count());
             println!("    Min length: {}", beamlattice.minlength);
             println!("    Radius: {}", beamlattice.radius);
         }
@@ -28,6 +28,7 @@ fn main() {
             value: None,
         }],
         resources: Resources {
+            unknown_resources: vec![],
             object: vec![Object {
                 id: 1,
                 objecttype: Some(ObjectType::Model),
@@ -1,3 +1,6 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
 use crate::threemf_namespaces::{BEAM_LATTICE_BALLS_NS, BEAM_LATTICE_NS};
 
 #[cfg(feature = "write")]
@@ -52,10 +55,13 @@ pub struct BeamLattice {
     pub ballradius: Option<f64>,
 
     /// Specifies the clipping mode of the beam lattice
+    ///
+    /// Unqualified like the other attributes of the beam lattice, only the attributes of the
+    /// balls extension are in their own namespace.
     #[cfg_attr(feature = "speed-optimized-read", serde(default))]
     #[cfg_attr(
         any(feature = "write", feature = "memory-optimized-read"),
-        xml(ns(BEAM_LATTICE_NS), attribute)
+        xml(attribute)
     )]
     pub clippingmode: Option<ClippingMode>,
 
@@ -93,7 +99,7 @@ pub struct BeamLattice {
     #[cfg_attr(feature = "speed-optimized-read", serde(default))]
     #[cfg_attr(
         any(feature = "write", feature = "memory-optimized-read"),
-        xml(ns(BEAM_LATTICE_NS), attribute)
+        xml(attribute)
     )]
     pub cap: Option<CapMode>,
 
@@ -111,6 +117,123 @@ pub struct BeamLattice {
     pub beamsets: Option<BeamSets>,
 }
 
+impl BeamLattice {
+    /// Checks that every beam and ball references one of the `vertex_count` vertices of the
+    /// enclosing mesh.
+    ///
+    /// Returns [`BeamLatticeError::VertexIndexOutOfRange`] for the first beam, or if all beams
+    /// are valid the first ball, referencing a vertex that does not exist.
+    pub fn check_vertex_indices(&self, vertex_count: usize) -> Result<(), BeamLatticeError> {
+        if let Some(index) = self
+            .beams
+            .beam
+            .iter()
+            .position(|b| b.v1 >= vertex_count || b.v2 >= vertex_count)
+        {
+            return Err(BeamLatticeError::VertexIndexOutOfRange {
+                kind: LatticeElementKind::Beam,
+                index,
+            });
+        }
+
+        if let Some(index) = self
+            .balls
+            .iter()
+            .flat_map(|balls| &balls.ball)
+            .position(|b| b.vindex >= vertex_count)
+        {
+            return Err(BeamLatticeError::VertexIndexOutOfRange {
+                kind: LatticeElementKind::Ball,
+                index,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the effective `(pid, p1, p2)` of `beam`, a beam of this lattice, within an
+    /// object with the given `pid` and `pindex`.
+    ///
+    /// Following the override chain of the Beam Lattice specification, the beam `pid`
+    /// overrides the lattice `pid`, which in turn overrides the object `pid`. Likewise `p1`
+    /// and `p2` of the beam each override the lattice `pindex`, which overrides the object
+    /// `pindex`. A value is `None` if it isn't given at any level.
+    pub fn resolved_beam_properties(
+        &self,
+        beam: &Beam,
+        object_pid: Option<usize>,
+        object_pindex: Option<usize>,
+    ) -> (Option<usize>, Option<usize>, Option<usize>) {
+        let pindex = self.pindex.or(object_pindex);
+        (
+            beam.pid.or(self.pid).or(object_pid),
+            beam.p1.or(pindex),
+            beam.p2.or(pindex),
+        )
+    }
+
+    /// Returns the effective `(pid, p)` of `ball`, a ball of this lattice, within an object
+    /// with the given `pid` and `pindex`.
+    ///
+    /// Follows the same override chain as [`BeamLattice::resolved_beam_properties()`], with
+    /// the `p` of the ball in place of `p1` and `p2`.
+    pub fn resolved_ball_properties(
+        &self,
+        ball: &Ball,
+        object_pid: Option<usize>,
+        object_pindex: Option<usize>,
+    ) -> (Option<usize>, Option<usize>) {
+        (
+            ball.pid.or(self.pid).or(object_pid),
+            ball.p.or(self.pindex).or(object_pindex),
+        )
+    }
+}
+
+/// The elements of a [`BeamLattice`] referencing the vertices of the mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeElementKind {
+    /// A [`Beam`], referencing vertices with `v1` and `v2`.
+    Beam,
+
+    /// A [`Ball`], referencing a vertex with `vindex`.
+    Ball,
+}
+
+impl fmt::Display for LatticeElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Beam => write!(f, "Beam"),
+            Self::Ball => write!(f, "Ball"),
+        }
+    }
+}
+
+/// Errors found in a [`BeamLattice`] by [`BeamLattice::check_vertex_indices()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BeamLatticeError {
+    /// A beam or ball references a vertex that is not part of the mesh.
+    ///
+    /// `index` is the index of the beam or ball within the lattice.
+    VertexIndexOutOfRange {
+        kind: LatticeElementKind,
+        index: usize,
+    },
+}
+
+impl fmt::Display for BeamLatticeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VertexIndexOutOfRange { kind, index } => write!(
+                f,
+                "{kind} {index} references a vertex that is not part of the mesh"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for BeamLatticeError {}
+
 /// Ball mode for beam lattices - specifies whether balls are created at beam vertices
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "speed-optimized-read", serde(from = "String"))]
@@ -451,6 +574,134 @@ pub struct BallRef {
     pub index: usize,
 }
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Ball, Balls, Beam, BeamLattice, Beams};
+
+    fn lattice(pid: Option<usize>, pindex: Option<usize>) -> BeamLattice {
+        BeamLattice {
+            minlength: 0.0001,
+            radius: 1.0,
+            ballmode: None,
+            ballradius: None,
+            clippingmode: None,
+            clippingmesh: None,
+            representationmesh: None,
+            pid,
+            pindex,
+            cap: None,
+            beams: Beams { beam: vec![] },
+            balls: Some(Balls { ball: vec![] }),
+            beamsets: None,
+        }
+    }
+
+    fn beam(pid: Option<usize>, p1: Option<usize>, p2: Option<usize>) -> Beam {
+        Beam {
+            v1: 0,
+            v2: 1,
+            r1: None,
+            r2: None,
+            p1,
+            p2,
+            pid,
+            cap1: None,
+            cap2: None,
+        }
+    }
+
+    fn ball(pid: Option<usize>, p: Option<usize>) -> Ball {
+        Ball {
+            vindex: 0,
+            r: None,
+            p,
+            pid,
+        }
+    }
+
+    #[test]
+    fn resolved_beam_properties_test() {
+        // nothing given at any level
+        assert_eq!(
+            lattice(None, None).resolved_beam_properties(&beam(None, None, None), None, None),
+            (None, None, None)
+        );
+
+        // everything inherited from the object
+        assert_eq!(
+            lattice(None, None).resolved_beam_properties(&beam(None, None, None), Some(3), Some(1)),
+            (Some(3), Some(1), Some(1))
+        );
+
+        // the lattice overrides the object
+        assert_eq!(
+            lattice(Some(4), Some(2)).resolved_beam_properties(
+                &beam(None, None, None),
+                Some(3),
+                Some(1)
+            ),
+            (Some(4), Some(2), Some(2))
+        );
+
+        // the lattice pid with the object pindex
+        assert_eq!(
+            lattice(Some(4), None).resolved_beam_properties(
+                &beam(None, None, None),
+                Some(3),
+                Some(1)
+            ),
+            (Some(4), Some(1), Some(1))
+        );
+
+        // the beam overrides the lattice, p1 and p2 independently
+        assert_eq!(
+            lattice(Some(4), Some(2)).resolved_beam_properties(
+                &beam(Some(5), Some(6), None),
+                Some(3),
+                Some(1)
+            ),
+            (Some(5), Some(6), Some(2))
+        );
+        assert_eq!(
+            lattice(None, None).resolved_beam_properties(
+                &beam(None, None, Some(7)),
+                Some(3),
+                Some(1)
+            ),
+            (Some(3), Some(1), Some(7))
+        );
+    }
+
+    #[test]
+    fn resolved_ball_properties_test() {
+        let lattice_without_properties = lattice(None, None);
+        assert_eq!(
+            lattice_without_properties.resolved_ball_properties(&ball(None, None), None, None),
+            (None, None)
+        );
+        assert_eq!(
+            lattice_without_properties.resolved_ball_properties(
+                &ball(None, None),
+                Some(3),
+                Some(1)
+            ),
+            (Some(3), Some(1))
+        );
+
+        let lattice = lattice(Some(4), Some(2));
+        assert_eq!(
+            lattice.resolved_ball_properties(&ball(None, None), Some(3), Some(1)),
+            (Some(4), Some(2))
+        );
+        assert_eq!(
+            lattice.resolved_ball_properties(&ball(Some(5), Some(6)), Some(3), Some(1)),
+            (Some(5), Some(6))
+        );
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -591,6 +842,95 @@ mod write_tests {
         assert_eq!(beamlattice_string, xml_string);
     }
 
+    fn beamlattice_with_all_attributes() -> BeamLattice {
+        BeamLattice {
+            minlength: 0.0001,
+            radius: 1.0,
+            ballmode: Some(BallMode::All),
+            ballradius: Some(0.5),
+            clippingmode: Some(ClippingMode::Inside),
+            clippingmesh: Some(2),
+            representationmesh: Some(3),
+            pid: Some(4),
+            pindex: Some(0),
+            cap: Some(CapMode::Butt),
+            beams: Beams {
+                beam: vec![Beam {
+                    v1: 0,
+                    v2: 1,
+                    r1: Some(1.5),
+                    r2: Some(1.6),
+                    p1: Some(1),
+                    p2: Some(2),
+                    pid: Some(4),
+                    cap1: Some(CapMode::Hemisphere),
+                    cap2: Some(CapMode::Sphere),
+                }],
+            },
+            balls: Some(Balls {
+                ball: vec![Ball {
+                    vindex: 0,
+                    r: Some(0.5),
+                    p: Some(1),
+                    pid: Some(4),
+                }],
+            }),
+            beamsets: None,
+        }
+    }
+
+    #[test]
+    pub fn toxml_beamlattice_with_all_attributes_test() {
+        let xml_string = format!(
+            r#"<beamlattice xmlns="{bl_ns}" xmlns:{bl2_prefix}="{bl2_ns}" minlength="0.0001" radius="1" {bl2_prefix}:ballmode="all" {bl2_prefix}:ballradius="0.5" clippingmode="inside" clippingmesh="2" representationmesh="3" pid="4" pindex="0" cap="butt"><beams><beam v1="0" v2="1" r1="1.5" r2="1.6" p1="1" p2="2" pid="4" cap1="hemisphere" cap2="sphere" /></beams><{bl2_prefix}:balls><ball vindex="0" r="0.5" p="1" pid="4" /></{bl2_prefix}:balls></beamlattice>"#,
+            bl_ns = BEAM_LATTICE_NS,
+            bl2_prefix = BEAM_LATTICE_BALLS_PREFIX,
+            bl2_ns = BEAM_LATTICE_BALLS_NS,
+        );
+        let beamlattice_string = to_string(&beamlattice_with_all_attributes()).unwrap();
+
+        assert_eq!(beamlattice_string, xml_string);
+    }
+
+    #[test]
+    pub fn toxml_beamlattice_attribute_namespaces_in_mesh_test() {
+        use crate::core::mesh::{Mesh, Triangles, Vertices};
+
+        let mesh = Mesh {
+            vertices: Vertices { vertex: vec![] },
+            triangles: Triangles { triangle: vec![] },
+            trianglesets: None,
+            beamlattice: Some(beamlattice_with_all_attributes()),
+        };
+        let mesh_string = to_string(&mesh).unwrap();
+
+        // the lattice is written with a prefix within the core namespace, its attributes
+        // must stay unqualified apart from the ones of the balls extension
+        let start = mesh_string.find("beamlattice ").unwrap();
+        let end = start + mesh_string[start..].find('>').unwrap();
+        let start_tag = &mesh_string[start..end];
+        for attribute in [
+            r#" minlength="0.0001""#,
+            r#" radius="1""#,
+            r#" clippingmode="inside""#,
+            r#" clippingmesh="2""#,
+            r#" representationmesh="3""#,
+            r#" pid="4""#,
+            r#" pindex="0""#,
+            r#" cap="butt""#,
+        ] {
+            assert!(start_tag.contains(attribute), "{attribute} in {start_tag}");
+        }
+        for attribute in ["ballmode=\"all\"", "ballradius=\"0.5\""] {
+            let prefixed = format!(" {BEAM_LATTICE_BALLS_PREFIX}:{attribute}");
+            assert!(start_tag.contains(&prefixed), "{prefixed} in {start_tag}");
+        }
+        assert!(mesh_string.contains(
+            r#" v1="0" v2="1" r1="1.5" r2="1.6" p1="1" p2="2" pid="4" cap1="hemisphere" cap2="sphere" />"#
+        ));
+        assert!(mesh_string.contains(r#" vindex="0" r="0.5" p="1" pid="4" />"#));
+    }
+
     #[derive(Debug, ToXml, PartialEq, Eq)]
     #[xml(ns(b2 = BEAM_LATTICE_BALLS_NS))]
     struct EnumTestType {
@@ -918,6 +1258,7 @@ mod memory_optimized_read_tests {
                 recommendedextensions: None,
                 metadata: vec![],
                 resources: Resources {
+                    unknown_resources: vec![],
                     object: vec![Object {
                         id: 1,
                         objecttype: None,
@@ -1429,6 +1770,7 @@ mod speed_optimized_read_tests {
                 recommendedextensions: None,
                 metadata: vec![],
                 resources: Resources {
+                    unknown_resources: vec![],
                     object: vec![Object {
                         id: 1,
                         objecttype: None,
@@ -7,6 +7,8 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{string::String, vec::Vec};
+
 use crate::{
     core::transform::Transform,
     threemf_namespaces::{CORE_NS, PROD_NS},
@@ -34,6 +36,11 @@ pub struct Build {
 }
 
 /// Represents a single item in the build configuration, referencing an object with transform.
+///
+/// The attributes are written in the order of the fields: `objectid`, `transform`,
+/// `partnumber`, `p:path` and `p:UUID`. Together with the fixed six decimals the `transform`
+/// is written with, build items written in this order are written byte for byte the same
+/// after reading them back.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
 #[cfg_attr(feature = "write", derive(ToXml))]
@@ -78,7 +85,10 @@ mod write_tests {
     use instant_xml::to_string;
     use pretty_assertions::assert_eq;
 
-    use crate::threemf_namespaces::{CORE_NS, PROD_NS, PROD_PREFIX};
+    use crate::{
+        core::transform::Transform,
+        threemf_namespaces::{CORE_NS, PROD_NS, PROD_PREFIX},
+    };
 
     use super::{Build, Item};
 
@@ -118,6 +128,26 @@ mod write_tests {
         assert_eq!(item_string, xml_string);
     }
 
+    #[test]
+    pub fn toxml_item_attribute_order_test() {
+        let xml_string = format!(
+            r#"<item xmlns="{}" xmlns:{}="{}" objectid="6" transform="1.000000 0.000000 0.000000 0.000000 1.000000 0.000000 0.000000 0.000000 1.000000 10.000000 0.000000 0.000000" partnumber="part_1" {}:path="/3D/part.model" {}:UUID="someUUID" />"#,
+            CORE_NS, PROD_PREFIX, PROD_NS, PROD_PREFIX, PROD_PREFIX
+        );
+        let item = Item {
+            objectid: 6,
+            transform: Some(Transform([
+                1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 10.0, 0.0, 0.0,
+            ])),
+            partnumber: Some("part_1".to_string()),
+            path: Some("/3D/part.model".to_owned()),
+            uuid: Some("someUUID".to_owned()),
+        };
+        let item_string = to_string(&item).unwrap();
+
+        assert_eq!(item_string, xml_string);
+    }
+
     #[test]
     pub fn toxml_build_test() {
         let xml_string = format!(
@@ -0,0 +1,2187 @@
+//! Builders for constructing mesh geometry.
+//!
+//! These builders only depend on the [`core`](crate::core) types, so they are available
+//! without any of the `io` features:
+//!
+//! - [`MeshBuilder`] - Vertices and triangles of a [`Mesh`], with optional triangle sets
+//!   and beam lattice
+//! - [`TriangleSetsBuilder`] - Organizes triangles into named groups
+//! - [`BeamLatticeBuilder`] - Beams and balls connecting the vertices of a mesh
+//! - [`BeamBuilder`], [`BallBuilder`] and [`BeamSetBuilder`] - Individual lattice elements
+//!
+//! The object, model and package builders of the `io` module build their mesh objects
+//! with [`MeshBuilder`] and re-export these builders.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use threemf2::core::builder::MeshBuilder;
+//!
+//! let mut builder = MeshBuilder::new();
+//! builder
+//!     .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+//!     .add_triangle(&[0, 1, 2]);
+//! let mesh = builder.build()?;
+//! ```
+
+use alloc::{
+    borrow::ToOwned,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{fmt, ops::Range};
+
+use crate::core::{
+    beamlattice::{
+        Ball, BallMode, BallRef, Balls, Beam, BeamLattice, BeamLatticeError, BeamRef, BeamSet,
+        BeamSets, Beams, CapMode, ClippingMode,
+    },
+    mesh::{self, Mesh, Triangle, Triangles, Vertex, Vertices},
+    model::Unit,
+    triangle_set::{TriangleRef, TriangleRefRange, TriangleSet, TriangleSets},
+};
+
+/// Default beam lattice `minlength` and `radius` in millimeters.
+const DEFAULT_BEAM_LATTICE_LENGTH_MM: f64 = 0.0001;
+
+/// Errors that can occur when building a [`Mesh`] with [`MeshBuilder::build()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    /// A triangle references the same vertex more than once, e.g. `v1 == v2`.
+    ///
+    /// Remove the triangle with [`MeshBuilder::remove_degenerate_triangles()`] or keep it
+    /// with [`MeshBuilder::allow_degenerate_triangles()`].
+    DegenerateTriangleIndices { triangle: usize },
+
+    /// The mesh has triangles but no vertices for them to reference.
+    TrianglesWithoutVertices,
+
+    /// A triangle references the vertex `index`, which was not added.
+    TriangleIndexOutOfBounds { triangle: usize, index: usize },
+
+    /// The beam lattice of the mesh is invalid, e.g. a beam references a vertex that
+    /// was not added.
+    BeamLattice(BeamLatticeError),
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DegenerateTriangleIndices { triangle } => write!(
+                f,
+                "Triangle {triangle} references the same vertex more than once"
+            ),
+            Self::TrianglesWithoutVertices => write!(f, "Mesh has triangles but no vertices"),
+            Self::TriangleIndexOutOfBounds { triangle, index } => write!(
+                f,
+                "Triangle {triangle} references vertex {index}, which does not exist"
+            ),
+            Self::BeamLattice(err) => write!(f, "Invalid beam lattice: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for MeshError {}
+
+/// Type-safe wrapper for object IDs to prevent mix-ups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectId(pub(crate) usize);
+
+impl From<usize> for ObjectId {
+    fn from(id: usize) -> Self {
+        ObjectId(id)
+    }
+}
+
+impl From<ObjectId> for usize {
+    fn from(id: ObjectId) -> usize {
+        id.0
+    }
+}
+
+/// Type-safe wrapper for the ids of property groups, e.g. base materials, referenced by
+/// the `pid` of objects and triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PropertyGroupId(pub(crate) usize);
+
+impl From<usize> for PropertyGroupId {
+    fn from(id: usize) -> Self {
+        PropertyGroupId(id)
+    }
+}
+
+impl From<PropertyGroupId> for usize {
+    fn from(id: PropertyGroupId) -> usize {
+        id.0
+    }
+}
+
+/// Handle of a triangle set or beam set returned when the set is added, i.e. its position
+/// among the sets of the built [`TriangleSets`] or [`BeamSets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetId(pub(crate) usize);
+
+impl From<SetId> for usize {
+    fn from(id: SetId) -> usize {
+        id.0
+    }
+}
+
+/// Builder for constructing triangle mesh geometry.
+///
+/// `MeshBuilder` allows you to define 3D geometry by adding vertices and triangles.
+/// It also supports optional features like triangle sets and beam lattices.
+///
+/// Vertices are referenced by their 0-based index in the order they were added.
+/// Triangles reference vertices by index.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// obj.add_vertices(&[
+///     [0.0, 0.0, 0.0],   // vertex 0
+///     [10.0, 0.0, 0.0],  // vertex 1
+///     [10.0, 10.0, 0.0], // vertex 2
+/// ]);
+///
+/// obj.add_triangles(&[
+///     [0, 1, 2],  // Triangle using vertices 0, 1, 2
+/// ]);
+/// ```
+pub struct MeshBuilder {
+    vertices: Vec<Vertex>,
+    triangles: Vec<Triangle>,
+    triangle_sets: Option<TriangleSetsBuilder>,
+    beam_lattice: Option<BeamLatticeBuilder>,
+    allow_degenerate_triangles: bool,
+    skip_validation: bool,
+    unit: Unit,
+}
+
+impl MeshBuilder {
+    /// Create an empty mesh builder for a model in millimeters.
+    pub fn new() -> Self {
+        Self::with_unit(Unit::Millimeter)
+    }
+
+    /// Create an empty mesh builder for a model in `unit`.
+    ///
+    /// The unit is only used to express the [`BeamLatticeBuilder`] defaults in it.
+    pub fn with_unit(unit: Unit) -> Self {
+        Self {
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+            triangle_sets: None,
+            beam_lattice: None,
+            allow_degenerate_triangles: false,
+            skip_validation: false,
+            unit,
+        }
+    }
+
+    /// Allow triangles that reference the same vertex more than once.
+    ///
+    /// By default building the mesh fails with
+    /// [`MeshError::DegenerateTriangleIndices`] for such triangles.
+    pub fn allow_degenerate_triangles(&mut self, allow: bool) -> &mut Self {
+        self.allow_degenerate_triangles = allow;
+        self
+    }
+
+    /// Skip the validation of the geometry when building the mesh.
+    ///
+    /// By default [`MeshBuilder::build()`] checks every triangle and the beam lattice against
+    /// the added vertices. Skipping it saves a pass over large meshes whose indices are known
+    /// to be valid, invalid indices then end up in the written model.
+    pub fn skip_validation(&mut self, skip: bool) -> &mut Self {
+        self.skip_validation = skip;
+        self
+    }
+
+    /// Add a single vertex at the specified coordinates.
+    ///
+    /// Returns the builder for method chaining.
+    ///
+    /// # Parameters
+    ///
+    /// - `coords`: 3D coordinates as `[x, y, z]`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_vertex(&[0.0, 0.0, 0.0])
+    ///    .add_vertex(&[10.0, 0.0, 0.0])
+    ///    .add_vertex(&[0.0, 10.0, 0.0]);
+    /// ```
+    pub fn add_vertex(&mut self, coords: &[f64; 3]) -> &mut Self {
+        self.vertices.push(Vertex {
+            x: coords[0],
+            y: coords[1],
+            z: coords[2],
+        });
+        self
+    }
+
+    /// Add multiple vertices from a slice of coordinate arrays.
+    ///
+    /// Each element should be a 3D coordinate `[x, y, z]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_vertices(&[
+    ///     [0.0, 0.0, 0.0],
+    ///     [10.0, 0.0, 0.0],
+    ///     [10.0, 10.0, 0.0],
+    /// ]);
+    /// ```
+    pub fn add_vertices(&mut self, vertices: &[[f64; 3]]) -> &mut Self {
+        for vertex in vertices {
+            self.add_vertex(vertex);
+        }
+
+        self
+    }
+
+    /// Add vertices from a flattened coordinate array.
+    ///
+    /// The slice should contain coordinate values in sequence: `[x0, y0, z0, x1, y1, z1, ...]`.
+    /// The length must be a multiple of 3.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_vertices_flat(&[
+    ///     0.0, 0.0, 0.0,     // vertex 0
+    ///     10.0, 0.0, 0.0,    // vertex 1
+    ///     10.0, 10.0, 0.0,   // vertex 2
+    /// ]);
+    /// ```
+    pub fn add_vertices_flat(&mut self, vertices: &[f64]) -> &mut Self {
+        for vertex in vertices.chunks_exact(3) {
+            self.vertices.push(Vertex {
+                x: vertex[0],
+                y: vertex[1],
+                z: vertex[2],
+            });
+        }
+
+        self
+    }
+
+    /// Add a single triangle referencing three vertices by index.
+    ///
+    /// Vertices are referenced by their 0-based index in the order they were added.
+    /// Indices must reference existing vertices.
+    ///
+    /// # Parameters
+    ///
+    /// - `indices`: Triangle vertex indices as `[v1, v2, v3]`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_vertex(&[0.0, 0.0, 0.0]);    // index 0
+    /// obj.add_vertex(&[10.0, 0.0, 0.0]);   // index 1
+    /// obj.add_vertex(&[0.0, 10.0, 0.0]);   // index 2
+    /// obj.add_triangle(&[0, 1, 2]);
+    /// ```
+    pub fn add_triangle(&mut self, indices: &[usize; 3]) -> &mut Self {
+        self.triangles.push(Triangle {
+            v1: indices[0],
+            v2: indices[1],
+            v3: indices[2],
+            p1: None,
+            p2: None,
+            p3: None,
+            pid: None,
+        });
+        self
+    }
+
+    /// Add a single triangle with properties from the property group `pid`.
+    ///
+    /// `pindices` are the indices of the properties within the group for the three
+    /// vertices, e.g. the materials of a base materials group.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let red = builder.add_base_materials(|m| {
+    ///     m.add_material("Red", "#FF0000");
+    /// })?;
+    /// obj.add_triangle_with_properties(&[0, 1, 2], red, &[0, 0, 0]);
+    /// ```
+    pub fn add_triangle_with_properties(
+        &mut self,
+        indices: &[usize; 3],
+        pid: PropertyGroupId,
+        pindices: &[usize; 3],
+    ) -> &mut Self {
+        self.triangles.push(Triangle {
+            v1: indices[0],
+            v2: indices[1],
+            v3: indices[2],
+            p1: Some(pindices[0]),
+            p2: Some(pindices[1]),
+            p3: Some(pindices[2]),
+            pid: Some(pid.0),
+        });
+        self
+    }
+
+    /// Add multiple triangles from a slice of index arrays.
+    ///
+    /// Each element should be a triangle with three vertex indices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_triangles(&[
+    ///     [0, 1, 2],
+    ///     [0, 2, 3],
+    ///     [0, 3, 4],
+    /// ]);
+    /// ```
+    pub fn add_triangles(&mut self, triangles: &[[usize; 3]]) -> &mut Self {
+        for triangle in triangles {
+            self.add_triangle(triangle);
+        }
+
+        self
+    }
+
+    /// Add triangles from a flattened index array.
+    ///
+    /// The slice should contain vertex indices in sequence: `[v1, v2, v3, v1, v2, v3, ...]`.
+    /// The length must be a multiple of 3.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_triangles_flat(&[
+    ///     0, 1, 2,  // triangle 0
+    ///     0, 2, 3,  // triangle 1
+    /// ]);
+    /// ```
+    pub fn add_triangles_flat(&mut self, triangles: &[usize]) -> &mut Self {
+        for triangle in triangles.chunks_exact(3) {
+            self.triangles.push(Triangle {
+                v1: triangle[0],
+                v2: triangle[1],
+                v3: triangle[2],
+                p1: None,
+                p2: None,
+                p3: None,
+                pid: None,
+            });
+        }
+
+        self
+    }
+
+    /// Replace the vertices with prebuilt ones.
+    ///
+    /// The vector is moved into the mesh without copying, which avoids the per-vertex
+    /// cost of [`MeshBuilder::add_vertices`] for geometry that already exists as [`Vertex`].
+    /// Previously added vertices are discarded.
+    pub fn set_vertices(&mut self, vertices: Vec<Vertex>) -> &mut Self {
+        self.vertices = vertices;
+        self
+    }
+
+    /// Replace the triangles with prebuilt ones.
+    ///
+    /// The vector is moved into the mesh without copying, keeping the properties of the
+    /// triangles. Previously added triangles are discarded.
+    pub fn set_triangles(&mut self, triangles: Vec<Triangle>) -> &mut Self {
+        self.triangles = triangles;
+        self
+    }
+
+    /// Add triangle sets to organize triangles into named groups.
+    ///
+    /// Triangle sets allow you to group triangles by name and identifier for
+    /// organizational purposes. See [`TriangleSetsBuilder`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: A closure that configures the [`TriangleSetsBuilder`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_triangle_sets(|sets| {
+    ///     sets.add_set("TopFace", "top-id", &[0, 1], &[]);
+    ///     sets.add_set("BottomFace", "bottom-id", &[2, 3], &[]);
+    /// });
+    /// ```
+    pub fn add_triangle_sets<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut TriangleSetsBuilder),
+    {
+        if let Some(ref mut builder) = self.triangle_sets {
+            f(builder);
+        } else {
+            let mut builder = TriangleSetsBuilder::new();
+            f(&mut builder);
+            self.triangle_sets = Some(builder);
+        }
+
+        self
+    }
+
+    /// Add a beam lattice structure to the mesh.
+    ///
+    /// Beam lattices define strut-like structures connecting vertices. See
+    /// [`BeamLatticeBuilder`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: A closure that configures the [`BeamLatticeBuilder`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_beam_lattice(|lattice| {
+    ///     lattice.radius(0.5)
+    ///            .add_beam(0, 1)
+    ///            .add_beam(1, 2);
+    /// });
+    /// ```
+    pub fn add_beam_lattice<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut BeamLatticeBuilder),
+    {
+        let builder = self
+            .beam_lattice
+            .get_or_insert_with(|| BeamLatticeBuilder::new(self.unit.clone()));
+        builder.first_vertex = self.vertices.len();
+        f(builder);
+        // vertices generated by the lattice are appended after the existing ones
+        self.vertices.append(&mut builder.vertices);
+        self
+    }
+
+    /// Remove triangles whose area is below `area_epsilon`, including triangles that
+    /// reference the same vertex more than once.
+    ///
+    /// Triangle sets are updated to the new triangle indices. Triangles referencing
+    /// vertices that don't exist are kept as they are. Returns the number of removed triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    /// obj.add_triangles(&[[0, 1, 3], [0, 1, 2]]);
+    /// assert_eq!(obj.remove_degenerate_triangles(1e-12), 1);
+    /// ```
+    pub fn remove_degenerate_triangles(&mut self, area_epsilon: f64) -> usize {
+        let vertex = |index: usize| self.vertices.get(index).map(|v| [v.x, v.y, v.z]);
+        let is_degenerate = |t: &Triangle| {
+            if t.has_repeated_vertex() {
+                return true;
+            }
+            let (Some(a), Some(b), Some(c)) = (vertex(t.v1), vertex(t.v2), vertex(t.v3)) else {
+                return false;
+            };
+
+            let cross = mesh::face_normal(a, b, c);
+            // the area is half the length of the cross product, compared squared as
+            // `f64::sqrt` is not available without std
+            let double_area_squared =
+                cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2];
+            area_epsilon > 0.0 && double_area_squared < 4.0 * area_epsilon * area_epsilon
+        };
+
+        let mut new_indices = Vec::with_capacity(self.triangles.len());
+        let mut next_index = 0;
+        for triangle in &self.triangles {
+            if is_degenerate(triangle) {
+                new_indices.push(None);
+            } else {
+                new_indices.push(Some(next_index));
+                next_index += 1;
+            }
+        }
+
+        let removed = self.triangles.len() - next_index;
+        if removed == 0 {
+            return 0;
+        }
+
+        let mut index = 0;
+        self.triangles.retain(|_| {
+            let keep = new_indices[index].is_some();
+            index += 1;
+            keep
+        });
+
+        if let Some(triangle_sets) = &mut self.triangle_sets {
+            triangle_sets.remap_triangles(&new_indices);
+        }
+
+        removed
+    }
+
+    /// Remove vertices that are not referenced by any triangle, beam or ball.
+    ///
+    /// The remaining vertices keep their order and all references are updated to the new
+    /// vertex indices. Returns the number of removed vertices.
+    pub fn remove_unused_vertices(&mut self) -> usize {
+        let mut used = vec![false; self.vertices.len()];
+        let mut mark = |index: usize| {
+            if let Some(is_used) = used.get_mut(index) {
+                *is_used = true;
+            }
+        };
+
+        for triangle in &self.triangles {
+            mark(triangle.v1);
+            mark(triangle.v2);
+            mark(triangle.v3);
+        }
+        if let Some(beam_lattice) = &self.beam_lattice {
+            for beam in &beam_lattice.beams {
+                mark(beam.v1);
+                mark(beam.v2);
+            }
+            for ball in &beam_lattice.balls {
+                mark(ball.vindex);
+            }
+        }
+
+        let mut new_indices = Vec::with_capacity(used.len());
+        let mut next_index = 0;
+        for &is_used in &used {
+            new_indices.push(next_index);
+            if is_used {
+                next_index += 1;
+            }
+        }
+
+        let removed = self.vertices.len() - next_index;
+        if removed == 0 {
+            return 0;
+        }
+
+        let mut index = 0;
+        self.vertices.retain(|_| {
+            let keep = used[index];
+            index += 1;
+            keep
+        });
+
+        let remap = |index: &mut usize| {
+            if let Some(&new_index) = new_indices.get(*index) {
+                *index = new_index;
+            }
+        };
+        for triangle in &mut self.triangles {
+            remap(&mut triangle.v1);
+            remap(&mut triangle.v2);
+            remap(&mut triangle.v3);
+        }
+        if let Some(beam_lattice) = &mut self.beam_lattice {
+            for beam in &mut beam_lattice.beams {
+                remap(&mut beam.v1);
+                remap(&mut beam.v2);
+            }
+            for ball in &mut beam_lattice.balls {
+                remap(&mut ball.vindex);
+            }
+        }
+
+        removed
+    }
+
+    /// Merge vertices closer than `epsilon` to each other into the first of them, e.g. the
+    /// per-triangle copies of the vertices exported by CAD tools.
+    ///
+    /// Vertices are bucketed by their coordinates quantized to cells of size `epsilon`, so
+    /// each vertex is only compared to the vertices in the neighboring cells. An `epsilon`
+    /// of zero only merges vertices at exactly the same position. The remaining vertices keep
+    /// their order and all triangles, beams and balls are updated to the new vertex indices,
+    /// so the orientation of the triangles is preserved.
+    ///
+    /// Triangles that collapse because two of their vertices were merged are removed and the
+    /// triangle sets are updated to the new triangle indices. Triangles that referenced the
+    /// same vertex more than once before are kept. Returns the number of removed triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// // two triangles sharing an edge, each with their own copy of its vertices
+    /// obj.add_vertices(&[
+    ///     [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+    ///     [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0],
+    /// ]);
+    /// obj.add_triangles(&[[0, 1, 2], [3, 4, 5]]);
+    /// assert_eq!(obj.dedup_vertices(1e-9), 0);
+    /// ```
+    pub fn dedup_vertices(&mut self, epsilon: f64) -> usize {
+        let epsilon = epsilon.max(0.0);
+        let cell = |v: &Vertex| {
+            [v.x, v.y, v.z].map(|c| {
+                if epsilon == 0.0 {
+                    // adding zero turns -0.0 into 0.0
+                    return (c + 0.0).to_bits() as i64;
+                }
+                // `f64::floor` is not available without std
+                let q = c / epsilon;
+                let t = q as i64;
+                if (t as f64) > q { t - 1 } else { t }
+            })
+        };
+        let offsets = if epsilon > 0.0 { -1..=1 } else { 0..=0 };
+        let is_close = |a: &Vertex, b: &Vertex| {
+            let d = [a.x - b.x, a.y - b.y, a.z - b.z];
+            d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= epsilon * epsilon
+        };
+
+        let mut cells: BTreeMap<[i64; 3], Vec<usize>> = BTreeMap::new();
+        let mut new_indices = Vec::with_capacity(self.vertices.len());
+        let mut kept = vec![false; self.vertices.len()];
+        let mut next_index = 0;
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            let key = cell(vertex);
+            let survivor = 'search: {
+                for dx in offsets.clone() {
+                    for dy in offsets.clone() {
+                        for dz in offsets.clone() {
+                            let neighbor = [
+                                key[0].saturating_add(dx),
+                                key[1].saturating_add(dy),
+                                key[2].saturating_add(dz),
+                            ];
+                            if let Some(&survivor) = cells.get(&neighbor).and_then(|c| {
+                                c.iter().find(|&&s| is_close(&self.vertices[s], vertex))
+                            }) {
+                                break 'search Some(survivor);
+                            }
+                        }
+                    }
+                }
+                None
+            };
+
+            match survivor {
+                Some(survivor) => new_indices.push(new_indices[survivor]),
+                None => {
+                    new_indices.push(next_index);
+                    next_index += 1;
+                    kept[index] = true;
+                    cells.entry(key).or_default().push(index);
+                }
+            }
+        }
+
+        if next_index == self.vertices.len() {
+            return 0;
+        }
+
+        let mut index = 0;
+        self.vertices.retain(|_| {
+            let keep = kept[index];
+            index += 1;
+            keep
+        });
+
+        let remap = |index: &mut usize| {
+            if let Some(&new_index) = new_indices.get(*index) {
+                *index = new_index;
+            }
+        };
+        let mut new_triangle_indices = Vec::with_capacity(self.triangles.len());
+        let mut next_triangle_index = 0;
+        for triangle in &mut self.triangles {
+            let was_degenerate = triangle.has_repeated_vertex();
+            remap(&mut triangle.v1);
+            remap(&mut triangle.v2);
+            remap(&mut triangle.v3);
+            if !was_degenerate && triangle.has_repeated_vertex() {
+                new_triangle_indices.push(None);
+            } else {
+                new_triangle_indices.push(Some(next_triangle_index));
+                next_triangle_index += 1;
+            }
+        }
+        if let Some(beam_lattice) = &mut self.beam_lattice {
+            for beam in &mut beam_lattice.beams {
+                remap(&mut beam.v1);
+                remap(&mut beam.v2);
+            }
+            for ball in &mut beam_lattice.balls {
+                remap(&mut ball.vindex);
+            }
+        }
+
+        let removed = self.triangles.len() - next_triangle_index;
+        if removed > 0 {
+            let mut index = 0;
+            self.triangles.retain(|_| {
+                let keep = new_triangle_indices[index].is_some();
+                index += 1;
+                keep
+            });
+
+            if let Some(triangle_sets) = &mut self.triangle_sets {
+                triangle_sets.remap_triangles(&new_triangle_indices);
+            }
+        }
+
+        removed
+    }
+
+    /// Flip all triangles if the volume they enclose is negative, so their normals point
+    /// outwards as slicers and renderers expect. Returns whether the triangles were flipped.
+    ///
+    /// The triangles are expected to form a closed mesh with a consistent winding, see
+    /// [`Mesh::signed_volume()`]. Flipping swaps `v2` and `v3` of every triangle together
+    /// with their properties `p2` and `p3`.
+    pub fn orient_outward(&mut self) -> bool {
+        if mesh::signed_volume(&self.vertices, &self.triangles) >= 0.0 {
+            return false;
+        }
+
+        for triangle in &mut self.triangles {
+            core::mem::swap(&mut triangle.v2, &mut triangle.v3);
+            core::mem::swap(&mut triangle.p2, &mut triangle.p3);
+        }
+        true
+    }
+
+    /// Build the [`Mesh`] from the added geometry.
+    ///
+    /// Returns [`MeshError::TriangleIndexOutOfBounds`] for the first triangle referencing
+    /// a vertex that was not added.
+    /// Returns [`MeshError::DegenerateTriangleIndices`] for the first triangle referencing
+    /// the same vertex more than once, unless allowed with
+    /// [`MeshBuilder::allow_degenerate_triangles()`].
+    /// Returns [`MeshError::BeamLattice`] if a beam or ball references a vertex that was
+    /// not added.
+    ///
+    /// None of these checks run if skipped with [`MeshBuilder::skip_validation()`].
+    pub fn build(self) -> Result<Mesh, MeshError> {
+        if !self.skip_validation {
+            self.validate()?;
+        }
+
+        // the schema requires at least one triangle set, an empty container is left out
+        let trianglesets = self
+            .triangle_sets
+            .map(|b| b.build())
+            .filter(|sets| !sets.trianglesets.is_empty());
+        let beamlattice = self.beam_lattice.map(|b| b.build());
+        if !self.skip_validation
+            && let Some(beamlattice) = &beamlattice
+        {
+            beamlattice
+                .check_vertex_indices(self.vertices.len())
+                .map_err(MeshError::BeamLattice)?;
+        }
+        Ok(Mesh {
+            vertices: Vertices {
+                vertex: self.vertices,
+            },
+            triangles: Triangles {
+                triangle: self.triangles,
+            },
+            trianglesets,
+            beamlattice,
+        })
+    }
+
+    /// Checks the triangles against the added vertices.
+    fn validate(&self) -> Result<(), MeshError> {
+        if self.vertices.is_empty() && !self.triangles.is_empty() {
+            return Err(MeshError::TrianglesWithoutVertices);
+        }
+
+        for (triangle, t) in self.triangles.iter().enumerate() {
+            if let Some(index) = [t.v1, t.v2, t.v3]
+                .into_iter()
+                .find(|&index| index >= self.vertices.len())
+            {
+                return Err(MeshError::TriangleIndexOutOfBounds { triangle, index });
+            }
+        }
+
+        if !self.allow_degenerate_triangles
+            && let Some(triangle) = self
+                .triangles
+                .iter()
+                .position(Triangle::has_repeated_vertex)
+        {
+            return Err(MeshError::DegenerateTriangleIndices { triangle });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MeshBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for organizing triangles into named sets.
+///
+/// Triangle sets allow you to group triangles within a mesh for organizational purposes,
+/// such as identifying different faces or regions. Each set has a name and identifier,
+/// and references triangles either individually or as ranges.
+///
+/// Triangle sets are added as a recommended extension (not required).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// obj.add_triangle_sets(|sets| {
+///     // Add a set referencing specific triangle indices, keeping its id
+///     let top_face = sets.add_set("TopFace", "top-id", &[0, 1, 2], &[]);
+///
+///     // Add a set using a range of triangles
+///     sets.add_set("SideFaces", "side-id", &[], &[(3, 10)]);
+///
+///     // Mix individual refs and ranges
+///     sets.add_set("Mixed", "mixed-id", &[11, 12], &[(20, 30), (40, 50)]);
+/// });
+/// ```
+pub struct TriangleSetsBuilder {
+    sets: Vec<TriangleSet>,
+}
+
+impl TriangleSetsBuilder {
+    fn new() -> Self {
+        Self { sets: Vec::new() }
+    }
+
+    /// Add a triangle set with a name, identifier, and triangle references.
+    ///
+    /// Triangles can be referenced either individually (via `refs`) or as ranges
+    /// (via `ranges`). Both methods can be used together.
+    ///
+    /// Returns the [`SetId`] of the set, its index in the built [`TriangleSets`].
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: Human-readable name for the set
+    /// - `identifier`: Unique identifier for the set
+    /// - `refs`: Slice of individual triangle indices to include
+    /// - `ranges`: Slice of triangle index ranges (inclusive start, inclusive end)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// // Reference triangles 0, 1, and 2 individually
+    /// sets.add_set("Group1", "g1", &[0, 1, 2], &[]);
+    ///
+    /// // Reference triangles 10-20 (inclusive)
+    /// sets.add_set("Group2", "g2", &[], &[(10, 20)]);
+    ///
+    /// // Mix both approaches
+    /// sets.add_set("Group3", "g3", &[5, 6], &[(10, 15), (20, 25)]);
+    /// ```
+    pub fn add_set(
+        &mut self,
+        name: &str,
+        identifier: &str,
+        refs: &[usize],
+        ranges: &[(usize, usize)],
+    ) -> SetId {
+        let triangle_ref = refs.iter().map(|&index| TriangleRef { index }).collect();
+        let triangle_refrange = ranges
+            .iter()
+            .map(|&(start, end)| TriangleRefRange {
+                startindex: start,
+                endindex: end,
+            })
+            .collect();
+
+        self.sets.push(TriangleSet {
+            name: name.to_owned(),
+            identifier: identifier.to_owned(),
+            triangle_ref,
+            triangle_refrange,
+        });
+        SetId(self.sets.len() - 1)
+    }
+
+    /// Update the triangle references after triangles were removed from the mesh.
+    ///
+    /// `new_indices` maps every old triangle index to its new index,
+    /// or `None` if the triangle was removed.
+    fn remap_triangles(&mut self, new_indices: &[Option<usize>]) {
+        let new_index = |index: usize| new_indices.get(index).copied().unwrap_or(Some(index));
+
+        for set in &mut self.sets {
+            set.triangle_ref = set
+                .triangle_ref
+                .iter()
+                .filter_map(|r| new_index(r.index).map(|index| TriangleRef { index }))
+                .collect();
+
+            let mut ranges: Vec<TriangleRefRange> = vec![];
+            for range in &set.triangle_refrange {
+                let mut current: Option<TriangleRefRange> = None;
+                for index in (range.startindex..=range.endindex).filter_map(new_index) {
+                    match &mut current {
+                        Some(r) if r.endindex + 1 == index => r.endindex = index,
+                        _ => {
+                            ranges.extend(current.take());
+                            current = Some(TriangleRefRange {
+                                startindex: index,
+                                endindex: index,
+                            });
+                        }
+                    }
+                }
+                ranges.extend(current);
+            }
+            set.triangle_refrange = ranges;
+        }
+    }
+
+    fn build(self) -> TriangleSets {
+        TriangleSets {
+            trianglesets: self.sets,
+        }
+    }
+}
+
+/// Builder for constructing beam lattice structures.
+///
+/// Beam lattices define strut-like structures connecting vertices in a mesh.
+/// They are part of the 3MF Beam Lattice extension and are useful for representing
+/// lightweight lattice structures, supports, or truss designs.
+///
+/// The builder allows you to:
+/// - Define beams connecting pairs of vertices
+/// - Add balls (spherical nodes) at vertices
+/// - Organize beams and balls into named sets
+/// - Configure default properties like radius, ball mode, clipping, etc.
+///
+/// The Beam Lattice extension namespace is automatically added when beams are present.
+pub struct BeamLatticeBuilder {
+    minlength: Option<f64>,
+    radius: Option<f64>,
+    ballmode: Option<BallMode>,
+    ballradius: Option<f64>,
+    clippingmode: Option<ClippingMode>,
+    clippingmesh: Option<usize>,
+    representationmesh: Option<usize>,
+    pid: Option<usize>,
+    pindex: Option<usize>,
+    cap: Option<CapMode>,
+    beams: Vec<Beam>,
+    balls: Vec<Ball>,
+    beamsets: Vec<BeamSet>,
+    unit: Unit,
+    /// Index the first generated vertex gets in the mesh.
+    first_vertex: usize,
+    /// Vertices generated for the mesh, see [`BeamLatticeBuilder::generate_grid`].
+    vertices: Vec<Vertex>,
+}
+
+impl BeamLatticeBuilder {
+    fn new(unit: Unit) -> Self {
+        Self {
+            minlength: None,
+            radius: None,
+            ballmode: None,
+            ballradius: None,
+            clippingmode: None,
+            clippingmesh: None,
+            representationmesh: None,
+            pid: None,
+            pindex: None,
+            cap: None,
+            beams: Vec::new(),
+            balls: Vec::new(),
+            beamsets: Vec::new(),
+            unit,
+            first_vertex: 0,
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Set the minimum length for beams (default: 0.0001 mm in the model unit).
+    ///
+    /// Beams shorter than this length may be ignored during processing.
+    pub fn minlength(&mut self, minlength: f64) -> &mut Self {
+        self.minlength = Some(minlength);
+        self
+    }
+
+    /// Set the default radius for all beams (default: 0.0001 mm in the model unit).
+    ///
+    /// Individual beams can override this with their own radius values.
+    pub fn radius(&mut self, radius: f64) -> &mut Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /// Set how balls (nodes) are rendered at beam connections.
+    ///
+    /// See [`BallMode`] for available options.
+    pub fn ballmode(&mut self, mode: BallMode) -> &mut Self {
+        self.ballmode = Some(mode);
+        self
+    }
+
+    /// Set the default radius for all balls.
+    ///
+    /// Individual balls can override this with their own radius values.
+    pub fn ballradius(&mut self, radius: f64) -> &mut Self {
+        self.ballradius = Some(radius);
+        self
+    }
+
+    /// Set how the lattice is clipped by the clipping mesh.
+    ///
+    /// See [`ClippingMode`] for available options.
+    pub fn clippingmode(&mut self, mode: ClippingMode) -> &mut Self {
+        self.clippingmode = Some(mode);
+        self
+    }
+
+    /// Set the mesh object used for clipping the lattice.
+    ///
+    /// The lattice will be clipped to the bounds of this mesh based on the clipping mode.
+    pub fn clippingmesh(&mut self, object_id: ObjectId) -> &mut Self {
+        self.clippingmesh = Some(object_id.0);
+        self
+    }
+
+    /// Set an alternative mesh for visualization.
+    ///
+    /// This mesh can be used as a simplified representation of the lattice.
+    pub fn representationmesh(&mut self, object_id: ObjectId) -> &mut Self {
+        self.representationmesh = Some(object_id.0);
+        self
+    }
+
+    /// Set the property ID for the lattice.
+    pub fn pid(&mut self, pid: usize) -> &mut Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Set the property index for the lattice.
+    pub fn pindex(&mut self, pindex: usize) -> &mut Self {
+        self.pindex = Some(pindex);
+        self
+    }
+
+    /// Set the default cap mode for beam ends.
+    ///
+    /// Individual beams can override this. See [`CapMode`] for available options.
+    pub fn cap(&mut self, cap: CapMode) -> &mut Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// Add a simple beam connecting two vertices.
+    ///
+    /// The beam will use default properties (radius, cap mode, etc.).
+    ///
+    /// # Parameters
+    ///
+    /// - `v1`: Index of the first vertex
+    /// - `v2`: Index of the second vertex
+    pub fn add_beam(&mut self, v1: usize, v2: usize) -> &mut Self {
+        let beam = BeamBuilder::new(v1, v2).build();
+        self.beams.push(beam);
+        self
+    }
+
+    /// Add a beam with custom configuration.
+    ///
+    /// Use this method to configure individual beam properties like radius, cap modes, etc.
+    ///
+    /// # Parameters
+    ///
+    /// - `v1`: Index of the first vertex
+    /// - `v2`: Index of the second vertex
+    /// - `f`: A closure that configures the [`BeamBuilder`]
+    pub fn add_beam_advanced<F>(&mut self, v1: usize, v2: usize, f: F) -> &mut Self
+    where
+        F: FnOnce(BeamBuilder) -> BeamBuilder,
+    {
+        let builder = BeamBuilder::new(v1, v2);
+        let beam = f(builder).build();
+        self.beams.push(beam);
+        self
+    }
+
+    /// Add multiple simple beams from vertex pairs.
+    ///
+    /// # Parameters
+    ///
+    /// - `vertex_pairs`: Slice of `(v1, v2)` vertex index pairs
+    pub fn add_beams(&mut self, vertex_pairs: &[(usize, usize)]) -> &mut Self {
+        for &(v1, v2) in vertex_pairs {
+            self.add_beam(v1, v2);
+        }
+        self
+    }
+
+    /// Add multiple simple beams from undirected edges, skipping duplicates.
+    ///
+    /// Each edge is normalized to `(min, max)` so `(0, 1)` and `(1, 0)` describe the same
+    /// beam. Edges that repeat an earlier edge in the slice or a beam already added to this
+    /// builder are skipped.
+    ///
+    /// # Parameters
+    ///
+    /// - `edges`: Slice of `(v1, v2)` vertex index pairs
+    ///
+    /// # Returns
+    ///
+    /// The number of duplicate edges that were skipped.
+    pub fn add_beams_dedup(&mut self, edges: &[(usize, usize)]) -> usize {
+        let mut seen = self
+            .beams
+            .iter()
+            .map(|beam| (beam.v1.min(beam.v2), beam.v1.max(beam.v2)))
+            .collect::<BTreeSet<_>>();
+
+        let mut skipped = 0;
+        for &(v1, v2) in edges {
+            let edge = (v1.min(v2), v1.max(v2));
+            if seen.insert(edge) {
+                self.add_beam(edge.0, edge.1);
+            } else {
+                skipped += 1;
+            }
+        }
+        skipped
+    }
+
+    /// Add a simple ball (spherical node) at a vertex.
+    ///
+    /// The ball will use default properties (radius, etc.).
+    ///
+    /// # Parameters
+    ///
+    /// - `vindex`: Index of the vertex where the ball is located
+    pub fn add_ball(&mut self, vindex: usize) -> &mut Self {
+        let ball = BallBuilder::new(vindex).build();
+        self.balls.push(ball);
+        self
+    }
+
+    /// Add a ball with custom configuration.
+    ///
+    /// Use this method to configure individual ball properties like radius.
+    ///
+    /// # Parameters
+    ///
+    /// - `vindex`: Index of the vertex where the ball is located
+    /// - `f`: A closure that configures the [`BallBuilder`]
+    pub fn add_ball_advanced<F>(&mut self, vindex: usize, f: F) -> &mut Self
+    where
+        F: FnOnce(BallBuilder) -> BallBuilder,
+    {
+        let builder = BallBuilder::new(vindex);
+        let ball = f(builder).build();
+        self.balls.push(ball);
+        self
+    }
+
+    /// Add multiple simple balls from vertex indices.
+    ///
+    /// # Parameters
+    ///
+    /// - `vindices`: Slice of vertex indices where balls should be placed
+    pub fn add_balls(&mut self, vindices: &[usize]) -> &mut Self {
+        for &vindex in vindices {
+            self.add_ball(vindex);
+        }
+        self
+    }
+
+    /// Add a grid of `nx` by `ny` by `nz` vertices, `spacing` apart along each axis and
+    /// starting at the origin, and connect every vertex to its neighbors along the axes.
+    ///
+    /// The vertices are appended to the mesh once the closure passed to
+    /// [`MeshBuilder::add_beam_lattice`] returns, ordered along x first, then y and z.
+    /// The beams use the default properties (radius, cap mode, etc.).
+    ///
+    /// # Returns
+    ///
+    /// The range of the indices of the added vertices in the mesh.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// obj.add_beam_lattice(|lattice| {
+    ///     // a cube of 8 vertices and 12 beams
+    ///     let vertices = lattice.radius(0.5).generate_grid(2, 2, 2, 10.0);
+    ///     lattice.add_balls(&vertices.collect::<Vec<_>>());
+    /// });
+    /// ```
+    pub fn generate_grid(&mut self, nx: usize, ny: usize, nz: usize, spacing: f64) -> Range<usize> {
+        let start = self.first_vertex + self.vertices.len();
+        let index = |x: usize, y: usize, z: usize| start + x + nx * (y + ny * z);
+
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    self.vertices.push(Vertex {
+                        x: x as f64 * spacing,
+                        y: y as f64 * spacing,
+                        z: z as f64 * spacing,
+                    });
+                    let v = index(x, y, z);
+                    if x > 0 {
+                        self.add_beam(index(x - 1, y, z), v);
+                    }
+                    if y > 0 {
+                        self.add_beam(index(x, y - 1, z), v);
+                    }
+                    if z > 0 {
+                        self.add_beam(index(x, y, z - 1), v);
+                    }
+                }
+            }
+        }
+
+        start..start + nx * ny * nz
+    }
+
+    /// Add a beam set to organize beams and balls into named groups.
+    ///
+    /// Returns the [`SetId`] of the set, its index in the built [`BeamSets`].
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: A closure that configures the [`BeamSetBuilder`]
+    pub fn add_beamset<F>(&mut self, f: F) -> SetId
+    where
+        F: FnOnce(&mut BeamSetBuilder),
+    {
+        let mut builder = BeamSetBuilder::new();
+        f(&mut builder);
+        self.beamsets.push(builder.build());
+        SetId(self.beamsets.len() - 1)
+    }
+
+    fn build(self) -> BeamLattice {
+        let beams = Beams { beam: self.beams };
+
+        let balls = if self.balls.is_empty() {
+            None
+        } else {
+            Some(Balls { ball: self.balls })
+        };
+
+        let beamsets = if self.beamsets.is_empty() {
+            None
+        } else {
+            Some(BeamSets {
+                beamset: self.beamsets,
+            })
+        };
+
+        let default_length = DEFAULT_BEAM_LATTICE_LENGTH_MM / self.unit.millimeters();
+        BeamLattice {
+            minlength: self.minlength.unwrap_or(default_length),
+            radius: self.radius.unwrap_or(default_length),
+            ballmode: self.ballmode,
+            ballradius: self.ballradius,
+            clippingmode: self.clippingmode,
+            clippingmesh: self.clippingmesh,
+            representationmesh: self.representationmesh,
+            pid: self.pid,
+            pindex: self.pindex,
+            cap: self.cap,
+            beams,
+            balls,
+            beamsets,
+        }
+    }
+}
+
+/// Builder for individual beams in a beam lattice
+pub struct BeamBuilder {
+    v1: usize,
+    v2: usize,
+    r1: Option<f64>,
+    r2: Option<f64>,
+    p1: Option<usize>,
+    p2: Option<usize>,
+    pid: Option<usize>,
+    cap1: Option<CapMode>,
+    cap2: Option<CapMode>,
+}
+
+impl BeamBuilder {
+    /// Create a new beam with the specified vertex indices
+    pub fn new(v1: usize, v2: usize) -> Self {
+        Self {
+            v1,
+            v2,
+            r1: None,
+            r2: None,
+            p1: None,
+            p2: None,
+            pid: None,
+            cap1: None,
+            cap2: None,
+        }
+    }
+
+    /// Set the radius at the first vertex
+    pub fn radius_1(mut self, radius: f64) -> Self {
+        self.r1 = Some(radius);
+        self
+    }
+
+    /// Set the radius at the second vertex
+    pub fn radius_2(mut self, radius: f64) -> Self {
+        self.r2 = Some(radius);
+        self
+    }
+
+    /// Set the property index for the first vertex
+    pub fn pindex_1(mut self, pindex: usize) -> Self {
+        self.p1 = Some(pindex);
+        self
+    }
+
+    /// Set the property index for the second vertex
+    pub fn pindex_2(mut self, pindex: usize) -> Self {
+        self.p2 = Some(pindex);
+        self
+    }
+
+    /// Set the property ID for the beam
+    pub fn pid(mut self, pid: usize) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Set the cap mode for the first end of the beam
+    pub fn cap_1(mut self, cap: CapMode) -> Self {
+        self.cap1 = Some(cap);
+        self
+    }
+
+    /// Set the cap mode for the second end of the beam
+    pub fn cap_2(mut self, cap: CapMode) -> Self {
+        self.cap2 = Some(cap);
+        self
+    }
+
+    fn build(self) -> Beam {
+        Beam {
+            v1: self.v1,
+            v2: self.v2,
+            r1: self.r1,
+            r2: self.r2,
+            p1: self.p1,
+            p2: self.p2,
+            pid: self.pid,
+            cap1: self.cap1,
+            cap2: self.cap2,
+        }
+    }
+}
+
+/// Builder for balls in a beam lattice
+pub struct BallBuilder {
+    vindex: usize,
+    r: Option<f64>,
+    p: Option<usize>,
+    pid: Option<usize>,
+}
+
+impl BallBuilder {
+    /// Create a new ball at the specified vertex index
+    pub fn new(vindex: usize) -> Self {
+        Self {
+            vindex,
+            r: None,
+            p: None,
+            pid: None,
+        }
+    }
+
+    /// Set the radius of the ball
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.r = Some(radius);
+        self
+    }
+
+    /// Set the property index for the ball
+    pub fn pindex(mut self, pindex: usize) -> Self {
+        self.p = Some(pindex);
+        self
+    }
+
+    /// Set the property ID for the ball
+    pub fn pid(mut self, pid: usize) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    fn build(self) -> Ball {
+        Ball {
+            vindex: self.vindex,
+            r: self.r,
+            p: self.p,
+            pid: self.pid,
+        }
+    }
+}
+
+/// Builder for beam sets in a beam lattice
+pub struct BeamSetBuilder {
+    name: Option<String>,
+    identifier: Option<String>,
+    beam_refs: Vec<usize>,
+    ball_refs: Vec<usize>,
+}
+
+impl BeamSetBuilder {
+    fn new() -> Self {
+        Self {
+            name: None,
+            identifier: None,
+            beam_refs: Vec::new(),
+            ball_refs: Vec::new(),
+        }
+    }
+
+    /// Set the name of the beam set
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Set the identifier of the beam set
+    pub fn identifier(&mut self, id: &str) -> &mut Self {
+        self.identifier = Some(id.to_owned());
+        self
+    }
+
+    /// Add a reference to a beam by index
+    pub fn add_beam_ref(&mut self, index: usize) -> &mut Self {
+        self.beam_refs.push(index);
+        self
+    }
+
+    /// Add multiple beam references
+    pub fn add_beam_refs(&mut self, indices: &[usize]) -> &mut Self {
+        self.beam_refs.extend_from_slice(indices);
+        self
+    }
+
+    /// Add a reference to a ball by index
+    pub fn add_ball_ref(&mut self, index: usize) -> &mut Self {
+        self.ball_refs.push(index);
+        self
+    }
+
+    /// Add multiple ball references
+    pub fn add_ball_refs(&mut self, indices: &[usize]) -> &mut Self {
+        self.ball_refs.extend_from_slice(indices);
+        self
+    }
+
+    fn build(self) -> BeamSet {
+        BeamSet {
+            name: self.name,
+            identifier: self.identifier,
+            refs: self
+                .beam_refs
+                .into_iter()
+                .map(|index| BeamRef { index })
+                .collect(),
+            ballref: self
+                .ball_refs
+                .into_iter()
+                .map(|index| BallRef { index })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::beamlattice::LatticeElementKind;
+
+    // ========== BeamBuilder Tests ==========
+
+    #[test]
+    fn test_beam_builder_basic() {
+        let beam = BeamBuilder::new(0, 1).build();
+
+        assert_eq!(beam.v1, 0);
+        assert_eq!(beam.v2, 1);
+        assert_eq!(beam.r1, None);
+        assert_eq!(beam.r2, None);
+        assert_eq!(beam.p1, None);
+        assert_eq!(beam.p2, None);
+        assert_eq!(beam.pid, None);
+        assert_eq!(beam.cap1, None);
+        assert_eq!(beam.cap2, None);
+    }
+
+    #[test]
+    fn test_beam_builder_with_all_options() {
+        let beam = BeamBuilder::new(0, 1)
+            .radius_1(1.5)
+            .radius_2(2.0)
+            .pindex_1(10)
+            .pindex_2(20)
+            .pid(5)
+            .cap_1(CapMode::Hemisphere)
+            .cap_2(CapMode::Butt)
+            .build();
+
+        assert_eq!(beam.v1, 0);
+        assert_eq!(beam.v2, 1);
+        assert_eq!(beam.r1, Some(1.5));
+        assert_eq!(beam.r2, Some(2.0));
+        assert_eq!(beam.p1, Some(10));
+        assert_eq!(beam.p2, Some(20));
+        assert_eq!(beam.pid, Some(5));
+        assert_eq!(beam.cap1, Some(CapMode::Hemisphere));
+        assert_eq!(beam.cap2, Some(CapMode::Butt));
+    }
+
+    // ========== BallBuilder Tests ==========
+
+    #[test]
+    fn test_ball_builder_basic() {
+        let ball = BallBuilder::new(5).build();
+
+        assert_eq!(ball.vindex, 5);
+        assert_eq!(ball.r, None);
+        assert_eq!(ball.p, None);
+        assert_eq!(ball.pid, None);
+    }
+
+    #[test]
+    fn test_ball_builder_with_options() {
+        let ball = BallBuilder::new(5).radius(0.75).pindex(15).pid(3).build();
+
+        assert_eq!(ball.vindex, 5);
+        assert_eq!(ball.r, Some(0.75));
+        assert_eq!(ball.p, Some(15));
+        assert_eq!(ball.pid, Some(3));
+    }
+
+    // ========== BeamSetBuilder Tests ==========
+
+    #[test]
+    fn test_beamset_builder() {
+        let mut builder = BeamSetBuilder::new();
+        builder
+            .name("Test Set")
+            .identifier("test-set-001")
+            .add_beam_refs(&[0, 1, 2, 3])
+            .add_ball_ref(0)
+            .add_ball_ref(1);
+
+        let beamset = builder.build();
+
+        assert_eq!(beamset.name, Some("Test Set".to_owned()));
+        assert_eq!(beamset.identifier, Some("test-set-001".to_owned()));
+        assert_eq!(beamset.refs.len(), 4);
+        assert_eq!(beamset.refs[0].index, 0);
+        assert_eq!(beamset.refs[3].index, 3);
+        assert_eq!(beamset.ballref.len(), 2);
+        assert_eq!(beamset.ballref[0].index, 0);
+        assert_eq!(beamset.ballref[1].index, 1);
+    }
+
+    // ========== BeamLatticeBuilder Tests ==========
+
+    #[test]
+    fn test_beam_lattice_builder_minimal() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder.add_beam(0, 1);
+
+        let beamlattice = builder.build();
+
+        // Should use default values
+        assert_eq!(beamlattice.minlength, 0.0001);
+        assert_eq!(beamlattice.radius, 0.0001);
+        assert_eq!(beamlattice.beams.beam.len(), 1);
+        assert_eq!(beamlattice.beams.beam[0].v1, 0);
+        assert_eq!(beamlattice.beams.beam[0].v2, 1);
+        assert_eq!(beamlattice.balls, None);
+        assert_eq!(beamlattice.beamsets, None);
+    }
+
+    #[test]
+    fn test_beam_lattice_default_values() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        // Don't set minlength or radius
+        builder.add_beam(0, 1);
+
+        let beamlattice = builder.build();
+
+        assert_eq!(beamlattice.minlength, 0.0001);
+        assert_eq!(beamlattice.radius, 0.0001);
+    }
+
+    #[test]
+    fn test_beam_lattice_default_values_in_model_unit() {
+        let mut builder = MeshBuilder::with_unit(Unit::Inch);
+        builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]])
+            .add_beam_lattice(|lattice| {
+                lattice.add_beam(0, 1);
+            });
+
+        let beamlattice = builder.build().unwrap().beamlattice.unwrap();
+
+        assert_eq!(beamlattice.minlength, 0.0001 / 25.4);
+        assert_eq!(beamlattice.radius, 0.0001 / 25.4);
+    }
+
+    #[test]
+    fn test_beam_lattice_vertex_index_out_of_range() {
+        let mut builder = MeshBuilder::new();
+        builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]])
+            .add_beam_lattice(|lattice| {
+                lattice.add_beam(0, 1).add_beam(1, 2);
+            });
+        assert_eq!(
+            builder.build(),
+            Err(MeshError::BeamLattice(
+                BeamLatticeError::VertexIndexOutOfRange {
+                    kind: LatticeElementKind::Beam,
+                    index: 1,
+                }
+            ))
+        );
+
+        let mut builder = MeshBuilder::new();
+        builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]])
+            .add_beam_lattice(|lattice| {
+                lattice.add_beam(0, 1).add_balls(&[0, 1, 2]);
+            });
+        assert_eq!(
+            builder.build(),
+            Err(MeshError::BeamLattice(
+                BeamLatticeError::VertexIndexOutOfRange {
+                    kind: LatticeElementKind::Ball,
+                    index: 2,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_beam_lattice_builder_with_custom_values() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .minlength(0.001)
+            .radius(1.0)
+            .add_beam(0, 1)
+            .add_beam(1, 2);
+
+        let beamlattice = builder.build();
+
+        assert_eq!(beamlattice.minlength, 0.001);
+        assert_eq!(beamlattice.radius, 1.0);
+        assert_eq!(beamlattice.beams.beam.len(), 2);
+    }
+
+    #[test]
+    fn test_beam_lattice_builder_add_beams_dedup() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder.add_beam(2, 1);
+
+        let skipped = builder.add_beams_dedup(&[(0, 1), (1, 0), (1, 2), (2, 3), (3, 2), (0, 1)]);
+
+        let beamlattice = builder.build();
+
+        assert_eq!(skipped, 4);
+        let beams = beamlattice
+            .beams
+            .beam
+            .iter()
+            .map(|b| (b.v1, b.v2))
+            .collect::<Vec<_>>();
+        assert_eq!(beams, vec![(2, 1), (0, 1), (2, 3)]);
+    }
+
+    #[test]
+    fn test_beam_lattice_builder_with_balls() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .minlength(0.001)
+            .radius(1.0)
+            .ballmode(BallMode::Mixed)
+            .ballradius(0.5)
+            .add_beam(0, 1)
+            .add_ball(0)
+            .add_ball_advanced(1, |b| b.radius(0.75));
+
+        let beamlattice = builder.build();
+
+        assert_eq!(beamlattice.ballmode, Some(BallMode::Mixed));
+        assert_eq!(beamlattice.ballradius, Some(0.5));
+        assert!(beamlattice.balls.is_some());
+
+        let balls = beamlattice.balls.as_ref().unwrap();
+        assert_eq!(balls.ball.len(), 2);
+        assert_eq!(balls.ball[0].vindex, 0);
+        assert_eq!(balls.ball[0].r, None); // Uses default
+        assert_eq!(balls.ball[1].vindex, 1);
+        assert_eq!(balls.ball[1].r, Some(0.75)); // Custom radius
+    }
+
+    #[test]
+    fn test_beam_lattice_builder_with_all_features() {
+        let clip_mesh_id = ObjectId(10);
+        let repr_mesh_id = ObjectId(20);
+
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .minlength(0.002)
+            .radius(2.0)
+            .ballmode(BallMode::All)
+            .ballradius(1.0)
+            .clippingmode(ClippingMode::Inside)
+            .clippingmesh(clip_mesh_id)
+            .representationmesh(repr_mesh_id)
+            .pid(5)
+            .pindex(10)
+            .cap(CapMode::Sphere)
+            .add_beam(0, 1);
+
+        let beamlattice = builder.build();
+
+        assert_eq!(beamlattice.minlength, 0.002);
+        assert_eq!(beamlattice.radius, 2.0);
+        assert_eq!(beamlattice.ballmode, Some(BallMode::All));
+        assert_eq!(beamlattice.ballradius, Some(1.0));
+        assert_eq!(beamlattice.clippingmode, Some(ClippingMode::Inside));
+        assert_eq!(beamlattice.clippingmesh, Some(10));
+        assert_eq!(beamlattice.representationmesh, Some(20));
+        assert_eq!(beamlattice.pid, Some(5));
+        assert_eq!(beamlattice.pindex, Some(10));
+        assert_eq!(beamlattice.cap, Some(CapMode::Sphere));
+    }
+
+    #[test]
+    fn test_beam_lattice_add_beams_bulk() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .radius(1.0)
+            .add_beams(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        let beamlattice = builder.build();
+
+        assert_eq!(beamlattice.beams.beam.len(), 4);
+        assert_eq!(beamlattice.beams.beam[0].v1, 0);
+        assert_eq!(beamlattice.beams.beam[0].v2, 1);
+        assert_eq!(beamlattice.beams.beam[3].v1, 3);
+        assert_eq!(beamlattice.beams.beam[3].v2, 0);
+    }
+
+    #[test]
+    fn test_beam_lattice_add_balls_bulk() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .radius(1.0)
+            .ballmode(BallMode::Mixed)
+            .ballradius(0.5)
+            .add_beam(0, 1)
+            .add_balls(&[0, 1, 2, 3]);
+
+        let beamlattice = builder.build();
+
+        assert!(beamlattice.balls.is_some());
+        let balls = beamlattice.balls.as_ref().unwrap();
+        assert_eq!(balls.ball.len(), 4);
+        assert_eq!(balls.ball[0].vindex, 0);
+        assert_eq!(balls.ball[3].vindex, 3);
+    }
+
+    #[test]
+    fn test_beam_lattice_with_multiple_beamsets() {
+        let mut builder = BeamLatticeBuilder::new(Unit::Millimeter);
+        builder
+            .radius(1.0)
+            .add_beams(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let bottom = builder.add_beamset(|bs| {
+            bs.name("Bottom")
+                .identifier("bottom-001")
+                .add_beam_refs(&[0, 1]);
+        });
+        let top = builder.add_beamset(|bs| {
+            bs.name("Top").identifier("top-001").add_beam_refs(&[2, 3]);
+        });
+
+        let beamlattice = builder.build();
+
+        assert!(beamlattice.beamsets.is_some());
+        let beamsets = beamlattice.beamsets.as_ref().unwrap();
+        assert_eq!(beamsets.beamset.len(), 2);
+        let bottom = &beamsets.beamset[usize::from(bottom)];
+        assert_eq!(bottom.name, Some("Bottom".to_owned()));
+        assert_eq!(bottom.refs.len(), 2);
+        let top = &beamsets.beamset[usize::from(top)];
+        assert_eq!(top.name, Some("Top".to_owned()));
+        assert_eq!(top.refs.len(), 2);
+    }
+
+    #[test]
+    fn test_triangle_set_ids_match_set_order() {
+        let mut mesh_builder = MeshBuilder::new();
+        let mut ids = vec![];
+        mesh_builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+            .add_triangle(&[0, 1, 2])
+            .add_triangle_sets(|sets| {
+                ids.push(sets.add_set("First", "first", &[0], &[]));
+                ids.push(sets.add_set("Second", "second", &[], &[(0, 0)]));
+            });
+
+        let mesh = mesh_builder.build().unwrap();
+
+        assert_eq!(ids, vec![SetId(0), SetId(1)]);
+        let sets = mesh.trianglesets.unwrap().trianglesets;
+        assert_eq!(sets[usize::from(ids[0])].identifier, "first");
+        assert_eq!(sets[usize::from(ids[1])].identifier, "second");
+    }
+
+    #[test]
+    fn test_mesh_with_beam_lattice() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [10.0, 10.0, 0.0]])
+            .add_beam_lattice(|bl| {
+                bl.minlength(0.001)
+                    .radius(1.0)
+                    .add_beam(0, 1)
+                    .add_beam(1, 2);
+            });
+
+        let mesh = mesh_builder.build().unwrap();
+
+        assert_eq!(mesh.vertices.vertex.len(), 3);
+        assert!(mesh.beamlattice.is_some());
+
+        let bl = mesh.beamlattice.as_ref().unwrap();
+        assert_eq!(bl.minlength, 0.001);
+        assert_eq!(bl.radius, 1.0);
+        assert_eq!(bl.beams.beam.len(), 2);
+    }
+
+    #[test]
+    fn test_mesh_with_generated_grid() {
+        let mut grid = 0..0;
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[[-1.0, -1.0, -1.0]])
+            .add_beam_lattice(|bl| {
+                grid = bl.radius(0.5).generate_grid(2, 2, 2, 10.0);
+            });
+        let mesh = mesh_builder.build().unwrap();
+
+        assert_eq!(grid, 1..9);
+        assert_eq!(mesh.vertices.vertex.len(), 9);
+        let last = &mesh.vertices.vertex[8];
+        assert_eq!([last.x, last.y, last.z], [10.0, 10.0, 10.0]);
+
+        let bl = mesh.beamlattice.as_ref().unwrap();
+        assert_eq!(bl.beams.beam.len(), 12);
+        for beam in &bl.beams.beam {
+            assert!(grid.contains(&beam.v1) && grid.contains(&beam.v2));
+            assert_eq!(beam.r1, None);
+            let (a, b) = (
+                &mesh.vertices.vertex[beam.v1],
+                &mesh.vertices.vertex[beam.v2],
+            );
+            let length = (b.x - a.x).abs() + (b.y - a.y).abs() + (b.z - a.z).abs();
+            assert_eq!(length, 10.0);
+        }
+
+        // a second grid continues after the first one
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder.add_beam_lattice(|bl| {
+            assert_eq!(bl.generate_grid(3, 1, 1, 1.0), 0..3);
+            assert_eq!(bl.generate_grid(2, 2, 1, 1.0), 3..7);
+            assert_eq!(bl.generate_grid(0, 2, 2, 1.0), 7..7);
+        });
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.vertices.vertex.len(), 7);
+        assert_eq!(mesh.beamlattice.unwrap().beams.beam.len(), 2 + 4);
+    }
+
+    #[test]
+    fn test_mesh_dedup_vertices_of_cube() {
+        let corners = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+        ];
+        let faces: [[usize; 3]; 12] = [
+            [0, 2, 1],
+            [1, 2, 3],
+            [4, 5, 6],
+            [5, 7, 6],
+            [0, 1, 4],
+            [1, 5, 4],
+            [2, 6, 3],
+            [3, 6, 7],
+            [0, 4, 2],
+            [2, 4, 6],
+            [1, 3, 5],
+            [3, 7, 5],
+        ];
+
+        // every triangle has its own vertices, slightly off around the cell boundaries
+        let mut mesh_builder = MeshBuilder::new();
+        for (i, face) in faces.iter().enumerate() {
+            for (j, &corner) in face.iter().enumerate() {
+                let noise = if (i + j) % 2 == 0 { 1e-9 } else { -1e-9 };
+                mesh_builder.add_vertex(&corners[corner].map(|c| c + noise));
+            }
+            mesh_builder.add_triangle(&[3 * i, 3 * i + 1, 3 * i + 2]);
+        }
+        assert_eq!(mesh_builder.vertices.len(), 36);
+
+        assert_eq!(mesh_builder.dedup_vertices(1e-6), 0);
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.vertices.vertex.len(), 8);
+        assert_eq!(mesh.triangles.triangle.len(), 12);
+
+        // the triangles reference the same corners in the same order
+        let position = |index: usize| {
+            let v = &mesh.vertices.vertex[index];
+            [v.x, v.y, v.z].map(|c| (c * 1e3).round() / 1e3)
+        };
+        for (triangle, face) in mesh.triangles.triangle.iter().zip(&faces) {
+            let positions = [triangle.v1, triangle.v2, triangle.v3].map(position);
+            assert_eq!(positions, face.map(|corner| corners[corner]));
+        }
+    }
+
+    #[test]
+    fn test_mesh_dedup_vertices_drops_collapsed_triangles() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .allow_degenerate_triangles(true)
+            .add_vertices(&[
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1e-3, 0.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ])
+            .add_triangles(&[[0, 1, 2], [3, 1, 0], [1, 1, 2], [3, 4, 2]])
+            .add_triangle_sets(|sets| {
+                sets.add_set("All", "all", &[], &[(0, 3)]);
+            })
+            .add_beam_lattice(|bl| {
+                bl.radius(0.1).add_beam(3, 4).add_ball(3);
+            });
+
+        // too far apart to be merged
+        assert_eq!(mesh_builder.dedup_vertices(1e-4), 0);
+        assert_eq!(mesh_builder.vertices.len(), 5);
+
+        // merging vertex 3 into vertex 0 collapses the second triangle, the already
+        // degenerate third one is kept
+        assert_eq!(mesh_builder.dedup_vertices(1e-2), 1);
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.vertices.vertex.len(), 4);
+        let triangles = mesh
+            .triangles
+            .triangle
+            .iter()
+            .map(|t| [t.v1, t.v2, t.v3])
+            .collect::<Vec<_>>();
+        assert_eq!(triangles, vec![[0, 1, 2], [1, 1, 2], [0, 3, 2]]);
+
+        let set = &mesh.trianglesets.as_ref().unwrap().trianglesets[0];
+        let ranges = set
+            .triangle_refrange
+            .iter()
+            .map(|r| (r.startindex, r.endindex))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(0, 2)]);
+
+        let bl = mesh.beamlattice.as_ref().unwrap();
+        assert_eq!((bl.beams.beam[0].v1, bl.beams.beam[0].v2), (0, 3));
+        assert_eq!(bl.balls.as_ref().unwrap().ball[0].vindex, 0);
+    }
+
+    #[test]
+    fn test_mesh_orient_outward() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder.add_vertices(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+        ]);
+        // a unit cube with all triangles facing inwards
+        mesh_builder.add_triangle_with_properties(&[0, 1, 2], PropertyGroupId(7), &[3, 4, 5]);
+        mesh_builder.add_triangles(&[
+            [1, 3, 2],
+            [4, 6, 5],
+            [5, 6, 7],
+            [0, 4, 1],
+            [1, 4, 5],
+            [2, 3, 6],
+            [3, 7, 6],
+            [0, 2, 4],
+            [2, 6, 4],
+            [1, 5, 3],
+            [3, 5, 7],
+        ]);
+
+        assert!(mesh_builder.orient_outward());
+        assert!(!mesh_builder.orient_outward());
+
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.signed_volume(), 1.0);
+        let t = &mesh.triangles.triangle[0];
+        assert_eq!([t.v1, t.v2, t.v3], [0, 2, 1]);
+        // the properties stay with their vertices
+        assert_eq!([t.p1, t.p2, t.p3], [Some(3), Some(5), Some(4)]);
+    }
+
+    #[test]
+    fn test_mesh_triangle_with_properties() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+            .add_triangle_with_properties(&[0, 1, 2], PropertyGroupId(4), &[1, 0, 2]);
+
+        let mesh = mesh_builder.build().unwrap();
+        let t = &mesh.triangles.triangle[0];
+        assert_eq!(t.pid, Some(4));
+        assert_eq!([t.p1, t.p2, t.p3], [Some(1), Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn test_mesh_triangle_index_out_of_bounds() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+            .add_triangles(&[[0, 1, 2], [0, 1, 99]]);
+
+        assert_eq!(
+            mesh_builder.build(),
+            Err(MeshError::TriangleIndexOutOfBounds {
+                triangle: 1,
+                index: 99
+            })
+        );
+    }
+
+    #[test]
+    fn test_mesh_degenerate_triangle() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+            .add_triangles(&[[0, 1, 2], [2, 1, 2]]);
+
+        assert_eq!(
+            mesh_builder.build(),
+            Err(MeshError::DegenerateTriangleIndices { triangle: 1 })
+        );
+    }
+
+    #[test]
+    fn test_mesh_valid_geometry() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertices(&[
+                [0.0, 0.0, 0.0],
+                [10.0, 0.0, 0.0],
+                [0.0, 10.0, 0.0],
+                [0.0, 0.0, 10.0],
+            ])
+            .add_triangles(&[[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]]);
+
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.triangles.triangle.len(), 4);
+    }
+
+    #[test]
+    fn test_mesh_skip_validation() {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .skip_validation(true)
+            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]])
+            .add_triangles(&[[0, 1, 99], [1, 1, 2]])
+            .add_beam_lattice(|bl| {
+                bl.add_beam(0, 3);
+            });
+
+        let mesh = mesh_builder.build().unwrap();
+        assert_eq!(mesh.triangles.triangle[0].v3, 99);
+        assert_eq!(mesh.beamlattice.unwrap().beams.beam[0].v2, 3);
+    }
+
+    #[test]
+    fn test_mesh_set_prebuilt_vertices_and_triangles() {
+        let mut vertices = Vec::with_capacity(16);
+        vertices.extend(
+            [[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]].map(|[x, y, z]| Vertex {
+                x,
+                y,
+                z,
+            }),
+        );
+        let mut triangles = Vec::with_capacity(8);
+        triangles.push(Triangle {
+            v1: 0,
+            v2: 1,
+            v3: 2,
+            p1: Some(1),
+            p2: None,
+            p3: None,
+            pid: Some(5),
+        });
+        let vertices_ptr = vertices.as_ptr();
+        let triangles_ptr = triangles.as_ptr();
+
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder
+            .add_vertex(&[1.0, 1.0, 1.0])
+            .set_vertices(vertices)
+            .set_triangles(triangles);
+        let mesh = mesh_builder.build().unwrap();
+
+        // the buffers are moved into the mesh without reallocation
+        let vertex = &mesh.vertices.vertex;
+        assert_eq!(vertex.len(), 3);
+        assert_eq!(vertex.capacity(), 16);
+        assert_eq!(vertex.as_ptr(), vertices_ptr);
+        let triangle = &mesh.triangles.triangle;
+        assert_eq!(triangle.capacity(), 8);
+        assert_eq!(triangle.as_ptr(), triangles_ptr);
+        assert_eq!(triangle[0].pid, Some(5));
+    }
+}
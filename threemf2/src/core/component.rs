@@ -7,6 +7,8 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{string::String, vec::Vec};
+
 use crate::{
     core::transform::Transform,
     threemf_namespaces::{CORE_NS, PROD_NS},
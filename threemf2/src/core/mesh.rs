@@ -7,6 +7,8 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
 use crate::core::beamlattice::BeamLattice;
 use crate::core::triangle_set::TriangleSets;
 use crate::threemf_namespaces::BEAM_LATTICE_NS;
@@ -54,6 +56,265 @@ pub struct Mesh {
     pub beamlattice: Option<BeamLattice>,
 }
 
+impl Mesh {
+    /// Returns true if the mesh has no vertices, no triangles and no beam lattice.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.vertex.is_empty()
+            && self.triangles.triangle.is_empty()
+            && self.beamlattice.is_none()
+    }
+
+    /// Returns the normal of the triangle, following the right-hand rule on `v1`, `v2`, `v3`.
+    ///
+    /// The normal is not normalized, its length is twice the area of the triangle.
+    /// Returns `None` if the triangle references a vertex outside of the mesh.
+    pub fn triangle_normal(&self, triangle: &Triangle) -> Option<[f64; 3]> {
+        let vertex = |index: usize| self.vertices.vertex.get(index).map(|v| [v.x, v.y, v.z]);
+        let (a, b, c) = (
+            vertex(triangle.v1)?,
+            vertex(triangle.v2)?,
+            vertex(triangle.v3)?,
+        );
+        Some(face_normal(a, b, c))
+    }
+
+    /// Returns the minimum and maximum corner of the axis aligned box enclosing all vertices,
+    /// or `None` if the mesh has no vertices.
+    pub fn bounding_box(&self) -> Option<([f64; 3], [f64; 3])> {
+        let mut vertices = self.vertices.vertex.iter().map(|v| [v.x, v.y, v.z]);
+        let first = vertices.next()?;
+
+        Some(vertices.fold((first, first), |(mut min, mut max), v| {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(v[axis]);
+                max[axis] = max[axis].max(v[axis]);
+            }
+            (min, max)
+        }))
+    }
+
+    /// Returns the volume enclosed by the triangles, positive if their normals point outwards
+    /// and negative if they point inwards.
+    ///
+    /// The volume is only meaningful for closed meshes. Triangles referencing a vertex outside
+    /// of the mesh are skipped.
+    pub fn signed_volume(&self) -> f64 {
+        signed_volume(&self.vertices.vertex, &self.triangles.triangle)
+    }
+
+    /// Returns one unit normal per vertex, accumulated from the area-weighted normals of
+    /// the triangles referencing it.
+    ///
+    /// Vertices not referenced by any triangle get a zero vector.
+    /// Only available with the `std` feature, normalizing requires the square root of `std`.
+    #[cfg(feature = "std")]
+    pub fn vertex_normals(&self) -> Vec<[f64; 3]> {
+        let mut normals = vec![[0.0; 3]; self.vertices.vertex.len()];
+
+        for triangle in &self.triangles.triangle {
+            let Some(normal) = self.triangle_normal(triangle) else {
+                continue;
+            };
+            for index in [triangle.v1, triangle.v2, triangle.v3] {
+                for (acc, n) in normals[index].iter_mut().zip(normal) {
+                    *acc += n;
+                }
+            }
+        }
+
+        for normal in &mut normals {
+            let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+            if length > 0.0 {
+                normal.iter_mut().for_each(|n| *n /= length);
+            }
+        }
+
+        normals
+    }
+
+    /// Groups the triangles into triangle strips and returns the vertex indices of each strip.
+    ///
+    /// Triangle `i` of a strip `s` is `(s[i], s[i + 1], s[i + 2])` for even `i` and
+    /// `(s[i + 1], s[i], s[i + 2])` for odd `i`, which keeps the winding of the triangles.
+    /// Every triangle is part of exactly one strip, triangles without a suitable neighbour end
+    /// up in a strip of their own.
+    ///
+    /// The strips are built greedily: starting from the first unused triangle, each strip is
+    /// extended with an unused triangle sharing the last edge as long as there is one. The
+    /// result is not optimal, but a strip of `n` triangles takes `n + 2` indices instead of
+    /// `3 * n`, so for regular meshes like grids the index count drops to roughly a third,
+    /// and for irregular meshes typically to about half of the plain triangle list.
+    pub fn to_triangle_strips(&self) -> Vec<Vec<usize>> {
+        let triangles = &self.triangles.triangle;
+
+        // triangles by their directed edges, following the winding of the triangle
+        let mut edges: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        for (index, t) in triangles.iter().enumerate() {
+            for edge in [(t.v1, t.v2), (t.v2, t.v3), (t.v3, t.v1)] {
+                edges.entry(edge).or_default().push(index);
+            }
+        }
+
+        let mut used = vec![false; triangles.len()];
+        // marks the triangles taken by the strip currently being tried
+        let mut attempt = vec![usize::MAX; triangles.len()];
+        let mut strips = Vec::new();
+
+        for start in 0..triangles.len() {
+            if used[start] {
+                continue;
+            }
+
+            let t = &triangles[start];
+            let mut best: Option<(Vec<usize>, Vec<usize>)> = None;
+            for (rotation, first) in [[t.v1, t.v2, t.v3], [t.v2, t.v3, t.v1], [t.v3, t.v1, t.v2]]
+                .into_iter()
+                .enumerate()
+            {
+                let attempt_id = start * 3 + rotation;
+                attempt[start] = attempt_id;
+
+                let mut strip = first.to_vec();
+                let mut members = vec![start];
+                loop {
+                    let (p, q) = (strip[strip.len() - 2], strip[strip.len() - 1]);
+                    // odd triangles of a strip are reversed, so the edge is looked up backwards
+                    let edge = if (strip.len() - 2) % 2 == 0 {
+                        (p, q)
+                    } else {
+                        (q, p)
+                    };
+                    let next = edges.get(&edge).and_then(|candidates| {
+                        candidates
+                            .iter()
+                            .copied()
+                            .find(|&c| !used[c] && attempt[c] != attempt_id)
+                    });
+                    let Some(next) = next else {
+                        break;
+                    };
+
+                    let n = &triangles[next];
+                    let third = if (n.v1, n.v2) == edge {
+                        n.v3
+                    } else if (n.v2, n.v3) == edge {
+                        n.v1
+                    } else {
+                        n.v2
+                    };
+                    attempt[next] = attempt_id;
+                    members.push(next);
+                    strip.push(third);
+                }
+
+                if best.as_ref().is_none_or(|(_, m)| members.len() > m.len()) {
+                    best = Some((strip, members));
+                }
+            }
+
+            if let Some((strip, members)) = best {
+                for member in members {
+                    used[member] = true;
+                }
+                strips.push(strip);
+            }
+        }
+
+        strips
+    }
+
+    /// Returns the holes of the mesh as loops of vertex indices.
+    ///
+    /// Boundary edges are the edges belonging to exactly one triangle, the edges of
+    /// degenerate triangles with a repeated vertex are ignored. Connected boundary edges are
+    /// stitched into loops following the winding of the first triangle of each loop, the
+    /// first vertex of a loop is not repeated at its end. At vertices shared by several holes
+    /// the loops may be split differently than expected, but every boundary edge is part of
+    /// exactly one loop. A closed mesh returns no loops.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let triangle_edges = self
+            .triangles
+            .triangle
+            .iter()
+            .flat_map(|t| [(t.v1, t.v2), (t.v2, t.v3), (t.v3, t.v1)])
+            .filter(|(a, b)| a != b);
+
+        let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (a, b) in triangle_edges.clone() {
+            *counts.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+        let boundary = triangle_edges
+            .filter(|&(a, b)| counts[&(a.min(b), a.max(b))] == 1)
+            .collect::<Vec<_>>();
+
+        // boundary edges by the vertices they connect
+        let mut incident: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (index, &(a, b)) in boundary.iter().enumerate() {
+            incident.entry(a).or_default().push(index);
+            incident.entry(b).or_default().push(index);
+        }
+
+        let mut used = vec![false; boundary.len()];
+        let mut loops = Vec::new();
+        for start in 0..boundary.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+
+            let (first, mut current) = boundary[start];
+            let mut boundary_loop = vec![first];
+            while current != first {
+                boundary_loop.push(current);
+                let Some(next) = incident[&current].iter().copied().find(|&e| !used[e]) else {
+                    break;
+                };
+                used[next] = true;
+                let (a, b) = boundary[next];
+                current = if a == current { b } else { a };
+            }
+            loops.push(boundary_loop);
+        }
+
+        loops
+    }
+}
+
+/// Returns the normal of the triangle with the corners `a`, `b`, `c`, following the
+/// right-hand rule. The normal is not normalized, its length is twice the area of the triangle.
+pub(crate) fn face_normal(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> [f64; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+/// Returns the signed volume enclosed by `triangles`, see [`Mesh::signed_volume()`].
+pub(crate) fn signed_volume(vertices: &[Vertex], triangles: &[Triangle]) -> f64 {
+    let vertex = |index: usize| vertices.get(index).map(|v| [v.x, v.y, v.z]);
+    let six_times_volume: f64 = triangles
+        .iter()
+        .filter_map(|triangle| {
+            let (a, b, c) = (
+                vertex(triangle.v1)?,
+                vertex(triangle.v2)?,
+                vertex(triangle.v3)?,
+            );
+            // a · (b × c), six times the signed volume of the tetrahedron with the origin
+            Some(
+                a[0] * (b[1] * c[2] - b[2] * c[1])
+                    + a[1] * (b[2] * c[0] - b[0] * c[2])
+                    + a[2] * (b[0] * c[1] - b[1] * c[0]),
+            )
+        })
+        .sum();
+
+    six_times_volume / 6.0
+}
+
 /// Collection of Vertex
 ///
 /// See [`Vertex`] for more details
@@ -185,6 +446,266 @@ pub struct Triangle {
     pub pid: Option<usize>,
 }
 
+impl Triangle {
+    /// Returns true if the triangle references the same vertex more than once, e.g. `v1 == v2`.
+    pub fn has_repeated_vertex(&self) -> bool {
+        self.v1 == self.v2 || self.v2 == self.v3 || self.v1 == self.v3
+    }
+
+    /// Returns the property group id and the property indices of the three vertices
+    /// after applying the inheritance rules of the 3MF Core specification.
+    ///
+    /// The triangle `pid` falls back to the object `pid`, `p1` falls back to the object
+    /// `pindex`, and `p2` and `p3` fall back to `p1`. Returns `None` if the triangle has
+    /// no property group or no property index for the first vertex.
+    pub fn resolved_properties(
+        &self,
+        object_pid: Option<usize>,
+        object_pindex: Option<usize>,
+    ) -> Option<(usize, [usize; 3])> {
+        let pid = self.pid.or(object_pid)?;
+        let p1 = self.p1.or(object_pindex)?;
+        let p2 = self.p2.unwrap_or(p1);
+        let p3 = self.p3.unwrap_or(p1);
+
+        Some((pid, [p1, p2, p3]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Mesh, Triangle, Triangles, Vertex, Vertices};
+    use crate::core::test_support::cube;
+
+    fn triangle(
+        pid: Option<usize>,
+        p1: Option<usize>,
+        p2: Option<usize>,
+        p3: Option<usize>,
+    ) -> Triangle {
+        Triangle {
+            v1: 0,
+            v2: 1,
+            v3: 2,
+            p1,
+            p2,
+            p3,
+            pid,
+        }
+    }
+
+    #[test]
+    fn resolved_properties_test() {
+        // no properties on the triangle and the object
+        assert_eq!(
+            triangle(None, None, None, None).resolved_properties(None, None),
+            None
+        );
+
+        // everything inherited from the object
+        assert_eq!(
+            triangle(None, None, None, None).resolved_properties(Some(3), Some(1)),
+            Some((3, [1, 1, 1]))
+        );
+
+        // p2 and p3 default to p1
+        assert_eq!(
+            triangle(Some(5), Some(2), None, None).resolved_properties(Some(3), Some(1)),
+            Some((5, [2, 2, 2]))
+        );
+
+        // triangle pid without p1 uses the object pindex
+        assert_eq!(
+            triangle(Some(5), None, None, None).resolved_properties(Some(3), Some(1)),
+            Some((5, [1, 1, 1]))
+        );
+
+        // p1 with the object pid
+        assert_eq!(
+            triangle(None, Some(2), Some(4), None).resolved_properties(Some(3), None),
+            Some((3, [2, 4, 2]))
+        );
+
+        // fully specified triangle
+        assert_eq!(
+            triangle(Some(5), Some(2), Some(4), Some(6)).resolved_properties(None, None),
+            Some((5, [2, 4, 6]))
+        );
+
+        // pid without any pindex
+        assert_eq!(
+            triangle(Some(5), None, None, None).resolved_properties(None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn signed_volume_test() {
+        let mut mesh = cube(1.0);
+        assert_eq!(mesh.signed_volume(), 1.0);
+
+        for vertex in &mut mesh.vertices.vertex {
+            vertex.x = vertex.x * 2.0 + 5.0;
+        }
+        assert_eq!(mesh.signed_volume(), 2.0);
+
+        for triangle in &mut mesh.triangles.triangle {
+            core::mem::swap(&mut triangle.v2, &mut triangle.v3);
+        }
+        assert_eq!(mesh.signed_volume(), -2.0);
+    }
+
+    #[test]
+    fn boundary_loops_test() {
+        let mut mesh = cube(1.0);
+        assert_eq!(mesh.boundary_loops(), Vec::<Vec<usize>>::new());
+
+        // a missing triangle leaves a hole along its edges
+        mesh.triangles.triangle.remove(1);
+        assert_eq!(mesh.boundary_loops(), vec![vec![2, 1, 3]]);
+
+        // without the whole bottom face the hole spans its four corners
+        mesh.triangles.triangle.remove(0);
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        let mut corners = loops[0].clone();
+        corners.sort();
+        assert_eq!(corners, vec![0, 1, 2, 3]);
+
+        // a second hole on the opposite face
+        mesh.triangles.triangle.remove(0);
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn bounding_box_test() {
+        let mut mesh = Mesh {
+            vertices: Vertices { vertex: vec![] },
+            triangles: Triangles { triangle: vec![] },
+            trianglesets: None,
+            beamlattice: None,
+        };
+        assert_eq!(mesh.bounding_box(), None);
+
+        mesh.vertices.vertex = vec![
+            Vertex {
+                x: 1.0,
+                y: -2.0,
+                z: 3.0,
+            },
+            Vertex {
+                x: -1.0,
+                y: 5.0,
+                z: 0.5,
+            },
+            Vertex {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0,
+            },
+        ];
+        assert_eq!(
+            mesh.bounding_box(),
+            Some(([-1.0, -2.0, 0.5], [1.0, 5.0, 10.0]))
+        );
+
+        mesh.vertices.vertex.truncate(1);
+        assert_eq!(
+            mesh.bounding_box(),
+            Some(([1.0, -2.0, 3.0], [1.0, -2.0, 3.0]))
+        );
+
+        mesh.vertices.vertex[0].x = f64::MAX;
+        mesh.vertices.vertex.push(Vertex {
+            x: -f64::MAX,
+            y: 1e300,
+            z: -1e300,
+        });
+        assert_eq!(
+            mesh.bounding_box(),
+            Some(([-f64::MAX, -2.0, -1e300], [f64::MAX, 1e300, 3.0]))
+        );
+    }
+
+    #[test]
+    fn to_triangle_strips_test() {
+        // a grid of 10 x 10 quads, each split into two counter-clockwise triangles
+        let size = 10;
+        let index = |x: usize, y: usize| y * (size + 1) + x;
+        let vertex = (0..=size)
+            .flat_map(|y| {
+                (0..=size).map(move |x| Vertex {
+                    x: x as f64,
+                    y: y as f64,
+                    z: 0.0,
+                })
+            })
+            .collect();
+        let triangle = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                [
+                    [index(x, y), index(x + 1, y), index(x + 1, y + 1)],
+                    [index(x, y), index(x + 1, y + 1), index(x, y + 1)],
+                ]
+            })
+            .map(|[v1, v2, v3]| Triangle {
+                v1,
+                v2,
+                v3,
+                p1: None,
+                p2: None,
+                p3: None,
+                pid: None,
+            })
+            .collect::<Vec<_>>();
+        let mesh = Mesh {
+            vertices: Vertices { vertex },
+            triangles: Triangles { triangle },
+            trianglesets: None,
+            beamlattice: None,
+        };
+
+        let strips = mesh.to_triangle_strips();
+        let triangle_count = mesh.triangles.triangle.len();
+        let index_count = strips.iter().map(Vec::len).sum::<usize>();
+        assert!(strips.len() < triangle_count / 4);
+        assert!(index_count < triangle_count * 3 / 2);
+
+        // the strips hold every triangle exactly once with the original winding
+        let normalize = |mut t: [usize; 3]| {
+            let first = (0..3).min_by_key(|&i| t[i]).unwrap();
+            t.rotate_left(first);
+            t
+        };
+        let mut expected = mesh
+            .triangles
+            .triangle
+            .iter()
+            .map(|t| normalize([t.v1, t.v2, t.v3]))
+            .collect::<Vec<_>>();
+        let mut actual = strips
+            .iter()
+            .flat_map(|s| {
+                s.windows(3).enumerate().map(|(i, w)| {
+                    if i % 2 == 0 {
+                        normalize([w[0], w[1], w[2]])
+                    } else {
+                        normalize([w[1], w[0], w[2]])
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -376,6 +897,24 @@ mod memory_optimized_read_tests {
         );
     }
 
+    #[test]
+    pub fn fromxml_vertex_scientific_notation_test() {
+        let xml_string = format!(
+            r#"<vertex xmlns="{}" x="1.2345e-3" y="-2.5E+2" z="1e2" />"#,
+            CORE_NS
+        );
+        let vertex = from_str::<Vertex>(&xml_string).unwrap();
+
+        assert_eq!(
+            vertex,
+            Vertex {
+                x: 0.0012345,
+                y: -250.0,
+                z: 100.0,
+            }
+        );
+    }
+
     #[test]
     pub fn fromxml_vertices_test() {
         let xml_string = format!(
@@ -546,6 +1085,24 @@ mod speed_optimized_read_tests {
         );
     }
 
+    #[test]
+    pub fn fromxml_vertex_scientific_notation_test() {
+        let xml_string = format!(
+            r#"<vertex xmlns="{}" x="1.2345e-3" y="-2.5E+2" z="1e2" />"#,
+            CORE_NS
+        );
+        let vertex = from_str::<Vertex>(&xml_string).unwrap();
+
+        assert_eq!(
+            vertex,
+            Vertex {
+                x: 0.0012345,
+                y: -250.0,
+                z: 100.0,
+            }
+        );
+    }
+
     #[test]
     pub fn fromxml_vertices_test() {
         let xml_string = format!(
@@ -10,6 +10,8 @@ use instant_xml::{FromXml, Kind};
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
 use crate::threemf_namespaces::CORE_NS;
 
 //ToDo: Add additional optional fields on Metadata
@@ -17,6 +19,12 @@ use crate::threemf_namespaces::CORE_NS;
 ///
 /// Metadata provides additional information about the model, such as author,
 /// description, or custom properties.
+///
+/// When a model is read from a 3MF package the values are normalized with
+/// [`Metadata::normalize_value`]: whitespace is only kept if `preserve="1"` is set,
+/// otherwise a whitespace-only value is read as `None`. A value with
+/// `xml:space="preserve"`, set on the element or inherited from the `<model>` element,
+/// is read with `preserve="1"` unless the element sets `preserve` itself.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
 #[cfg_attr(feature = "write", derive(ToXml))]
@@ -43,6 +51,30 @@ pub struct Metadata {
     pub value: Option<String>,
 }
 
+impl Metadata {
+    /// Returns true if the whitespace of the value must be preserved, i.e. `preserve="1"`.
+    pub fn preserves_whitespace(&self) -> bool {
+        self.preserve.as_ref().is_some_and(|p| p.0)
+    }
+
+    /// Applies the whitespace rule used when reading a 3MF package.
+    ///
+    /// If [`Metadata::preserves_whitespace`] the value is kept as it is. Otherwise the value
+    /// is trimmed and a value that is empty or consists only of whitespace becomes `None`,
+    /// so the presence of a value can be checked with [`Option::is_some`].
+    pub fn normalize_value(&mut self) {
+        if self.preserves_whitespace() {
+            return;
+        }
+
+        self.value = match self.value.take() {
+            Some(value) if value.trim().is_empty() => None,
+            Some(value) if value.trim().len() != value.len() => Some(value.trim().to_owned()),
+            value => value,
+        };
+    }
+}
+
 /// Group of metadata entries.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
@@ -57,11 +89,35 @@ pub struct MetadataGroup {
     pub metadata: Vec<Metadata>,
 }
 
+/// The `preserve` attribute of a [`Metadata`].
+///
+/// Accepts the boolean values of XML Schema, i.e. `1`, `0`, `true` and `false`.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
+#[cfg_attr(feature = "speed-optimized-read", serde(try_from = "String"))]
 #[cfg_attr(feature = "write", derive(ToXml))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "write", xml(ns(CORE_NS), rename = "preserve"))]
-pub struct Preserve(bool);
+pub struct Preserve(pub bool);
+
+impl Preserve {
+    #[cfg(any(feature = "memory-optimized-read", feature = "speed-optimized-read"))]
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1" | "true" => Some(Preserve(true)),
+            "0" | "false" => Some(Preserve(false)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "speed-optimized-read")]
+impl TryFrom<String> for Preserve {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Preserve::parse(&value).ok_or_else(|| format!("{value} is not a valid boolean value"))
+    }
+}
 
 #[cfg(feature = "memory-optimized-read")]
 impl<'xml> FromXml<'xml> for Preserve {
@@ -86,8 +142,8 @@ impl<'xml> FromXml<'xml> for Preserve {
             None => return Err(Error::MissingValue("No Must Preserve value found")),
         };
 
-        if let Ok(must_preserve) = value.parse::<bool>() {
-            *into = Some(Preserve(must_preserve));
+        if let Some(preserve) = Preserve::parse(&value) {
+            *into = Some(preserve);
             Ok(())
         } else {
             Err(Error::MissingValue("Not a valid boolean value"))
@@ -99,6 +155,46 @@ impl<'xml> FromXml<'xml> for Preserve {
     const KIND: Kind = Kind::Scalar;
 }
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Metadata, Preserve};
+
+    fn metadata(preserve: Option<bool>, value: Option<&str>) -> Metadata {
+        Metadata {
+            name: "Foo".to_owned(),
+            preserve: preserve.map(Preserve),
+            value: value.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn normalize_value_test() {
+        for preserve in [None, Some(false)] {
+            let mut whitespace = metadata(preserve, Some(" \n\t"));
+            whitespace.normalize_value();
+            assert_eq!(whitespace.value, None);
+
+            let mut empty = metadata(preserve, Some(""));
+            empty.normalize_value();
+            assert_eq!(empty.value, None);
+
+            let mut padded = metadata(preserve, Some("  Bar "));
+            padded.normalize_value();
+            assert_eq!(padded.value.as_deref(), Some("Bar"));
+        }
+
+        let mut whitespace = metadata(Some(true), Some(" "));
+        whitespace.normalize_value();
+        assert_eq!(whitespace.value.as_deref(), Some(" "));
+
+        let mut padded = metadata(Some(true), Some("  Bar "));
+        padded.normalize_value();
+        assert_eq!(padded.value.as_deref(), Some("  Bar "));
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -172,7 +268,27 @@ mod memory_optimized_read_tests {
 
     use crate::threemf_namespaces::CORE_NS;
 
-    use super::{Metadata, MetadataGroup};
+    use super::{Metadata, MetadataGroup, Preserve};
+
+    #[test]
+    pub fn fromxml_metadata_preserve_test() {
+        for (attribute, preserve) in [("1", true), ("true", true), ("0", false), ("false", false)] {
+            let xml_string = format!(
+                r#"<metadata xmlns="{}" name="Foo" preserve="{}"> </metadata>"#,
+                CORE_NS, attribute
+            );
+            let metadata = from_str::<Metadata>(&xml_string).unwrap();
+
+            assert_eq!(metadata.preserve, Some(Preserve(preserve)));
+            assert_eq!(metadata.preserves_whitespace(), preserve);
+        }
+
+        let xml_string = format!(
+            r#"<metadata xmlns="{}" name="Foo" preserve="yes"> </metadata>"#,
+            CORE_NS
+        );
+        assert!(from_str::<Metadata>(&xml_string).is_err());
+    }
 
     #[test]
     pub fn fromxml_metadata_test() {
@@ -243,7 +359,27 @@ mod speed_optimized_read_tests {
 
     use crate::threemf_namespaces::CORE_NS;
 
-    use super::{Metadata, MetadataGroup};
+    use super::{Metadata, MetadataGroup, Preserve};
+
+    #[test]
+    pub fn fromxml_metadata_preserve_test() {
+        for (attribute, preserve) in [("1", true), ("true", true), ("0", false), ("false", false)] {
+            let xml_string = format!(
+                r#"<metadata xmlns="{}" name="Foo" preserve="{}"> </metadata>"#,
+                CORE_NS, attribute
+            );
+            let metadata = from_str::<Metadata>(&xml_string).unwrap();
+
+            assert_eq!(metadata.preserve, Some(Preserve(preserve)));
+            assert_eq!(metadata.preserves_whitespace(), preserve);
+        }
+
+        let xml_string = format!(
+            r#"<metadata xmlns="{}" name="Foo" preserve="yes"> </metadata>"#,
+            CORE_NS
+        );
+        assert!(from_str::<Metadata>(&xml_string).is_err());
+    }
 
     #[test]
     pub fn fromxml_metadata_test() {
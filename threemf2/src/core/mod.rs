@@ -27,3 +27,9 @@ pub mod triangle_set;
 
 /// Defines the type representing a Beam Lattice element and other elements and attributes related to it.
 pub mod beamlattice;
+
+/// Builders for constructing mesh geometry, usable without the io features.
+pub mod builder;
+
+#[cfg(test)]
+pub(crate) mod test_support;
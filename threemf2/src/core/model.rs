@@ -7,6 +7,14 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+#[cfg(feature = "write")]
+use std::collections::HashSet;
+
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "write")]
+use crate::core::build::Item;
+
 use crate::{
     core::{build::Build, metadata::Metadata, resources::Resources},
     threemf_namespaces::{
@@ -32,12 +40,18 @@ pub struct Model {
     )]
     pub unit: Option<Unit>,
 
+    /// Space-separated prefixes of the extensions a consumer must support to process
+    /// the model.
     #[cfg_attr(
         any(feature = "write", feature = "memory-optimized-read"),
         xml(attribute)
     )]
     pub requiredextensions: Option<String>,
 
+    /// Space-separated prefixes of the extensions a consumer should support.
+    ///
+    /// Elements of an extension are read by their namespace, so e.g. a beam lattice is read
+    /// whether its extension is listed here, in `requiredextensions` or in neither.
     #[cfg_attr(
         any(feature = "write", feature = "memory-optimized-read"),
         xml(attribute)
@@ -86,6 +100,77 @@ impl From<String> for Unit {
     }
 }
 
+impl Unit {
+    /// Length of one unit in millimeters.
+    pub fn millimeters(&self) -> f64 {
+        match self {
+            Unit::Micron => 0.001,
+            Unit::Millimeter => 1.0,
+            Unit::Centimeter => 10.0,
+            Unit::Inch => 25.4,
+            Unit::Foot => 304.8,
+            Unit::Meter => 1000.0,
+        }
+    }
+}
+
+impl Model {
+    /// Scales the geometry of every object uniformly by `factor`.
+    ///
+    /// Scales the vertices as well as the beam lattice radii and minimum beam length,
+    /// the build item and component transforms are left untouched.
+    pub fn scale(&mut self, factor: f64) {
+        for mesh in self
+            .resources
+            .object
+            .iter_mut()
+            .filter_map(|o| o.mesh.as_mut())
+        {
+            for vertex in &mut mesh.vertices.vertex {
+                vertex.x *= factor;
+                vertex.y *= factor;
+                vertex.z *= factor;
+            }
+
+            if let Some(lattice) = &mut mesh.beamlattice {
+                lattice.minlength *= factor;
+                lattice.radius *= factor;
+                if let Some(ballradius) = &mut lattice.ballradius {
+                    *ballradius *= factor;
+                }
+                for beam in &mut lattice.beams.beam {
+                    for radius in [&mut beam.r1, &mut beam.r2].into_iter().flatten() {
+                        *radius *= factor;
+                    }
+                }
+                for ball in lattice.balls.iter_mut().flat_map(|b| &mut b.ball) {
+                    if let Some(radius) = &mut ball.r {
+                        *radius *= factor;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shifts the vertices of every object by `offset`.
+    ///
+    /// The build item and component transforms are left untouched.
+    pub fn translate(&mut self, offset: [f64; 3]) {
+        for mesh in self
+            .resources
+            .object
+            .iter_mut()
+            .filter_map(|o| o.mesh.as_mut())
+        {
+            for vertex in &mut mesh.vertices.vertex {
+                vertex.x += offset[0];
+                vertex.y += offset[1];
+                vertex.z += offset[2];
+            }
+        }
+    }
+}
+
 #[cfg(feature = "write")]
 impl Model {
     pub fn used_namespaces(&self) -> Vec<ThreemfNamespace> {
@@ -106,6 +191,113 @@ impl Model {
         used
     }
 
+    /// Returns a standalone copy of this model containing only the objects with the given ids.
+    ///
+    /// Objects referenced as components (within this model) by a listed object are kept as
+    /// well so the result stays valid. Build items referencing a listed object are kept, and a
+    /// plain build item is added for every listed object without one. Unknown ids are ignored.
+    /// The metadata of the model is carried over as it is.
+    pub fn subset(&self, object_ids: &[usize]) -> Model {
+        let mut keep = HashSet::new();
+        let mut pending = object_ids.to_vec();
+        while let Some(id) = pending.pop() {
+            let Some(object) = self.resources.object.iter().find(|o| o.id == id) else {
+                continue;
+            };
+            if !keep.insert(id) {
+                continue;
+            }
+            if let Some(components) = &object.components {
+                pending.extend(
+                    components
+                        .component
+                        .iter()
+                        .filter(|c| c.path.is_none())
+                        .map(|c| c.objectid),
+                );
+            }
+        }
+
+        let object = self
+            .resources
+            .object
+            .iter()
+            .filter(|o| keep.contains(&o.id))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut item = self
+            .build
+            .item
+            .iter()
+            .filter(|i| i.path.is_none() && object_ids.contains(&i.objectid))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in object_ids {
+            if keep.contains(id) && !item.iter().any(|i| i.objectid == *id) {
+                item.push(Item {
+                    objectid: *id,
+                    ..Default::default()
+                });
+            }
+        }
+
+        let mut model = Model {
+            unit: self.unit.clone(),
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: self.metadata.clone(),
+            resources: Resources {
+                unknown_resources: self.resources.unknown_resources.clone(),
+                object,
+                basematerials: self.resources.basematerials.clone(),
+            },
+            build: Build { uuid: None, item },
+        };
+        model.keep_used_extensions(self);
+        model
+    }
+
+    /// Sets the required and recommended extensions of this model to the ones of `source`,
+    /// dropping the extensions known to this library whose namespace this model doesn't use.
+    pub(crate) fn keep_used_extensions(&mut self, source: &Model) {
+        let used = self.used_namespaces();
+        self.requiredextensions =
+            Self::filter_extensions(source.requiredextensions.as_deref(), &used);
+        self.recommendedextensions =
+            Self::filter_extensions(source.recommendedextensions.as_deref(), &used);
+    }
+
+    /// Serializes the [subset](Model::subset) of this model containing only the objects with
+    /// the given ids into a standalone model XML string.
+    pub fn subset_to_xml(&self, object_ids: &[usize]) -> Result<String, instant_xml::Error> {
+        instant_xml::to_string(&self.subset(object_ids))
+    }
+
+    /// Drops the extension prefixes known to this library whose namespace is not used.
+    fn filter_extensions(extensions: Option<&str>, used: &[ThreemfNamespace]) -> Option<String> {
+        let extensions = extensions?
+            .split_whitespace()
+            .filter(|prefix| {
+                let known = [
+                    ThreemfNamespace::Prod,
+                    ThreemfNamespace::BeamLattice,
+                    ThreemfNamespace::CoreTriangleSet,
+                ];
+                match known.into_iter().find(|ns| ns.prefix() == Some(*prefix)) {
+                    Some(ns) => used.contains(&ns),
+                    None => true,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if extensions.is_empty() {
+            None
+        } else {
+            Some(extensions.join(" "))
+        }
+    }
+
     fn uses_prod_ns(&self) -> bool {
         if self.build.uuid.is_some() {
             return true;
@@ -158,6 +350,122 @@ impl Model {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::core::{
+        build::{Build, Item},
+        builder::MeshBuilder,
+        mesh::Vertex,
+        object::Object,
+        resources::Resources,
+    };
+
+    use super::Model;
+
+    fn lattice_model() -> Model {
+        let mut mesh = MeshBuilder::new();
+        mesh.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 2.0, 3.0]])
+            .add_beam_lattice(|lattice| {
+                lattice
+                    .minlength(0.01)
+                    .radius(0.5)
+                    .ballradius(0.25)
+                    .add_beam(0, 1)
+                    .add_ball(0);
+            });
+
+        Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                unknown_resources: vec![],
+                object: vec![Object {
+                    id: 1,
+                    objecttype: None,
+                    thumbnail: None,
+                    partnumber: None,
+                    name: None,
+                    pid: None,
+                    pindex: None,
+                    uuid: None,
+                    mesh: Some(mesh.build().unwrap()),
+                    components: None,
+                }],
+                basematerials: vec![],
+            },
+            build: Build {
+                uuid: None,
+                item: vec![Item {
+                    objectid: 1,
+                    ..Default::default()
+                }],
+            },
+        }
+    }
+
+    fn vertices(model: &Model) -> &[Vertex] {
+        &model.resources.object[0]
+            .mesh
+            .as_ref()
+            .unwrap()
+            .vertices
+            .vertex
+    }
+
+    #[test]
+    fn scale_test() {
+        let mut model = lattice_model();
+        model.scale(2.0);
+
+        assert_eq!(
+            vertices(&model)[1],
+            Vertex {
+                x: 2.0,
+                y: 4.0,
+                z: 6.0
+            }
+        );
+        let lattice = model.resources.object[0]
+            .mesh
+            .as_ref()
+            .unwrap()
+            .beamlattice
+            .as_ref()
+            .unwrap();
+        assert_eq!(lattice.minlength, 0.02);
+        assert_eq!(lattice.radius, 1.0);
+        assert_eq!(lattice.ballradius, Some(0.5));
+        assert_eq!(model.build, lattice_model().build);
+    }
+
+    #[test]
+    fn translate_test() {
+        let mut model = lattice_model();
+        model.translate([1.0, -2.0, 0.5]);
+
+        assert_eq!(
+            vertices(&model),
+            [
+                Vertex {
+                    x: 1.0,
+                    y: -2.0,
+                    z: 0.5
+                },
+                Vertex {
+                    x: 2.0,
+                    y: 0.0,
+                    z: 3.5
+                },
+            ]
+        );
+        assert_eq!(model.build, lattice_model().build);
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -196,6 +504,7 @@ mod write_tests {
                 value: None,
             }],
             resources: Resources {
+                unknown_resources: vec![],
                 basematerials: vec![],
                 object: vec![Object {
                     id: 346,
@@ -257,6 +566,7 @@ mod write_tests {
             recommendedextensions: None,
             metadata: vec![],
             resources: Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: Some(ObjectType::Model),
@@ -300,6 +610,7 @@ mod write_tests {
             recommendedextensions: None,
             metadata: vec![],
             resources: Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: Some(ObjectType::Model),
@@ -348,6 +659,7 @@ mod write_tests {
             recommendedextensions: None,
             metadata: vec![],
             resources: Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: Some(ObjectType::Model),
@@ -410,6 +722,7 @@ mod write_tests {
             recommendedextensions: None,
             metadata: vec![],
             resources: Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: Some(ObjectType::Model),
@@ -460,6 +773,7 @@ mod write_tests {
             recommendedextensions: None,
             metadata: vec![],
             resources: Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: Some(ObjectType::Model),
@@ -518,6 +832,91 @@ mod write_tests {
             ]
         );
     }
+
+    #[test]
+    fn test_subset_to_xml_keeps_listed_objects_and_dependencies() {
+        use crate::core::component::{Component, Components};
+
+        let object = |id: usize, components: Option<Components>| Object {
+            id,
+            objecttype: Some(ObjectType::Model),
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: None,
+            components,
+        };
+        let model = Model {
+            unit: Some(Unit::Millimeter),
+            requiredextensions: Some("p".to_owned()),
+            recommendedextensions: None,
+            metadata: vec![Metadata {
+                name: "Title".to_owned(),
+                preserve: None,
+                value: None,
+            }],
+            resources: Resources {
+                unknown_resources: vec![],
+                object: vec![
+                    object(1, None),
+                    object(2, None),
+                    object(
+                        3,
+                        Some(Components {
+                            component: vec![Component {
+                                objectid: 1,
+                                transform: None,
+                                path: None,
+                                uuid: None,
+                            }],
+                        }),
+                    ),
+                ],
+                basematerials: vec![],
+            },
+            build: Build {
+                uuid: Some("build-uuid".to_owned()),
+                item: vec![
+                    Item {
+                        objectid: 2,
+                        transform: None,
+                        partnumber: None,
+                        path: None,
+                        uuid: None,
+                    },
+                    Item {
+                        objectid: 3,
+                        transform: None,
+                        partnumber: Some("part-3".to_owned()),
+                        path: None,
+                        uuid: None,
+                    },
+                ],
+            },
+        };
+
+        let subset = model.subset(&[3, 42]);
+        let ids = subset
+            .resources
+            .object
+            .iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(subset.build.item.len(), 1);
+        assert_eq!(subset.build.item[0].partnumber.as_deref(), Some("part-3"));
+        assert_eq!(subset.requiredextensions, None);
+        assert_eq!(subset.metadata, model.metadata);
+
+        let xml = model.subset_to_xml(&[3, 42]).unwrap();
+        assert_eq!(xml, to_string(&subset).unwrap());
+        assert!(xml.contains(r#"<object id="3""#));
+        assert!(!xml.contains(r#"<object id="2""#));
+        assert!(xml.contains(r#"<metadata name="Title""#));
+    }
 }
 
 #[cfg(feature = "memory-optimized-read")]
@@ -561,6 +960,7 @@ mod memory_optimized_read_tests {
                     value: None,
                 }],
                 resources: Resources {
+                    unknown_resources: vec![],
                     basematerials: vec![],
                     object: vec![Object {
                         id: 346,
@@ -618,6 +1018,7 @@ mod memory_optimized_read_tests {
                     value: None,
                 }],
                 resources: Resources {
+                    unknown_resources: vec![],
                     basematerials: vec![],
                     object: vec![Object {
                         id: 346,
@@ -724,6 +1125,7 @@ mod speed_optimized_read_tests {
                     value: Some("".to_string()), //ToDo: Import output for empty value
                 }],
                 resources: Resources {
+                    unknown_resources: vec![],
                     basematerials: vec![],
                     object: vec![Object {
                         id: 346,
@@ -781,6 +1183,7 @@ mod speed_optimized_read_tests {
                     value: Some("".to_string()), //ToDo: Improve output for empty value
                 }],
                 resources: Resources {
+                    unknown_resources: vec![],
                     basematerials: vec![],
                     object: vec![Object {
                         id: 346,
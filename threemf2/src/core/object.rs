@@ -7,6 +7,8 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::string::String;
+
 use crate::{
     core::{component::Components, mesh::Mesh},
     threemf_namespaces::{CORE_NS, PROD_NS},
@@ -99,6 +101,61 @@ pub struct Object {
     pub components: Option<Components>,
 }
 
+impl Object {
+    /// Returns true if the object holds no geometry, i.e. it has neither a non-empty
+    /// [`Mesh`] nor any component.
+    pub fn is_empty(&self) -> bool {
+        self.mesh.as_ref().is_none_or(Mesh::is_empty)
+            && self
+                .components
+                .as_ref()
+                .is_none_or(|components| components.component.is_empty())
+    }
+
+    /// Returns a hash of the vertices and triangles of the [`Mesh`] of the object.
+    ///
+    /// The hash is order-sensitive, the same geometry with reordered vertices or triangles
+    /// hashes differently. Properties, triangle sets and beam lattices are not hashed, and
+    /// objects without a mesh share the hash of an empty mesh. Unlike the std hashers the
+    /// value is stable across runs and platforms.
+    pub fn geometry_hash(&self) -> u64 {
+        let mut hasher = Fnv1aHasher::default();
+        if let Some(mesh) = &self.mesh {
+            hasher.write_u64(mesh.vertices.vertex.len() as u64);
+            for vertex in &mesh.vertices.vertex {
+                for coordinate in [vertex.x, vertex.y, vertex.z] {
+                    // adding 0.0 turns -0.0 into 0.0, so both hash the same
+                    hasher.write_u64((coordinate + 0.0).to_bits());
+                }
+            }
+            for triangle in &mesh.triangles.triangle {
+                for index in [triangle.v1, triangle.v2, triangle.v3] {
+                    hasher.write_u64(index as u64);
+                }
+            }
+        }
+        hasher.0
+    }
+}
+
+/// 64 bit FNV-1a hash, writing integers as little endian bytes.
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1aHasher {
+    fn write_u64(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "speed-optimized-read", serde(from = "String"))]
 #[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
@@ -124,6 +181,9 @@ pub enum ObjectType {
     Surface,
 
     /// Other auxiliary uses
+    ///
+    /// Objects of this type may carry nonstandard content for vendor purposes, so they are
+    /// exempt from the degenerate triangle and empty object checks.
     Other,
 }
 
@@ -140,6 +200,119 @@ impl From<String> for ObjectType {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::core::{
+        component::{Component, Components},
+        mesh::{Mesh, Triangle, Triangles, Vertex, Vertices},
+    };
+
+    use super::Object;
+
+    fn object(mesh: Option<Mesh>, components: Option<Components>) -> Object {
+        Object {
+            id: 1,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh,
+            components,
+        }
+    }
+
+    #[test]
+    pub fn is_empty_test() {
+        let empty_mesh = Mesh {
+            vertices: Vertices { vertex: vec![] },
+            triangles: Triangles { triangle: vec![] },
+            trianglesets: None,
+            beamlattice: None,
+        };
+        let mesh = Mesh {
+            vertices: Vertices {
+                vertex: vec![Vertex {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                }],
+            },
+            ..empty_mesh.clone()
+        };
+
+        assert!(empty_mesh.is_empty());
+        assert!(!mesh.is_empty());
+
+        assert!(object(None, None).is_empty());
+        assert!(object(Some(empty_mesh), None).is_empty());
+        assert!(object(None, Some(Components { component: vec![] })).is_empty());
+        assert!(!object(Some(mesh), None).is_empty());
+        assert!(
+            !object(
+                None,
+                Some(Components {
+                    component: vec![Component {
+                        objectid: 2,
+                        transform: None,
+                        path: None,
+                        uuid: None,
+                    }],
+                }),
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    pub fn geometry_hash_test() {
+        let vertex = |x: f64| Vertex { x, y: 0.0, z: 0.0 };
+        let triangle = |v1, v2, v3| Triangle {
+            v1,
+            v2,
+            v3,
+            p1: None,
+            p2: None,
+            p3: None,
+            pid: None,
+        };
+        let mesh = Mesh {
+            vertices: Vertices {
+                vertex: vec![vertex(0.0), vertex(1.0), vertex(2.0)],
+            },
+            triangles: Triangles {
+                triangle: vec![triangle(0, 1, 2)],
+            },
+            trianglesets: None,
+            beamlattice: None,
+        };
+
+        let hash = object(Some(mesh.clone()), None).geometry_hash();
+        let mut same = object(Some(mesh.clone()), None);
+        same.id = 2;
+        same.name = Some("copy".to_owned());
+        same.mesh.as_mut().unwrap().triangles.triangle[0].pid = Some(1);
+        same.mesh.as_mut().unwrap().vertices.vertex[0].x = -0.0;
+        assert_eq!(same.geometry_hash(), hash);
+
+        let mut reordered = mesh.clone();
+        reordered.triangles.triangle[0] = triangle(1, 2, 0);
+        assert_ne!(object(Some(reordered), None).geometry_hash(), hash);
+
+        let mut moved = mesh;
+        moved.vertices.vertex[2].x = 3.0;
+        assert_ne!(object(Some(moved), None).geometry_hash(), hash);
+
+        assert_eq!(
+            object(None, None).geometry_hash(),
+            object(None, Some(Components { component: vec![] })).geometry_hash()
+        );
+        assert_ne!(object(None, None).geometry_hash(), hash);
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -1,12 +1,17 @@
+#[cfg(any(feature = "write", feature = "memory-optimized-read"))]
+use instant_xml::{Error, Id};
+
 #[cfg(feature = "write")]
-use instant_xml::ToXml;
+use instant_xml::{Serializer, ToXml};
 
 #[cfg(feature = "memory-optimized-read")]
-use instant_xml::FromXml;
+use instant_xml::{Deserializer, FromXml, Kind};
 
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{string::String, vec::Vec};
+
 use crate::{core::object::Object, threemf_namespaces::CORE_NS};
 
 /// A collection of Objects and other properties that are referenced by other elements.
@@ -19,13 +24,85 @@ use crate::{core::object::Object, threemf_namespaces::CORE_NS};
     xml(ns(CORE_NS), rename = "resources")
 )]
 pub struct Resources {
+    /// Resource elements not known to this library, e.g. `<m:multiproperties>` of the
+    /// Materials extension. They are kept as raw XML so they survive a round-trip and are
+    /// written before the objects that may reference them. See [`UnknownResource`]
+    #[cfg_attr(feature = "speed-optimized-read", serde(skip))]
+    pub unknown_resources: Vec<UnknownResource>,
+
+    /// Collection of Materials, written before the objects referencing them.
+    #[cfg_attr(feature = "speed-optimized-read", serde(default))]
+    pub basematerials: Vec<BaseMaterials>,
+
     /// Collection of Object. See [`crate::core::object::Object`]
     #[cfg_attr(feature = "speed-optimized-read", serde(default))]
     pub object: Vec<Object>,
+}
 
-    /// Collection of Materials.
-    #[cfg_attr(feature = "speed-optimized-read", serde(default))]
-    pub basematerials: Vec<BaseMaterials>,
+/// The raw XML of a resource element not known to this library.
+///
+/// The XML is self-contained, the namespace declarations of the prefixes used by the element
+/// are added to it when the model is read from a 3mf package. The deserializers never produce
+/// this type themselves, the reader collects the unknown elements from the model part.
+/// The XML is written as it is, it is the responsibility of the user to keep it valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownResource(pub String);
+
+impl UnknownResource {
+    /// Returns the `id` attribute of the element, e.g. the id of a `<m:colorgroup>` that
+    /// objects and triangles reference with their `pid`. Returns `None` if the element has
+    /// no numeric `id`.
+    pub fn id(&self) -> Option<usize> {
+        let element = self.0.trim_start().strip_prefix('<')?;
+
+        // the attributes of the start tag follow the element name
+        let mut rest = element.trim_start_matches(|c: char| !c.is_whitespace() && c != '>');
+        loop {
+            rest = rest.trim_start();
+            if rest.starts_with(['>', '/']) {
+                return None;
+            }
+            let (name, value) = rest.split_once('=')?;
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let (value, after) = value[1..].split_once(quote)?;
+            if name.trim_end() == "id" {
+                return value.trim().parse().ok();
+            }
+            rest = after;
+        }
+    }
+}
+
+#[cfg(feature = "write")]
+impl ToXml for UnknownResource {
+    fn serialize<W: std::fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        serializer.write_str(&self.0)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "memory-optimized-read")]
+impl<'xml> FromXml<'xml> for UnknownResource {
+    fn matches(_: Id<'_>, _: Option<Id<'_>>) -> bool {
+        false
+    }
+
+    fn deserialize<'cx>(
+        _: &mut Self::Accumulator,
+        _: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserializer.ignore()
+    }
+
+    type Accumulator = Option<Self>;
+
+    const KIND: Kind = Kind::Element;
 }
 
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
@@ -88,6 +165,7 @@ mod write_tests {
             CORE_NS, PROD_PREFIX, PROD_NS
         );
         let resources = Resources {
+            unknown_resources: vec![],
             object: vec![Object {
                 id: 1,
                 objecttype: None,
@@ -114,6 +192,7 @@ mod write_tests {
             CORE_NS
         );
         let resources = Resources {
+            unknown_resources: vec![],
             object: vec![],
             basematerials: vec![BaseMaterials {
                 id: 1,
@@ -189,6 +268,7 @@ mod memory_optimized_read_tests {
         assert_eq!(
             resources,
             Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: None,
@@ -217,6 +297,7 @@ mod memory_optimized_read_tests {
         assert_eq!(
             resources,
             Resources {
+                unknown_resources: vec![],
                 object: vec![],
                 basematerials: vec![BaseMaterials {
                     id: 1,
@@ -294,6 +375,7 @@ mod speed_optimized_read_tests {
         assert_eq!(
             resources,
             Resources {
+                unknown_resources: vec![],
                 object: vec![Object {
                     id: 1,
                     objecttype: None,
@@ -322,6 +404,7 @@ mod speed_optimized_read_tests {
         assert_eq!(
             resources,
             Resources {
+                unknown_resources: vec![],
                 object: vec![],
                 basematerials: vec![BaseMaterials {
                     id: 1,
@@ -377,3 +460,26 @@ mod speed_optimized_read_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::UnknownResource;
+
+    #[test]
+    fn unknown_resource_id_test() {
+        let id = |xml: &str| UnknownResource(xml.to_owned()).id();
+
+        assert_eq!(
+            id(
+                r##"<m:colorgroup xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02" id="2"><m:color color="#FF0000"/></m:colorgroup>"##
+            ),
+            Some(2)
+        );
+        assert_eq!(id("<x:group id='7' name=\"a\"/>"), Some(7));
+        assert_eq!(id(r#"<x:group name="id" other-id="3">"#), None);
+        assert_eq!(id(r#"<x:group><x:child id="3"/></x:group>"#), None);
+        assert_eq!(id(r#"<x:group id="none"/>"#), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! Fixtures shared by the unit tests of the crate.
+
+use crate::core::mesh::{Mesh, Triangle, Triangles, Vertex, Vertices};
+
+/// A cube with the edge length `size` at the origin, its triangles facing outwards.
+pub(crate) fn cube(size: f64) -> Mesh {
+    let vertex = |i: usize| Vertex {
+        x: (i & 1) as f64 * size,
+        y: ((i >> 1) & 1) as f64 * size,
+        z: ((i >> 2) & 1) as f64 * size,
+    };
+    let triangle = |[v1, v2, v3]: [usize; 3]| Triangle {
+        v1,
+        v2,
+        v3,
+        p1: None,
+        p2: None,
+        p3: None,
+        pid: None,
+    };
+    #[rustfmt::skip]
+    let triangles = [
+        [0, 2, 1], [1, 2, 3], [4, 5, 6], [5, 7, 6],
+        [0, 1, 4], [1, 5, 4], [2, 6, 3], [3, 6, 7],
+        [0, 4, 2], [2, 4, 6], [1, 3, 5], [3, 7, 5],
+    ];
+
+    Mesh {
+        vertices: Vertices {
+            vertex: (0..8).map(vertex).collect(),
+        },
+        triangles: Triangles {
+            triangle: triangles.into_iter().map(triangle).collect(),
+        },
+        trianglesets: None,
+        beamlattice: None,
+    }
+}
@@ -10,7 +10,8 @@ use instant_xml::{Deserializer, FromXml, Kind};
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
-use std::ops::Index;
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::{fmt, ops::Index};
 
 const MATRIX_SIZE: usize = 12;
 
@@ -27,7 +28,7 @@ const MATRIX_SIZE: usize = 12;
 /// Represents a 3D affine transformation as a 12-element array in row-major order.
 /// The matrix transforms points from object space to world space.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
-#[cfg_attr(feature = "speed-optimized-read", serde(from = "String"))]
+#[cfg_attr(feature = "speed-optimized-read", serde(try_from = "String"))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct Transform(pub [f64; MATRIX_SIZE]);
 
@@ -86,9 +87,13 @@ impl<'xml> FromXml<'xml> for Transform {
             None => return Err(Error::MissingValue("No transform string found")),
         };
 
-        let result = Transform::from(value.into_owned());
-        *into = Some(result);
-        Ok(())
+        match Transform::from_spec_str(&value) {
+            Ok(transform) => {
+                *into = Some(transform);
+                Ok(())
+            }
+            Err(err) => Err(Error::UnexpectedValue(err.to_string())),
+        }
     }
 
     type Accumulator = Option<Self>;
@@ -96,21 +101,229 @@ impl<'xml> FromXml<'xml> for Transform {
     const KIND: Kind = Kind::Scalar;
 }
 
-impl From<String> for Transform {
-    fn from(value: String) -> Self {
-        let values = value
-            .split(" ")
-            .map(|v| {
-                let parsed = v.parse::<f64>();
-                match parsed {
-                    Ok(val) => val,
-                    Err(_) => f64::MIN_POSITIVE,
-                }
-            })
-            .collect::<Vec<f64>>();
-
-        // write now it can always panic something to improve in the future
-        Self(values.try_into().unwrap())
+/// Errors returned by [`Transform::from_spec_str`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformParseError {
+    /// The string does not contain exactly 12 values.
+    WrongValueCount(usize),
+
+    /// A value could not be parsed as a number.
+    InvalidValue(String),
+
+    /// A value is infinite or NaN.
+    NonFiniteValue(String),
+}
+
+impl fmt::Display for TransformParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongValueCount(count) => write!(
+                f,
+                "Transform requires {MATRIX_SIZE} values but {count} were found"
+            ),
+            Self::InvalidValue(value) => write!(f, "Transform value {value} is not a number"),
+            Self::NonFiniteValue(value) => write!(f, "Transform value {value} is not finite"),
+        }
+    }
+}
+
+impl core::error::Error for TransformParseError {}
+
+impl Transform {
+    /// The transform that leaves every point unchanged.
+    #[rustfmt::skip]
+    pub const IDENTITY: Transform = Transform([
+        1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0,
+        0.0, 0.0, 1.0,
+        0.0, 0.0, 0.0,
+    ]);
+
+    /// Returns [`Transform::IDENTITY`].
+    pub const fn identity() -> Transform {
+        Self::IDENTITY
+    }
+
+    /// Returns the transform moving points by `translation`.
+    #[rustfmt::skip]
+    pub const fn from_translation(translation: [f64; 3]) -> Transform {
+        let [x, y, z] = translation;
+        Transform([
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            x, y, z,
+        ])
+    }
+
+    /// Returns the transform scaling points by the given factor along each axis.
+    #[rustfmt::skip]
+    pub const fn from_scale(scale: [f64; 3]) -> Transform {
+        let [x, y, z] = scale;
+        Transform([
+            x, 0.0, 0.0,
+            0.0, y, 0.0,
+            0.0, 0.0, z,
+            0.0, 0.0, 0.0,
+        ])
+    }
+
+    /// Returns the transform rotating points counterclockwise by `radians` around `axis`
+    /// through the origin, looking against the direction of `axis`.
+    ///
+    /// `axis` doesn't need to be of unit length, a zero `axis` gives the identity.
+    /// Only available with the `std` feature, the trigonometric functions require `std`.
+    #[cfg(feature = "std")]
+    #[rustfmt::skip]
+    pub fn from_rotation_axis(axis: [f64; 3], radians: f64) -> Transform {
+        let length = axis.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if length == 0.0 {
+            return Self::IDENTITY;
+        }
+        let [x, y, z] = axis.map(|a| a / length);
+        let (sin, cos) = radians.sin_cos();
+        let t = 1.0 - cos;
+
+        // the rows are the images of the unit vectors, 3MF transforms row vectors
+        Transform([
+            cos + x * x * t, x * y * t + z * sin, x * z * t - y * sin,
+            x * y * t - z * sin, cos + y * y * t, y * z * t + x * sin,
+            x * z * t + y * sin, y * z * t - x * sin, cos + z * z * t,
+            0.0, 0.0, 0.0,
+        ])
+    }
+
+    /// Applies the transform to a point.
+    pub fn transform_point(&self, point: [f64; 3]) -> [f64; 3] {
+        let m = &self.0;
+        let [x, y, z] = point;
+        [
+            x * m[0] + y * m[3] + z * m[6] + m[9],
+            x * m[1] + y * m[4] + z * m[7] + m[10],
+            x * m[2] + y * m[5] + z * m[8] + m[11],
+        ]
+    }
+
+    /// Returns the transform that applies `self` first and `other` afterwards.
+    ///
+    /// E.g. for a component inside a build item this is `component.then(item)`.
+    pub fn then(&self, other: &Transform) -> Transform {
+        let mut matrix = [0.0; MATRIX_SIZE];
+        for row in 0..3 {
+            let start = row * 3;
+            let linear =
+                other.transform_point([self.0[start], self.0[start + 1], self.0[start + 2]]);
+            for (column, value) in linear.iter().enumerate() {
+                // the translation of other only applies to the translation row
+                matrix[start + column] = value - other.0[9 + column];
+            }
+        }
+        let translation = other.transform_point([self.0[9], self.0[10], self.0[11]]);
+        matrix[9..].copy_from_slice(&translation);
+
+        Transform(matrix)
+    }
+
+    /// Returns the matrix product of `self` and `other`.
+    ///
+    /// 3MF transforms points as row vectors, so the product applies `self` first and `other`
+    /// afterwards, the same as [`Transform::then`]. E.g. a slicer places a component inside
+    /// a build item with `component.multiply(&item)`.
+    pub fn multiply(&self, other: &Transform) -> Transform {
+        self.then(other)
+    }
+
+    /// Applies the transform to a point, the same as [`Transform::transform_point`].
+    pub fn apply_to_point(&self, point: [f64; 3]) -> [f64; 3] {
+        self.transform_point(point)
+    }
+
+    /// Returns the determinant of the 3x3 linear part of the transform.
+    ///
+    /// A negative determinant means the transform mirrors, which flips the orientation of triangles.
+    pub fn determinant(&self) -> f64 {
+        let m = &self.0;
+        m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6])
+            + m[2] * (m[3] * m[7] - m[4] * m[6])
+    }
+
+    /// Returns the transform undoing `self`, e.g. to map a point in world space back into
+    /// the space of an object placed by a build item.
+    ///
+    /// Returns `None` if the transform is singular, i.e. it flattens space and can't be undone.
+    pub fn inverse(&self) -> Option<Transform> {
+        let m = &self.0;
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        // inverse of the linear part from its adjugate
+        #[rustfmt::skip]
+        let mut matrix = [
+            m[4] * m[8] - m[5] * m[7], m[2] * m[7] - m[1] * m[8], m[1] * m[5] - m[2] * m[4],
+            m[5] * m[6] - m[3] * m[8], m[0] * m[8] - m[2] * m[6], m[2] * m[3] - m[0] * m[5],
+            m[3] * m[7] - m[4] * m[6], m[1] * m[6] - m[0] * m[7], m[0] * m[4] - m[1] * m[3],
+            0.0, 0.0, 0.0,
+        ];
+        matrix[..9].iter_mut().for_each(|v| *v /= determinant);
+
+        // the translation is undone in the inverted space
+        let mut inverse = Transform(matrix);
+        let [x, y, z] = inverse.transform_point([m[9], m[10], m[11]]);
+        inverse.0[9..].copy_from_slice(&[-x, -y, -z]);
+
+        inverse.0.iter().all(|v| v.is_finite()).then_some(inverse)
+    }
+
+    /// Parses a transform from the space separated format of the 3MF `transform` attribute,
+    /// e.g. `"1 0 0 0 1 0 0 0 1 10 20 30"`.
+    ///
+    /// The string must contain exactly 12 finite numbers.
+    pub fn from_spec_str(value: &str) -> Result<Self, TransformParseError> {
+        let mut matrix = [0.0; MATRIX_SIZE];
+        let mut count = 0;
+
+        for value in value.split_whitespace() {
+            let parsed = value
+                .parse::<f64>()
+                .map_err(|_| TransformParseError::InvalidValue(value.to_owned()))?;
+            if !parsed.is_finite() {
+                return Err(TransformParseError::NonFiniteValue(value.to_owned()));
+            }
+
+            if count < MATRIX_SIZE {
+                matrix[count] = parsed;
+            }
+            count += 1;
+        }
+
+        if count != MATRIX_SIZE {
+            return Err(TransformParseError::WrongValueCount(count));
+        }
+
+        Ok(Self(matrix))
+    }
+
+    /// Formats the transform in the space separated format of the 3MF `transform` attribute.
+    ///
+    /// Each value is written in its shortest form that parses back to the same value, so
+    /// [`Transform::from_spec_str`] returns the same transform. The XML writer uses a fixed
+    /// six decimals instead.
+    pub fn to_spec_str(&self) -> String {
+        self.0
+            .iter()
+            .map(|&m| format!("{}", m))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl TryFrom<String> for Transform {
+    type Error = TransformParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_spec_str(&value)
     }
 }
 
@@ -126,6 +339,238 @@ impl Index<usize> for Transform {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Transform, TransformParseError};
+
+    #[test]
+    #[rustfmt::skip]
+    fn spec_str_roundtrip_test() {
+        let transform = Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10.5 -20 3e1").unwrap();
+        assert_eq!(
+            transform,
+            Transform([
+                1.0, 0.0, 0.0,
+                0.0, 1.0, 0.0,
+                0.0, 0.0, 1.0,
+                10.5, -20.0, 30.0,
+            ])
+        );
+
+        let spec_str = transform.to_spec_str();
+        assert_eq!(spec_str, "1 0 0 0 1 0 0 0 1 10.5 -20 30");
+        assert_eq!(Transform::from_spec_str(&spec_str).unwrap(), transform);
+
+        // values beyond six decimals survive the round trip
+        let transform = Transform([
+            0.1234567891, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            1e-9, 0.0, 0.0,
+        ]);
+        assert_eq!(
+            Transform::from_spec_str(&transform.to_spec_str()).unwrap(),
+            transform
+        );
+    }
+
+    #[test]
+    fn from_spec_str_invalid_test() {
+        assert_eq!(
+            Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10 20"),
+            Err(TransformParseError::WrongValueCount(11))
+        );
+        assert_eq!(
+            Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10 20 30 40"),
+            Err(TransformParseError::WrongValueCount(13))
+        );
+        assert_eq!(
+            Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10 x 30"),
+            Err(TransformParseError::InvalidValue("x".to_owned()))
+        );
+        assert_eq!(
+            Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10 inf 30"),
+            Err(TransformParseError::NonFiniteValue("inf".to_owned()))
+        );
+        assert_eq!(
+            Transform::from_spec_str("1 0 0 0 1 0 0 0 1 10 NaN 30"),
+            Err(TransformParseError::NonFiniteValue("NaN".to_owned()))
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn then_test() {
+        // rotation of 90 degrees around z, then a translation
+        let rotation = Transform([
+            0.0, 1.0, 0.0,
+            -1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0,
+        ]);
+        let translation = Transform([
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            10.0, 20.0, 30.0,
+        ]);
+
+        assert_eq!(rotation.transform_point([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+        assert_eq!(rotation.then(&Transform::IDENTITY), rotation);
+
+        let combined = rotation.then(&translation);
+        assert_eq!(combined.transform_point([1.0, 0.0, 0.0]), [10.0, 21.0, 30.0]);
+
+        let combined = translation.then(&rotation);
+        assert_eq!(combined.transform_point([1.0, 0.0, 0.0]), [-20.0, 11.0, 30.0]);
+
+        assert_eq!(rotation.determinant(), 1.0);
+        let mirror = Transform([
+            -1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0,
+        ]);
+        assert_eq!(mirror.determinant(), -1.0);
+    }
+
+    #[test]
+    fn constructors_test() {
+        assert_eq!(Transform::identity(), Transform::IDENTITY);
+        assert_eq!(
+            Transform::from_translation([1.0, 2.0, 3.0]).transform_point([1.0, 1.0, 1.0]),
+            [2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            Transform::from_scale([2.0, 3.0, -1.0]).transform_point([1.0, 1.0, 1.0]),
+            [2.0, 3.0, -1.0]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_rotation_axis_test() {
+        let assert_point = |point: [f64; 3], expected: [f64; 3]| {
+            for (value, expected) in point.iter().zip(expected) {
+                assert!(
+                    (value - expected).abs() < 1e-12,
+                    "{point:?} != {expected:?}"
+                );
+            }
+        };
+        let quarter = core::f64::consts::FRAC_PI_2;
+        let rotation = Transform::from_rotation_axis([0.0, 0.0, 2.0], quarter);
+        assert_point(rotation.transform_point([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+        assert_point(rotation.transform_point([0.0, 1.0, 0.0]), [-1.0, 0.0, 0.0]);
+        assert!((rotation.determinant() - 1.0).abs() < 1e-12);
+
+        let rotation = Transform::from_rotation_axis([1.0, 0.0, 0.0], quarter);
+        assert_point(rotation.transform_point([0.0, 1.0, 0.0]), [0.0, 0.0, 1.0]);
+
+        // a third turn around the diagonal cycles the axes
+        let rotation =
+            Transform::from_rotation_axis([1.0, 1.0, 1.0], 2.0 * core::f64::consts::FRAC_PI_3);
+        assert_point(rotation.transform_point([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+        assert_point(rotation.transform_point([0.0, 0.0, 1.0]), [1.0, 0.0, 0.0]);
+
+        assert_eq!(
+            Transform::from_rotation_axis([0.0, 0.0, 0.0], quarter),
+            Transform::IDENTITY
+        );
+    }
+
+    #[test]
+    fn multiply_test() {
+        let transform = Transform::from_scale([2.0, 2.0, 2.0])
+            .multiply(&Transform::from_translation([5.0, 6.0, 7.0]));
+        assert_eq!(Transform::identity().multiply(&transform), transform);
+        assert_eq!(transform.multiply(&Transform::identity()), transform);
+
+        let component = Transform::from_translation([10.0, 0.0, 0.0]);
+        let item = Transform::from_scale([2.0, 1.0, 1.0]);
+        assert_eq!(component.multiply(&item), component.then(&item));
+        assert_eq!(
+            component.multiply(&item).apply_to_point([1.0, 2.0, 3.0]),
+            [22.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn then_places_component_in_item_test() {
+        // rotation of 90 degrees around z
+        let rotation = Transform([
+            0.0, 1.0, 0.0,
+            -1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0,
+        ]);
+        let transform = Transform::from_scale([2.0, 2.0, 2.0])
+            .then(&rotation)
+            .then(&Transform::from_translation([5.0, 6.0, 7.0]));
+        assert_eq!(Transform::identity().then(&transform), transform);
+        assert_eq!(transform.then(&Transform::identity()), transform);
+
+        // the component is placed within the object of the item, which the item places
+        // in the build afterwards
+        let component = Transform::from_translation([10.0, 0.0, 0.0]);
+        let item = Transform::from_scale([2.0, 1.0, 1.0]);
+        let point = [1.0, 2.0, 3.0];
+        assert_eq!(
+            component.then(&item).transform_point(point),
+            item.transform_point(component.transform_point(point))
+        );
+        assert_eq!(
+            component.then(&item).transform_point(point),
+            [22.0, 2.0, 3.0]
+        );
+        assert_eq!(
+            item.then(&component).transform_point(point),
+            [12.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn inverse_test() {
+        // rotation around z, non-uniform scale, mirror along y and a translation
+        let transform = Transform([
+            0.0, 2.0, 0.0,
+            3.0, 0.0, 0.0,
+            0.0, 0.0, -0.5,
+            10.0, -20.0, 5.0,
+        ]);
+        let inverse = transform.inverse().unwrap();
+
+        let assert_identity = |t: Transform| {
+            for (value, expected) in t.0.iter().zip(Transform::IDENTITY.0) {
+                assert!((value - expected).abs() < 1e-12, "{t:?} is not the identity");
+            }
+        };
+        assert_identity(transform.then(&inverse));
+        assert_identity(inverse.then(&transform));
+
+        let point = [1.5, -2.0, 4.0];
+        let local = inverse.transform_point(transform.transform_point(point));
+        for (value, expected) in local.iter().zip(point) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+
+        assert_eq!(Transform::IDENTITY.inverse(), Some(Transform::IDENTITY));
+
+        // everything is projected onto the xy plane
+        let flatten = Transform([
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0,
+        ]);
+        assert_eq!(flatten.inverse(), None);
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -238,6 +683,15 @@ mod memory_optimized_read_tests {
             ]) 
         );
     }
+
+    #[test]
+    fn fromxml_test_invalid_transform() {
+        for value in ["", "1 0 0 0 1 0 0 0 1 10 20", "1 0 0 0 1 0 0 0 1 10 x 30"] {
+            let xml_string =
+                format!("<TestTransform><transform>{value}</transform></TestTransform>");
+            assert!(from_str::<TestTransform>(&xml_string).is_err());
+        }
+    }
 }
 
 #[cfg(feature = "speed-optimized-read")]
@@ -297,4 +751,13 @@ mod speed_optimized_read_tests {
             ]) 
         );
     }
+
+    #[test]
+    fn fromxml_test_invalid_transform() {
+        for value in ["", "1 0 0 0 1 0 0 0 1 10 20", "1 0 0 0 1 0 0 0 1 10 x 30"] {
+            let xml_string =
+                format!("<TestTransform><transform>{value}</transform></TestTransform>");
+            assert!(from_str::<TestTransform>(&xml_string).is_err());
+        }
+    }
 }
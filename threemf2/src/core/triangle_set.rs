@@ -7,6 +7,8 @@ use instant_xml::FromXml;
 #[cfg(feature = "speed-optimized-read")]
 use serde::Deserialize;
 
+use alloc::{string::String, vec::Vec};
+
 use crate::threemf_namespaces::CORE_TRIANGLESET_NS;
 
 /// Collection of Triangle Set. See [`TriangleSet`] for more details.
@@ -0,0 +1,8 @@
+//! Conversions of the [`core`](crate::core) types to other file formats, each behind its own
+//! feature:
+//!
+//! - [`stl`] - Binary and ASCII STL export of a [`Mesh`](crate::core::mesh::Mesh), with the
+//!   `stl-export` feature
+
+#[cfg(feature = "stl-export")]
+pub mod stl;
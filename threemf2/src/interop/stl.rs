@@ -0,0 +1,246 @@
+//! Export of meshes to the STL format, for tools that don't read 3MF.
+//!
+//! STL only knows triangles, so the triangle sets, beam lattice and properties of a mesh
+//! are dropped. The facet normals are computed from the vertex positions with the
+//! right-hand rule on `v1`, `v2`, `v3`, the same counter-clockwise winding 3MF uses for
+//! outward facing triangles.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use threemf2::{interop::stl, io::query::*};
+//!
+//! for instance in resolve_mesh_instances(&package)? {
+//!     let mut file = File::create(format!("object-{}.stl", instance.objectid))?;
+//!     stl::write_binary_stl_with_transform(instance.mesh, Some(&instance.transform), &mut file)?;
+//! }
+//! ```
+
+use std::io::{self, Write};
+
+use crate::core::{
+    mesh::{self, Mesh},
+    transform::Transform,
+};
+
+/// Header of the binary STL files, padded with zeros to [`BINARY_HEADER_LENGTH`].
+///
+/// It must not start with `solid`, which would make readers take the file for ASCII STL.
+const BINARY_HEADER: &[u8] = b"binary STL written by threemf2";
+
+/// Length of the header of a binary STL file in bytes.
+const BINARY_HEADER_LENGTH: usize = 80;
+
+/// Name of the solid in ASCII STL files.
+const SOLID_NAME: &str = "mesh";
+
+/// Writes the triangles of `mesh` as binary STL.
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if a triangle references a vertex outside of
+/// the mesh, or with [`io::ErrorKind::InvalidInput`] if the mesh has more triangles than
+/// binary STL can hold.
+pub fn write_binary_stl<W: Write>(mesh: &Mesh, writer: &mut W) -> io::Result<()> {
+    write_binary_stl_with_transform(mesh, None, writer)
+}
+
+/// Same as [`write_binary_stl()`] but applies `transform` to the vertices, e.g. the world
+/// transform of a mesh placed by a build item.
+///
+/// A mirroring transform turns the triangles inside out, so their winding is reversed to
+/// keep the facets facing outwards.
+pub fn write_binary_stl_with_transform<W: Write>(
+    mesh: &Mesh,
+    transform: Option<&Transform>,
+    writer: &mut W,
+) -> io::Result<()> {
+    let count = u32::try_from(mesh.triangles.triangle.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "binary STL holds at most u32::MAX triangles",
+        )
+    })?;
+
+    let mut header = [0u8; BINARY_HEADER_LENGTH];
+    header[..BINARY_HEADER.len()].copy_from_slice(BINARY_HEADER);
+    writer.write_all(&header)?;
+    writer.write_all(&count.to_le_bytes())?;
+
+    for facet in facets(mesh, transform) {
+        let (normal, vertices) = facet?;
+        for value in normal.iter().chain(vertices.iter().flatten()) {
+            writer.write_all(&(*value as f32).to_le_bytes())?;
+        }
+        // attribute byte count, unused
+        writer.write_all(&0u16.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Writes the triangles of `mesh` as ASCII STL.
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if a triangle references a vertex outside of
+/// the mesh.
+pub fn write_ascii_stl<W: Write>(mesh: &Mesh, writer: &mut W) -> io::Result<()> {
+    write_ascii_stl_with_transform(mesh, None, writer)
+}
+
+/// Same as [`write_ascii_stl()`] but applies `transform` to the vertices, see
+/// [`write_binary_stl_with_transform()`].
+pub fn write_ascii_stl_with_transform<W: Write>(
+    mesh: &Mesh,
+    transform: Option<&Transform>,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "solid {SOLID_NAME}")?;
+    for facet in facets(mesh, transform) {
+        let ([nx, ny, nz], vertices) = facet?;
+        writeln!(writer, "  facet normal {nx:e} {ny:e} {nz:e}")?;
+        writeln!(writer, "    outer loop")?;
+        for [x, y, z] in vertices {
+            writeln!(writer, "      vertex {x:e} {y:e} {z:e}")?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid {SOLID_NAME}")
+}
+
+/// The unit normal and the transformed vertices of every triangle of the mesh.
+///
+/// Degenerate triangles get a zero normal.
+fn facets<'a>(
+    mesh: &'a Mesh,
+    transform: Option<&'a Transform>,
+) -> impl Iterator<Item = io::Result<([f64; 3], [[f64; 3]; 3])>> + 'a {
+    let mirrored = transform.is_some_and(|t| t.determinant() < 0.0);
+    let vertex = move |index: usize| {
+        let v = mesh.vertices.vertex.get(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("triangle references vertex {index} outside of the mesh"),
+            )
+        })?;
+        let point = [v.x, v.y, v.z];
+        Ok::<_, io::Error>(transform.map_or(point, |t| t.transform_point(point)))
+    };
+
+    mesh.triangles.triangle.iter().map(move |triangle| {
+        let (v2, v3) = if mirrored {
+            (triangle.v3, triangle.v2)
+        } else {
+            (triangle.v2, triangle.v3)
+        };
+        let [a, b, c] = [vertex(triangle.v1)?, vertex(v2)?, vertex(v3)?];
+
+        let mut normal = mesh::face_normal(a, b, c);
+        let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+        if length > 0.0 {
+            normal.iter_mut().for_each(|n| *n /= length);
+        }
+
+        Ok((normal, [a, b, c]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::test_support::cube;
+
+    /// Reads the normal and vertices of the facets back from binary STL.
+    fn read_binary_facets(bytes: &[u8]) -> Vec<[f32; 12]> {
+        bytes[84..]
+            .chunks(50)
+            .map(|facet| {
+                let mut values = [0.0; 12];
+                for (i, value) in values.iter_mut().enumerate() {
+                    let le = facet[i * 4..i * 4 + 4].try_into().unwrap();
+                    *value = f32::from_le_bytes(le);
+                }
+                values
+            })
+            .collect()
+    }
+
+    #[test]
+    fn write_binary_stl_test() {
+        let mut bytes = vec![];
+        write_binary_stl(&cube(1.0), &mut bytes).unwrap();
+
+        assert_eq!(bytes.len(), 80 + 4 + 12 * 50);
+        assert!(bytes[..80].starts_with(BINARY_HEADER));
+        assert!(!bytes.starts_with(b"solid"));
+        assert_eq!(bytes[80..84], 12u32.to_le_bytes());
+
+        let facets = read_binary_facets(&bytes);
+        assert_eq!(facets.len(), 12);
+        // the bottom faces down, the top faces up
+        assert_eq!(facets[0][..3], [0.0, 0.0, -1.0]);
+        assert_eq!(facets[2][..3], [0.0, 0.0, 1.0]);
+        assert_eq!(
+            facets[0][3..],
+            [0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0]
+        );
+        for facet in bytes[84..].chunks(50) {
+            assert_eq!(facet[48..], [0, 0]);
+        }
+    }
+
+    #[test]
+    fn write_binary_stl_with_transform_test() {
+        let translation = Transform::from_translation([10.0, 0.0, 0.0]);
+        let mut bytes = vec![];
+        write_binary_stl_with_transform(&cube(1.0), Some(&translation), &mut bytes).unwrap();
+        let facets = read_binary_facets(&bytes);
+        assert_eq!(facets[0][..3], [0.0, 0.0, -1.0]);
+        assert_eq!(facets[0][3..6], [10.0, 0.0, 0.0]);
+
+        // mirrored along z the bottom becomes the top, still facing outwards
+        let mirror = Transform::from_scale([1.0, 1.0, -1.0]);
+        let mut bytes = vec![];
+        write_binary_stl_with_transform(&cube(1.0), Some(&mirror), &mut bytes).unwrap();
+        let facets = read_binary_facets(&bytes);
+        assert_eq!(facets[0][..3], [0.0, 0.0, 1.0]);
+        assert_eq!(facets[2][..3], [0.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn write_ascii_stl_test() {
+        let mut bytes = vec![];
+        write_ascii_stl(&cube(1.0), &mut bytes).unwrap();
+        let stl = String::from_utf8(bytes).unwrap();
+
+        assert!(stl.starts_with("solid mesh\n"));
+        assert!(stl.ends_with("endsolid mesh\n"));
+        assert_eq!(stl.matches("facet normal").count(), 12);
+        assert_eq!(stl.matches("vertex").count(), 36);
+
+        let first_facet = stl.lines().skip(1).take(7).collect::<Vec<_>>();
+        assert_eq!(
+            first_facet,
+            vec![
+                "  facet normal 0e0 0e0 -1e0",
+                "    outer loop",
+                "      vertex 0e0 0e0 0e0",
+                "      vertex 0e0 1e0 0e0",
+                "      vertex 1e0 0e0 0e0",
+                "    endloop",
+                "  endfacet",
+            ]
+        );
+    }
+
+    #[test]
+    fn write_stl_invalid_vertex_test() {
+        let mut mesh = cube(1.0);
+        mesh.triangles.triangle[3].v2 = 8;
+
+        let error = write_binary_stl(&mesh, &mut vec![]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = write_ascii_stl(&mesh, &mut vec![]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
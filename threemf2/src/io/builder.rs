@@ -11,9 +11,16 @@
 //! - [`ModelBuilder`] - Main entry point for creating 3MF models (root or sub-models)
 //! - [`MeshObjectBuilder`] - Creates objects with triangle mesh geometry
 //! - [`ComponentsObjectBuilder`] - Creates assembly objects that reference other objects
+//! - [`AssemblyBuilder`] - Creates an assembly together with the mesh objects it places
 //! - [`BeamLatticeBuilder`] - Adds beam lattice structures to meshes
 //! - [`BuildBuilder`] - Configures the build section (what gets printed)
+//! - [`BaseMaterialsBuilder`] - Defines a group of base materials referenced by objects
 //! - [`TriangleSetsBuilder`] - Organizes triangles into named groups
+//! - [`PackageBuilder`] - Assembles models and thumbnails into a [`ThreemfPackage`]
+//!
+//! The geometry builders, e.g. [`MeshBuilder`] and [`BeamLatticeBuilder`], are defined in
+//! [`core::builder`](crate::core::builder) so they can be used without the io features,
+//! and are re-exported here.
 //!
 //! # Basic Usage
 //!
@@ -85,17 +92,21 @@ use thiserror::Error;
 
 use crate::{
     core::{
-        beamlattice::{Ball, BallRef, Balls, Beam, BeamLattice, BeamRef, BeamSet, BeamSets, Beams},
         build::{Build, Item},
         component::{Component, Components},
-        mesh::{Mesh, Triangle, Triangles, Vertex, Vertices},
+        mesh::Mesh,
         metadata::Metadata,
         model::Model,
         object::Object,
-        resources::Resources,
+        resources::{Base, BaseMaterials, Resources},
         transform::Transform,
     },
-    io::XmlNamespace,
+    io::{
+        ThreemfPackage, XmlNamespace,
+        content_types::{ContentTypes, DefaultContentTypeEnum, DefaultContentTypes},
+        relationship::{Relationship, RelationshipType, Relationships},
+        thumbnail_handle::{ImageFormat, ThumbnailHandle},
+    },
     threemf_namespaces::{
         self, BEAM_LATTICE_BALLS_NS, BEAM_LATTICE_BALLS_PREFIX, BEAM_LATTICE_NS,
         BEAM_LATTICE_PREFIX, PROD_NS, PROD_PREFIX,
@@ -103,14 +114,26 @@ use crate::{
 };
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
 };
 
-pub use crate::core::beamlattice::{BallMode, CapMode, ClippingMode};
+pub use crate::core::beamlattice::{
+    BallMode, BeamLatticeError, CapMode, ClippingMode, LatticeElementKind,
+};
+pub use crate::core::builder::{
+    BallBuilder, BeamBuilder, BeamLatticeBuilder, BeamSetBuilder, MeshBuilder, MeshError, ObjectId,
+    PropertyGroupId, SetId, TriangleSetsBuilder,
+};
 pub use crate::core::model::Unit;
 pub use crate::core::object::ObjectType;
 
+/// The largest resource id allowed by the 3MF Core specification.
+const MAX_OBJECT_ID: usize = i32::MAX as usize;
+
+/// Object count above which [`BuildWarning::ConsiderProductionExtension`] is reported.
+const LARGE_MODEL_OBJECT_COUNT: usize = 100;
+
 /// Errors that can occur when building a [`Model`].
 ///
 /// These errors are returned from [`ModelBuilder::build()`] and related methods
@@ -138,6 +161,47 @@ pub enum ModelError {
     /// Error occurred while building a build item.
     #[error("Something wrong when adding Items")]
     ItemError(#[from] ItemError),
+
+    /// The requested object id leaves no room for the following id.
+    ///
+    /// See [`ModelBuilder::set_next_object_id()`].
+    #[error("Object id {0} is too large, the next object id would overflow")]
+    ObjectIdOverflow(usize),
+
+    /// An object without any geometry was added while empty objects are rejected.
+    ///
+    /// See [`ModelBuilder::reject_empty_objects()`].
+    #[error("Object {0} has no geometry")]
+    EmptyObject(usize),
+}
+
+/// Errors that can occur when adding base materials with
+/// [`ModelBuilder::add_base_materials()`].
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum BaseMaterialsError {
+    /// The group has no materials, the 3MF Core specification requires at least one.
+    #[error("Base materials must contain at least one material")]
+    NoMaterials,
+
+    /// The display color of a material is not an sRGB hex color, `#RRGGBB` or `#RRGGBBAA`.
+    #[error("Display color {color} of material {name} is not a valid sRGB color")]
+    InvalidDisplayColor { name: String, color: String },
+}
+
+/// Advisory findings reported by [`ModelBuilder::build_with_warnings()`].
+///
+/// Unlike [`ModelError`], warnings never fail the build, the model is valid as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildWarning {
+    /// The model has more than 100 objects but the Production extension is not used.
+    ///
+    /// Large multi-object models usually rely on the Production extension to split the
+    /// objects across model parts and to track them by UUID.
+    /// See [`ModelBuilder::make_production_extension_required()`].
+    ConsiderProductionExtension {
+        /// The number of objects in the model.
+        object_count: usize,
+    },
 }
 
 /// Errors related to the 3MF Production extension.
@@ -301,6 +365,9 @@ pub struct ModelBuilder {
     // tracks if the model requires production ext
     // ensures UUID is set at the minimum
     is_production_ext_required: bool,
+
+    // tracks if objects without geometry fail the build
+    reject_empty_objects: bool,
 }
 
 impl ModelBuilder {
@@ -333,12 +400,15 @@ impl ModelBuilder {
             is_root,
             next_object_id: 1.into(),
             is_production_ext_required: false,
+            reject_empty_objects: false,
         }
     }
 
     /// Set the unit of measurement for the model.
     ///
     /// This can be called multiple times; the last value set will be used.
+    /// The beam lattice defaults of mesh objects are expressed in the unit set
+    /// when the object is added.
     pub fn unit(&mut self, unit: Unit) -> &mut Self {
         self.unit = Some(unit);
         self
@@ -353,6 +423,32 @@ impl ModelBuilder {
         self
     }
 
+    /// Make [`build()`](ModelBuilder::build) fail with [`ModelError::EmptyObject`] if any
+    /// object has no geometry (see [`Object::is_empty()`]). Objects of type
+    /// [`ObjectType::Other`] are not checked. Off by default.
+    pub fn reject_empty_objects(&mut self, reject: bool) -> &mut Self {
+        self.reject_empty_objects = reject;
+        self
+    }
+
+    /// Set the [`ObjectId`] assigned to the next object added to the model.
+    ///
+    /// Subsequent objects are numbered consecutively from this id. This should be called
+    /// before adding objects, since ids already handed out are not checked for collisions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError::ObjectIdOverflow`] if `id + 1` does not fit in the 3MF resource
+    /// id range (a signed 32-bit integer), so the id sequence could overflow on 32-bit targets.
+    pub fn set_next_object_id(&mut self, id: usize) -> Result<&mut Self, ModelError> {
+        if id >= MAX_OBJECT_ID {
+            return Err(ModelError::ObjectIdOverflow(id));
+        }
+
+        self.next_object_id = ObjectId(id);
+        Ok(self)
+    }
+
     /// Enable the 3MF Production extension and enforce UUID requirements.
     ///
     /// When the Production extension is enabled:
@@ -452,6 +548,40 @@ impl ModelBuilder {
         self
     }
 
+    /// Add metadata like [`ModelBuilder::add_metadata`], unless the model already has
+    /// metadata with the same `name`.
+    pub fn add_metadata_if_absent(&mut self, name: &str, value: Option<&str>) -> &mut Self {
+        if !self.metadata.iter().any(|m| m.name == name) {
+            self.add_metadata(name, value);
+        }
+        self
+    }
+
+    /// Set the value of the metadata with the given `name`, adding it if the model has none.
+    ///
+    /// The first metadata with the name is replaced and any further entries with the same
+    /// name are removed, so the model ends up with exactly one entry for `name`.
+    pub fn set_metadata(&mut self, name: &str, value: Option<&str>) -> &mut Self {
+        let mut replaced = false;
+        self.metadata.retain_mut(|m| {
+            if m.name != name {
+                return true;
+            }
+            if replaced {
+                return false;
+            }
+            m.preserve = None;
+            m.value = value.map(|v| v.to_owned());
+            replaced = true;
+            true
+        });
+
+        if !replaced {
+            self.add_metadata(name, value);
+        }
+        self
+    }
+
     /// Add a mesh object to the model using a builder closure.
     ///
     /// The object is automatically assigned a unique [`ObjectId`] which is returned.
@@ -489,7 +619,11 @@ impl ModelBuilder {
     {
         let id = self.next_object_id;
 
-        let mut obj_builder = MeshObjectBuilder::new(id, self.is_production_ext_required);
+        let mut obj_builder = MeshObjectBuilder::new(
+            id,
+            self.is_production_ext_required,
+            self.unit.clone().unwrap_or_default(),
+        );
         f(&mut obj_builder)?;
 
         self.add_mesh_object_from_builder(obj_builder)
@@ -594,6 +728,126 @@ impl ModelBuilder {
         Ok(id)
     }
 
+    /// Add an assembly whose parts are created inline, see [`AssemblyBuilder`].
+    ///
+    /// Every mesh created with [`AssemblyBuilder::add_inline_mesh()`] becomes a regular mesh
+    /// object of the model and gets the next free [`ObjectId`]. The assembly itself is a
+    /// components object added after all of its inline meshes, so it gets the id following
+    /// the last one and the meshes always precede it in the resources. Building an assembly
+    /// with two inline meshes in an empty model results in mesh objects `1` and `2` followed
+    /// by the components object `3` referencing them.
+    ///
+    /// If the closure or the validation of the assembly fails, the inline meshes are removed
+    /// again and the model is left as it was before the call.
+    ///
+    /// # Returns
+    ///
+    /// The auto-assigned [`ObjectId`] of the assembly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let assembly_id = builder.add_assembly(|asm| {
+    ///     asm.name("Assembly");
+    ///
+    ///     let part = asm.add_inline_mesh(|obj| {
+    ///         obj.add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0]]);
+    ///         obj.add_triangles(&[[0, 1, 2]]);
+    ///         Ok(())
+    ///     })?;
+    ///
+    ///     asm.place(part, Transform::IDENTITY);
+    ///     asm.place(part, Transform([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 20.0, 0.0, 0.0]));
+    ///
+    ///     Ok(())
+    /// })?;
+    /// ```
+    pub fn add_assembly<F>(&mut self, f: F) -> Result<ObjectId, AssemblyError>
+    where
+        F: FnOnce(&mut AssemblyBuilder<'_>) -> Result<(), AssemblyError>,
+    {
+        let object_count = self.resources.objects.len();
+        let next_object_id = self.next_object_id;
+        let recommendedextensions = self.recommendedextensions.clone();
+
+        let all_object_ids = self
+            .resources
+            .objects
+            .iter()
+            .map(|o| ObjectId(o.id))
+            .collect::<Vec<_>>();
+        let object = ComponentsObjectBuilder::new(
+            next_object_id,
+            &all_object_ids,
+            self.is_production_ext_required,
+        );
+
+        let mut assembly = AssemblyBuilder {
+            model: self,
+            object,
+        };
+        let result = f(&mut assembly);
+        let mut object = assembly.object;
+
+        // the assembly follows its inline meshes
+        object.object_id = self.next_object_id;
+        let result = result.and_then(|_| {
+            self.add_composed_part_object_from_builder(object)
+                .map_err(AssemblyError::from)
+        });
+
+        if result.is_err() {
+            self.resources.objects.truncate(object_count);
+            self.next_object_id = next_object_id;
+            self.recommendedextensions = recommendedextensions;
+        }
+
+        result
+    }
+
+    /// Add a group of base materials to the model using a builder closure.
+    ///
+    /// The group shares the id sequence of the objects, since the ids of all resources of
+    /// a model must be unique, and is assigned the next free id which is returned. Use it
+    /// as the `pid` of objects, see [`MeshObjectBuilder::pid()`], or of triangles, see
+    /// [`MeshBuilder::add_triangle_with_properties()`]. The materials are referenced by their
+    /// 0-based index in the order they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BaseMaterialsError::NoMaterials`] if no material was added and
+    /// [`BaseMaterialsError::InvalidDisplayColor`] if a display color is not an sRGB hex
+    /// color, `#RRGGBB` or `#RRGGBBAA`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let materials = builder.add_base_materials(|m| {
+    ///     m.add_material("Red", "#FF0000FF")
+    ///         .add_material("Blue", "#0000FF");
+    /// })?;
+    ///
+    /// let cube_id = builder.add_mesh_object(|obj| {
+    ///     obj.pid(materials).pindex(1); // Blue
+    ///     // ... add geometry
+    ///     Ok(())
+    /// })?;
+    /// ```
+    pub fn add_base_materials<F>(&mut self, f: F) -> Result<PropertyGroupId, BaseMaterialsError>
+    where
+        F: FnOnce(&mut BaseMaterialsBuilder),
+    {
+        let id = self.next_object_id.0;
+        let mut materials_builder = BaseMaterialsBuilder::new();
+        f(&mut materials_builder);
+
+        let materials = materials_builder.build(id)?;
+        self.resources.basematerials.push(materials);
+        self.next_object_id = ObjectId(id + 1);
+
+        Ok(PropertyGroupId(id))
+    }
+
     /// Add a Build section to the model.
     ///
     /// The Build section specifies which objects should be manufactured (printed).
@@ -733,6 +987,16 @@ impl ModelBuilder {
             return Err(ModelError::BuildOnlyAllowedInRootModel);
         }
 
+        if self.reject_empty_objects
+            && let Some(object) = self
+                .resources
+                .objects
+                .iter()
+                .find(|o| o.is_empty() && o.objecttype != Some(ObjectType::Other))
+        {
+            return Err(ModelError::EmptyObject(object.id));
+        }
+
         let build = if let Some(builder) = self.build {
             builder.build(self.is_production_ext_required)?
         } else {
@@ -752,13 +1016,71 @@ impl ModelBuilder {
         })
     }
 
+    /// Build the final [`Model`] together with the [`BuildWarning`]s found in it.
+    ///
+    /// Performs the same validation as [`ModelBuilder::build()`], the warnings are purely
+    /// advisory and never fail the build.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if validation fails, see [`ModelBuilder::build()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let (model, warnings) = builder.build_with_warnings()?;
+    /// for warning in warnings {
+    ///     println!("{warning:?}");
+    /// }
+    /// ```
+    pub fn build_with_warnings(self) -> Result<(Model, Vec<BuildWarning>), ModelError> {
+        let mut warnings = vec![];
+
+        let object_count = self.resources.objects.len();
+        let uses_production_extension = self.is_production_ext_required
+            || self
+                .requiredextensions
+                .iter()
+                .chain(&self.recommendedextensions)
+                .any(|ns| ns.uri == PROD_NS);
+        if object_count > LARGE_MODEL_OBJECT_COUNT && !uses_production_extension {
+            warnings.push(BuildWarning::ConsiderProductionExtension { object_count });
+        }
+
+        self.build().map(|model| (model, warnings))
+    }
+
+    /// Build the final [`Model`] and wrap it as the root model of a new [`ThreemfPackage`].
+    ///
+    /// The package gets the canonical content types and the root relationship pointing
+    /// to `/3D/3dmodel.model`, see [`ThreemfPackage::from_root_model()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if validation fails, see [`ModelBuilder::build()`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let package = builder.into_package()?;
+    /// package.write(writer)?;
+    /// ```
+    pub fn into_package(self) -> Result<ThreemfPackage, ModelError> {
+        self.build().map(ThreemfPackage::from)
+    }
+
     fn set_recommended_namespaces_for_mesh(&mut self, mesh: &Mesh) {
         use threemf_namespaces::{CORE_TRIANGLESET_NS, CORE_TRIANGLESET_PREFIX};
-        if mesh.trianglesets.is_some()
+        // empty triangle sets are not written, so they don't need the extension
+        let has_triangle_sets = mesh
+            .trianglesets
+            .as_ref()
+            .is_some_and(|sets| !sets.trianglesets.is_empty());
+        if has_triangle_sets
             && self
                 .recommendedextensions
                 .iter()
-                .all(|ns| ns.uri == CORE_TRIANGLESET_NS)
+                .all(|ns| ns.uri != CORE_TRIANGLESET_NS)
         {
             self.recommendedextensions.push(XmlNamespace {
                 prefix: Some(CORE_TRIANGLESET_PREFIX.to_owned()),
@@ -855,23 +1177,75 @@ fn get_extensions_definition(extensions: &[XmlNamespace]) -> Option<String> {
 /// Builder for Resources
 pub struct ResourcesBuilder {
     objects: Vec<Object>,
+    basematerials: Vec<BaseMaterials>,
 }
 
 impl ResourcesBuilder {
     fn new() -> Self {
         Self {
             objects: Vec::new(),
+            basematerials: Vec::new(),
         }
     }
 
     fn build(self) -> Resources {
         Resources {
+            unknown_resources: Vec::new(),
             object: self.objects,
-            basematerials: Vec::new(),
+            basematerials: self.basematerials,
+        }
+    }
+}
+
+/// Builder for a group of base materials.
+///
+/// Created by [`ModelBuilder::add_base_materials()`], which assigns the id of the group.
+pub struct BaseMaterialsBuilder {
+    bases: Vec<Base>,
+}
+
+impl BaseMaterialsBuilder {
+    fn new() -> Self {
+        Self { bases: Vec::new() }
+    }
+
+    /// Add a material with the given name and sRGB display color, `#RRGGBB` or `#RRGGBBAA`.
+    ///
+    /// The display color is validated when the group is added to the model.
+    pub fn add_material(&mut self, name: &str, displaycolor: &str) -> &mut Self {
+        self.bases.push(Base {
+            name: name.to_owned(),
+            displaycolor: displaycolor.to_owned(),
+        });
+        self
+    }
+
+    fn build(self, id: usize) -> Result<BaseMaterials, BaseMaterialsError> {
+        if self.bases.is_empty() {
+            return Err(BaseMaterialsError::NoMaterials);
+        }
+
+        if let Some(base) = self.bases.iter().find(|b| !is_srgb_color(&b.displaycolor)) {
+            return Err(BaseMaterialsError::InvalidDisplayColor {
+                name: base.name.clone(),
+                color: base.displaycolor.clone(),
+            });
         }
+
+        Ok(BaseMaterials {
+            id,
+            base: self.bases,
+        })
     }
 }
 
+/// Returns true if `color` is an sRGB hex color, `#RRGGBB` or `#RRGGBBAA`.
+fn is_srgb_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 6 | 8) && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
 /// Errors that can occur when building the Build section.
 #[derive(Debug, Error, Clone, Copy)]
 pub enum BuildError {
@@ -1053,22 +1427,6 @@ impl ItemBuilder {
     }
 }
 
-/// Type-safe wrapper for object IDs to prevent mix-ups
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct ObjectId(usize);
-
-impl From<usize> for ObjectId {
-    fn from(id: usize) -> Self {
-        ObjectId(id)
-    }
-}
-
-impl From<ObjectId> for usize {
-    fn from(id: ObjectId) -> usize {
-        id.0
-    }
-}
-
 /// Builder for Object
 pub struct ObjectBuilder<T> {
     entity: T,
@@ -1088,6 +1446,9 @@ pub struct ObjectBuilder<T> {
 
 impl<T> ObjectBuilder<T> {
     /// Set the object type
+    ///
+    /// Meshes of [`ObjectType::Other`] objects may contain degenerate triangles, see
+    /// [`MeshBuilder::allow_degenerate_triangles()`].
     pub fn object_type(&mut self, object_type: ObjectType) -> &mut Self {
         self.objecttype = Some(object_type);
         self
@@ -1099,6 +1460,15 @@ impl<T> ObjectBuilder<T> {
         self
     }
 
+    /// Set the path of the object thumbnail within the package.
+    ///
+    /// The thumbnail itself must be added to the package thumbnails, otherwise writing
+    /// the package fails.
+    pub fn thumbnail(&mut self, path: &str) -> &mut Self {
+        self.thumbnail = Some(path.to_owned());
+        self
+    }
+
     /// Set the part number
     pub fn part_number(&mut self, part_number: &str) -> &mut Self {
         self.partnumber = Some(part_number.to_owned());
@@ -1133,6 +1503,44 @@ pub enum MeshObjectError {
     /// Call [`MeshObjectBuilder::uuid()`] to set the UUID.
     #[error("Production extension is enabled but Uuid is not set!")]
     ObjectUuidNotSet,
+
+    /// A triangle references the same vertex more than once, e.g. `v1 == v2`.
+    ///
+    /// Remove the triangle with [`MeshBuilder::remove_degenerate_triangles()`] or keep it
+    /// with [`MeshBuilder::allow_degenerate_triangles()`].
+    #[error("Triangle {triangle} references the same vertex more than once")]
+    DegenerateTriangleIndices { triangle: usize },
+
+    /// The mesh has triangles but no vertices for them to reference.
+    #[error("Mesh has triangles but no vertices")]
+    TrianglesWithoutVertices,
+
+    /// A triangle references the vertex `index`, which was not added.
+    ///
+    /// Skip the check with [`MeshBuilder::skip_validation()`] if the indices are known to
+    /// be valid.
+    #[error("Triangle {triangle} references vertex {index}, which does not exist")]
+    TriangleIndexOutOfBounds { triangle: usize, index: usize },
+
+    /// The beam lattice of the mesh is invalid, e.g. a beam references a vertex that
+    /// was not added.
+    #[error("Invalid beam lattice: {0}")]
+    BeamLattice(BeamLatticeError),
+}
+
+impl From<MeshError> for MeshObjectError {
+    fn from(err: MeshError) -> Self {
+        match err {
+            MeshError::DegenerateTriangleIndices { triangle } => {
+                Self::DegenerateTriangleIndices { triangle }
+            }
+            MeshError::TrianglesWithoutVertices => Self::TrianglesWithoutVertices,
+            MeshError::TriangleIndexOutOfBounds { triangle, index } => {
+                Self::TriangleIndexOutOfBounds { triangle, index }
+            }
+            MeshError::BeamLattice(err) => Self::BeamLattice(err),
+        }
+    }
 }
 
 /// Builder for creating mesh objects with triangle geometry.
@@ -1168,9 +1576,9 @@ pub enum MeshObjectError {
 pub type MeshObjectBuilder = ObjectBuilder<MeshBuilder>;
 
 impl MeshObjectBuilder {
-    fn new(object_id: ObjectId, is_production_ext_required: bool) -> Self {
+    fn new(object_id: ObjectId, is_production_ext_required: bool, unit: Unit) -> Self {
         Self {
-            entity: MeshBuilder::new(),
+            entity: MeshBuilder::with_unit(unit),
             object_id,
             objecttype: Some(ObjectType::Model),
             thumbnail: None,
@@ -1183,8 +1591,26 @@ impl MeshObjectBuilder {
         }
     }
 
+    /// Set the property group providing the default properties of the object, e.g. the
+    /// id returned by [`ModelBuilder::add_base_materials()`].
+    pub fn pid(&mut self, pid: PropertyGroupId) -> &mut Self {
+        self.pid = Some(pid.0);
+        self
+    }
+
+    /// Set the index of the default property within the group set with
+    /// [`MeshObjectBuilder::pid()`].
+    pub fn pindex(&mut self, pindex: usize) -> &mut Self {
+        self.pindex = Some(pindex);
+        self
+    }
+
     fn build(self) -> Result<Object, MeshObjectError> {
-        let mesh = self.entity.build_mesh().unwrap();
+        let mut entity = self.entity;
+        if self.objecttype == Some(ObjectType::Other) {
+            entity.allow_degenerate_triangles(true);
+        }
+        let mesh = entity.build()?;
 
         if self.is_production_ext_required && self.uuid.is_none() {
             return Err(MeshObjectError::ObjectUuidNotSet);
@@ -1205,354 +1631,85 @@ impl MeshObjectBuilder {
     }
 }
 
-/// Builder for constructing triangle mesh geometry.
+/// Errors that can occur when building a components (assembly) object.
+#[derive(Debug, Error, Clone)]
+pub enum ComponentsObjectError {
+    /// Component is missing a UUID when Production extension is required.
+    ///
+    /// Call [`ComponentBuilder::uuid()`] when configuring components.
+    #[error("production extension is enabled but uuid is not set")]
+    ComponentUuidNotSet,
+
+    /// Component has a path set but Production extension is not enabled.
+    ///
+    /// Call [`ModelBuilder::make_production_extension_required()`] before setting paths.
+    #[error("Path is set for a Component without enabling the Production extension")]
+    PathSetWithoutProductionExtension,
+
+    /// Component references an object ID that doesn't exist.
+    ///
+    /// Ensure the referenced object has been added to the model before creating
+    /// components that reference it. Components with a path are not checked.
+    #[error("One or more Component References unknown objects")]
+    ObjectReferenceNotFoundForComponent,
+
+    /// Object is missing a UUID when Production extension is required.
+    ///
+    /// Call [`ComponentsObjectBuilder::uuid()`] to set the UUID.
+    #[error("Production extension is enabled but Uuid is not set")]
+    ObjectUuidNotSet,
+}
+
+/// Builder for creating assembly objects that reference other objects.
+///
+/// `ComponentsObjectBuilder` creates objects that don't have their own geometry,
+/// but instead reference other objects (mesh objects or other assemblies) as components.
+/// Each component can have its own transform.
 ///
-/// `MeshBuilder` allows you to define 3D geometry by adding vertices and triangles.
-/// It also supports optional features like triangle sets and beam lattices.
+/// This is useful for:
+/// - Creating assemblies of multiple parts
+/// - Building hierarchical model structures
 ///
-/// Vertices are referenced by their 0-based index in the order they were added.
-/// Triangles reference vertices by index.
+/// Access component-building methods directly via [`Deref`] to [`ComponentsBuilder`].
 ///
 /// # Examples
 ///
 /// ```rust,ignore
-/// obj.add_vertices(&[
-///     [0.0, 0.0, 0.0],   // vertex 0
-///     [10.0, 0.0, 0.0],  // vertex 1
-///     [10.0, 10.0, 0.0], // vertex 2
-/// ]);
+/// // First create a mesh object to reference
+/// let bolt_id = builder.add_mesh_object(|obj| {
+///     obj.name("Bolt");
+///     // ... add geometry
+///     Ok(())
+/// })?;
+///
+/// // Create an assembly referencing the bolt multiple times
+/// let assembly_id = builder.add_components_object(|obj| {
+///     obj.name("BoltAssembly");
+///
+///     // Add first bolt
+///     obj.add_component(bolt_id);
+///
+///     // Add second bolt with transform
+///     obj.add_component_advanced(bolt_id, |comp| {
+///         comp.transform(Transform([
+///             1.0, 0.0, 0.0,
+///             0.0, 1.0, 0.0,
+///             0.0, 0.0, 1.0,
+///             10.0, 0.0, 0.0  // Translated 10mm in X
+///         ]));
+///     });
 ///
-/// obj.add_triangles(&[
-///     [0, 1, 2],  // Triangle using vertices 0, 1, 2
-/// ]);
+///     Ok(())
+/// })?;
 /// ```
-pub struct MeshBuilder {
-    vertices: Vec<Vertex>,
-    triangles: Vec<Triangle>,
-    triangle_sets: Option<TriangleSetsBuilder>,
-    beam_lattice: Option<BeamLatticeBuilder>,
-}
+pub type ComponentsObjectBuilder = ObjectBuilder<ComponentsBuilder>;
 
-impl MeshBuilder {
-    fn new() -> Self {
-        Self {
-            vertices: Vec::new(),
-            triangles: Vec::new(),
-            triangle_sets: None,
-            beam_lattice: None,
-        }
-    }
-
-    /// Add a single vertex at the specified coordinates.
-    ///
-    /// Returns the builder for method chaining.
-    ///
-    /// # Parameters
-    ///
-    /// - `coords`: 3D coordinates as `[x, y, z]`
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_vertex(&[0.0, 0.0, 0.0])
-    ///    .add_vertex(&[10.0, 0.0, 0.0])
-    ///    .add_vertex(&[0.0, 10.0, 0.0]);
-    /// ```
-    pub fn add_vertex(&mut self, coords: &[f64; 3]) -> &mut Self {
-        self.vertices.push(Vertex {
-            x: coords[0],
-            y: coords[1],
-            z: coords[2],
-        });
-        self
-    }
-
-    /// Add multiple vertices from a slice of coordinate arrays.
-    ///
-    /// Each element should be a 3D coordinate `[x, y, z]`.
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_vertices(&[
-    ///     [0.0, 0.0, 0.0],
-    ///     [10.0, 0.0, 0.0],
-    ///     [10.0, 10.0, 0.0],
-    /// ]);
-    /// ```
-    pub fn add_vertices(&mut self, vertices: &[[f64; 3]]) -> &mut Self {
-        for vertex in vertices {
-            self.add_vertex(vertex);
-        }
-
-        self
-    }
-
-    /// Add vertices from a flattened coordinate array.
-    ///
-    /// The slice should contain coordinate values in sequence: `[x0, y0, z0, x1, y1, z1, ...]`.
-    /// The length must be a multiple of 3.
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_vertices_flat(&[
-    ///     0.0, 0.0, 0.0,     // vertex 0
-    ///     10.0, 0.0, 0.0,    // vertex 1
-    ///     10.0, 10.0, 0.0,   // vertex 2
-    /// ]);
-    /// ```
-    pub fn add_vertices_flat(&mut self, vertices: &[f64]) -> &mut Self {
-        for vertex in vertices.chunks_exact(3) {
-            self.vertices.push(Vertex {
-                x: vertex[0],
-                y: vertex[1],
-                z: vertex[2],
-            });
-        }
-
-        self
-    }
-
-    /// Add a single triangle referencing three vertices by index.
-    ///
-    /// Vertices are referenced by their 0-based index in the order they were added.
-    /// Indices must reference existing vertices.
-    ///
-    /// # Parameters
-    ///
-    /// - `indices`: Triangle vertex indices as `[v1, v2, v3]`
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_vertex(&[0.0, 0.0, 0.0]);    // index 0
-    /// obj.add_vertex(&[10.0, 0.0, 0.0]);   // index 1
-    /// obj.add_vertex(&[0.0, 10.0, 0.0]);   // index 2
-    /// obj.add_triangle(&[0, 1, 2]);
-    /// ```
-    pub fn add_triangle(&mut self, indices: &[usize; 3]) -> &mut Self {
-        self.triangles.push(Triangle {
-            v1: indices[0],
-            v2: indices[1],
-            v3: indices[2],
-            p1: None,
-            p2: None,
-            p3: None,
-            pid: None,
-        });
-        self
-    }
-
-    /// Add multiple triangles from a slice of index arrays.
-    ///
-    /// Each element should be a triangle with three vertex indices.
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_triangles(&[
-    ///     [0, 1, 2],
-    ///     [0, 2, 3],
-    ///     [0, 3, 4],
-    /// ]);
-    /// ```
-    pub fn add_triangles(&mut self, triangles: &[[usize; 3]]) -> &mut Self {
-        for triangle in triangles {
-            self.add_triangle(triangle);
-        }
-
-        self
-    }
-
-    /// Add triangles from a flattened index array.
-    ///
-    /// The slice should contain vertex indices in sequence: `[v1, v2, v3, v1, v2, v3, ...]`.
-    /// The length must be a multiple of 3.
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_triangles_flat(&[
-    ///     0, 1, 2,  // triangle 0
-    ///     0, 2, 3,  // triangle 1
-    /// ]);
-    /// ```
-    pub fn add_triangles_flat(&mut self, triangles: &[usize]) -> &mut Self {
-        for triangle in triangles.chunks_exact(3) {
-            self.triangles.push(Triangle {
-                v1: triangle[0],
-                v2: triangle[1],
-                v3: triangle[2],
-                p1: None,
-                p2: None,
-                p3: None,
-                pid: None,
-            });
-        }
-
-        self
-    }
-
-    /// Add triangle sets to organize triangles into named groups.
-    ///
-    /// Triangle sets allow you to group triangles by name and identifier for
-    /// organizational purposes. See [`TriangleSetsBuilder`] for details.
-    ///
-    /// # Parameters
-    ///
-    /// - `f`: A closure that configures the [`TriangleSetsBuilder`]
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_triangle_sets(|sets| {
-    ///     sets.add_set("TopFace", "top-id", &[0, 1], &[]);
-    ///     sets.add_set("BottomFace", "bottom-id", &[2, 3], &[]);
-    /// });
-    /// ```
-    pub fn add_triangle_sets<F>(&mut self, f: F) -> &mut Self
-    where
-        F: FnOnce(&mut TriangleSetsBuilder),
-    {
-        if let Some(ref mut builder) = self.triangle_sets {
-            f(builder);
-        } else {
-            let mut builder = TriangleSetsBuilder::new();
-            f(&mut builder);
-            self.triangle_sets = Some(builder);
-        }
-
-        self
-    }
-
-    /// Add a beam lattice structure to the mesh.
-    ///
-    /// Beam lattices define strut-like structures connecting vertices. See
-    /// [`BeamLatticeBuilder`] for details.
-    ///
-    /// # Parameters
-    ///
-    /// - `f`: A closure that configures the [`BeamLatticeBuilder`]
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// obj.add_beam_lattice(|lattice| {
-    ///     lattice.radius(0.5)
-    ///            .add_beam(0, 1)
-    ///            .add_beam(1, 2);
-    /// });
-    /// ```
-    pub fn add_beam_lattice<F>(&mut self, f: F) -> &mut Self
-    where
-        F: FnOnce(&mut BeamLatticeBuilder),
-    {
-        if let Some(builder) = &mut self.beam_lattice {
-            f(builder);
-        } else {
-            let mut builder = BeamLatticeBuilder::new();
-            f(&mut builder);
-            self.beam_lattice = Some(builder);
-        }
-        self
-    }
-
-    fn build_mesh(self) -> Result<Mesh, MeshObjectError> {
-        let trianglesets = self.triangle_sets.map(|b| b.build());
-        let beamlattice = self.beam_lattice.map(|b| b.build());
-        Ok(Mesh {
-            vertices: Vertices {
-                vertex: self.vertices,
-            },
-            triangles: Triangles {
-                triangle: self.triangles,
-            },
-            trianglesets,
-            beamlattice,
-        })
-    }
-}
-
-/// Errors that can occur when building a components (assembly) object.
-#[derive(Debug, Error, Clone)]
-pub enum ComponentsObjectError {
-    /// Component is missing a UUID when Production extension is required.
-    ///
-    /// Call [`ComponentBuilder::uuid()`] when configuring components.
-    #[error("production extension is enabled but uuid is not set")]
-    ComponentUuidNotSet,
-
-    /// Component has a path set but Production extension is not enabled.
-    ///
-    /// Call [`ModelBuilder::make_production_extension_required()`] before setting paths.
-    #[error("Path is set for a Component without enabling the Production extension")]
-    PathSetWithoutProductionExtension,
-
-    /// Component references an object ID that doesn't exist.
-    ///
-    /// Ensure the referenced object has been added to the model before creating
-    /// components that reference it.
-    #[error("One or more Component References unknown objects")]
-    ObjectReferenceNotFoundForComponent,
-
-    /// Object is missing a UUID when Production extension is required.
-    ///
-    /// Call [`ComponentsObjectBuilder::uuid()`] to set the UUID.
-    #[error("Production extension is enabled but Uuid is not set")]
-    ObjectUuidNotSet,
-}
-
-/// Builder for creating assembly objects that reference other objects.
-///
-/// `ComponentsObjectBuilder` creates objects that don't have their own geometry,
-/// but instead reference other objects (mesh objects or other assemblies) as components.
-/// Each component can have its own transform.
-///
-/// This is useful for:
-/// - Creating assemblies of multiple parts
-/// - Building hierarchical model structures
-///
-/// Access component-building methods directly via [`Deref`] to [`ComponentsBuilder`].
-///
-/// # Examples
-///
-/// ```rust,ignore
-/// // First create a mesh object to reference
-/// let bolt_id = builder.add_mesh_object(|obj| {
-///     obj.name("Bolt");
-///     // ... add geometry
-///     Ok(())
-/// })?;
-///
-/// // Create an assembly referencing the bolt multiple times
-/// let assembly_id = builder.add_components_object(|obj| {
-///     obj.name("BoltAssembly");
-///
-///     // Add first bolt
-///     obj.add_component(bolt_id);
-///
-///     // Add second bolt with transform
-///     obj.add_component_advanced(bolt_id, |comp| {
-///         comp.transform(Transform([
-///             1.0, 0.0, 0.0,
-///             0.0, 1.0, 0.0,
-///             0.0, 0.0, 1.0,
-///             10.0, 0.0, 0.0  // Translated 10mm in X
-///         ]));
-///     });
-///
-///     Ok(())
-/// })?;
-/// ```
-pub type ComponentsObjectBuilder = ObjectBuilder<ComponentsBuilder>;
-
-impl ComponentsObjectBuilder {
-    fn new(
-        object_id: ObjectId,
-        all_existing_object_ids: &[ObjectId],
-        is_production_ext_required: bool,
-    ) -> Self {
+impl ComponentsObjectBuilder {
+    fn new(
+        object_id: ObjectId,
+        all_existing_object_ids: &[ObjectId],
+        is_production_ext_required: bool,
+    ) -> Self {
         Self {
             entity: ComponentsBuilder::new(all_existing_object_ids),
             object_id,
@@ -1591,10 +1748,77 @@ impl ComponentsObjectBuilder {
     }
 }
 
+/// Errors that can occur when building an assembly with [`ModelBuilder::add_assembly()`].
+#[derive(Debug, Error, Clone)]
+pub enum AssemblyError {
+    /// Error occurred while building an inline mesh object.
+    #[error("Something wrong when adding an inline mesh")]
+    MeshObjectError(#[from] MeshObjectError),
+
+    /// Error occurred while building the components object of the assembly.
+    #[error("Something wrong when adding the assembly")]
+    ComponentsObjectError(#[from] ComponentsObjectError),
+}
+
+/// Builder for an assembly whose parts are created inline.
+///
+/// Created by [`ModelBuilder::add_assembly()`]. Meshes added with
+/// [`add_inline_mesh()`](AssemblyBuilder::add_inline_mesh) become mesh objects of the model
+/// and [`place()`](AssemblyBuilder::place) references them as components of the assembly.
+///
+/// Access the [`ComponentsObjectBuilder`] of the assembly via [`Deref`], e.g. to set its name
+/// or to add components referencing objects that already exist in the model.
+pub struct AssemblyBuilder<'a> {
+    model: &'a mut ModelBuilder,
+    object: ComponentsObjectBuilder,
+}
+
+impl AssemblyBuilder<'_> {
+    /// Add a mesh object to the model that can be placed in this assembly.
+    ///
+    /// Works like [`ModelBuilder::add_mesh_object()`] and returns the id of the new object.
+    pub fn add_inline_mesh<F>(&mut self, f: F) -> Result<ObjectId, MeshObjectError>
+    where
+        F: FnOnce(&mut MeshObjectBuilder) -> Result<(), MeshObjectError>,
+    {
+        let id = self.model.add_mesh_object(f)?;
+        self.object.entity.all_existing_object_ids.push(id);
+
+        Ok(id)
+    }
+
+    /// Add a component placing the object `object_id` in the assembly with the given transform.
+    ///
+    /// Use [`Transform::IDENTITY`] to place the object as it is. For components that need
+    /// a UUID or a path, use [`add_component_advanced()`](ComponentsBuilder::add_component_advanced).
+    pub fn place(&mut self, object_id: ObjectId, transform: Transform) -> &mut Self {
+        self.object.add_component_advanced(object_id, |c| {
+            c.transform(transform);
+        });
+        self
+    }
+}
+
+impl Deref for AssemblyBuilder<'_> {
+    type Target = ComponentsObjectBuilder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl DerefMut for AssemblyBuilder<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.object
+    }
+}
+
 /// Builder for managing components in an assembly object.
 ///
 /// Components are references to other objects with optional transforms, UUIDs, and paths.
-/// The builder validates that referenced objects exist in the model.
+/// The builder validates that referenced objects exist in the model. Components with a path
+/// reference objects in another model part and are not validated, keeping those references
+/// intact is the responsibility of the caller.
 pub struct ComponentsBuilder {
     components: Vec<Component>,
 
@@ -1676,9 +1900,12 @@ impl ComponentsBuilder {
             }
         }
 
+        // components with a path reference an object in another model part,
+        // those can only be validated at the package level
         let all_object_exists = self
             .components
             .iter()
+            .filter(|c| c.path.is_none())
             .all(|c| self.all_existing_object_ids.contains(&ObjectId(c.objectid)));
 
         if !all_object_exists {
@@ -1729,6 +1956,9 @@ impl ComponentBuilder {
     ///
     /// Only allowed when Production extension is enabled. The path specifies
     /// an alternative model file where the referenced object can be found.
+    ///
+    /// The referenced object is not required to exist in the model being built,
+    /// the caller has to make sure it exists in the model part at the given path.
     pub fn path(&mut self, path: &str) -> &mut Self {
         self.path = Some(path.to_owned());
         self
@@ -1744,559 +1974,192 @@ impl ComponentBuilder {
     }
 }
 
-/// Builder for organizing triangles into named sets.
-///
-/// Triangle sets allow you to group triangles within a mesh for organizational purposes,
-/// such as identifying different faces or regions. Each set has a name and identifier,
-/// and references triangles either individually or as ranges.
-///
-/// Triangle sets are added as a recommended extension (not required).
-///
-/// # Examples
-///
-/// ```rust,ignore
-/// obj.add_triangle_sets(|sets| {
-///     // Add a set referencing specific triangle indices
-///     sets.add_set("TopFace", "top-id", &[0, 1, 2], &[]);
-///
-///     // Add a set using a range of triangles
-///     sets.add_set("SideFaces", "side-id", &[], &[(3, 10)]);
+/// Errors that can occur when building a [`ThreemfPackage`] with [`PackageBuilder`].
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PackageError {
+    /// A part was added at a path that is already used by another part.
+    #[error("Part path {0} is already used in the package")]
+    DuplicatePartPath(String),
+
+    /// A relationship was added for a model that is not part of the package.
+    #[error("No model found at {0}")]
+    ModelNotFound(String),
+
+    /// The thumbnail format can't be described by a content type.
+    #[error("Thumbnail {0} has an unknown image format")]
+    UnknownThumbnailFormat(String),
+}
+
+/// Path of the root model written by [`PackageBuilder`].
+const ROOT_MODEL_PATH: &str = "/3D/3dmodel.model";
+
+/// Builder for a [`ThreemfPackage`] made of a root model, sub models and thumbnails.
 ///
-///     // Mix individual refs and ranges
-///     sets.add_set("Mixed", "mixed-id", &[11, 12], &[(20, 30), (40, 50)]);
-/// });
-/// ```
-pub struct TriangleSetsBuilder {
-    sets: Vec<crate::core::triangle_set::TriangleSet>,
+/// The builder creates the relationship files of the package. Relationships owned by
+/// the package are written to `_rels/.rels`, relationships owned by a model part are
+/// written to the `_rels` folder next to it, e.g. a thumbnail of the sub model
+/// `/3D/Objects/part.model` ends up in `/3D/Objects/_rels/part.model.rels`.
+/// Sub models are referenced from the relationships of the root model.
+pub struct PackageBuilder {
+    root: Model,
+    sub_models: Vec<(String, Model)>,
+    thumbnails: Vec<(Option<String>, String, ThumbnailHandle)>,
 }
 
-impl TriangleSetsBuilder {
-    fn new() -> Self {
-        Self { sets: Vec::new() }
+impl PackageBuilder {
+    /// Create a new package builder with the root model placed at `/3D/3dmodel.model`.
+    pub fn new(root: Model) -> Self {
+        Self {
+            root,
+            sub_models: vec![],
+            thumbnails: vec![],
+        }
     }
 
-    /// Add a triangle set with a name, identifier, and triangle references.
-    ///
-    /// Triangles can be referenced either individually (via `refs`) or as ranges
-    /// (via `ranges`). Both methods can be used together.
-    ///
-    /// # Parameters
-    ///
-    /// - `name`: Human-readable name for the set
-    /// - `identifier`: Unique identifier for the set
-    /// - `refs`: Slice of individual triangle indices to include
-    /// - `ranges`: Slice of triangle index ranges (inclusive start, inclusive end)
-    ///
-    /// # Examples
-    ///
-    /// ```rust,ignore
-    /// // Reference triangles 0, 1, and 2 individually
-    /// sets.add_set("Group1", "g1", &[0, 1, 2], &[]);
-    ///
-    /// // Reference triangles 10-20 (inclusive)
-    /// sets.add_set("Group2", "g2", &[], &[(10, 20)]);
-    ///
-    /// // Mix both approaches
-    /// sets.add_set("Group3", "g3", &[5, 6], &[(10, 15), (20, 25)]);
-    /// ```
-    pub fn add_set(
-        &mut self,
-        name: &str,
-        identifier: &str,
-        refs: &[usize],
-        ranges: &[(usize, usize)],
-    ) -> &mut Self {
-        use crate::core::triangle_set::{TriangleRef, TriangleRefRange, TriangleSet};
-
-        let triangle_ref = refs.iter().map(|&index| TriangleRef { index }).collect();
-        let triangle_refrange = ranges
-            .iter()
-            .map(|&(start, end)| TriangleRefRange {
-                startindex: start,
-                endindex: end,
-            })
-            .collect();
+    /// Add a sub model at the given path within the package.
+    pub fn add_sub_model(&mut self, path: &str, model: Model) -> Result<&mut Self, PackageError> {
+        let path = Self::part_path(path);
+        self.ensure_unused(&path)?;
 
-        self.sets.push(TriangleSet {
-            name: name.to_owned(),
-            identifier: identifier.to_owned(),
-            triangle_ref,
-            triangle_refrange,
-        });
-        self
-    }
-
-    fn build(self) -> crate::core::triangle_set::TriangleSets {
-        crate::core::triangle_set::TriangleSets {
-            trianglesets: self.sets,
-        }
-    }
-}
-
-/// Builder for constructing beam lattice structures.
-///
-/// Beam lattices define strut-like structures connecting vertices in a mesh.
-/// They are part of the 3MF Beam Lattice extension and are useful for representing
-/// lightweight lattice structures, supports, or truss designs.
-///
-/// The builder allows you to:
-/// - Define beams connecting pairs of vertices
-/// - Add balls (spherical nodes) at vertices
-/// - Organize beams and balls into named sets
-/// - Configure default properties like radius, ball mode, clipping, etc.
-///
-/// The Beam Lattice extension namespace is automatically added when beams are present.
-pub struct BeamLatticeBuilder {
-    minlength: Option<f64>,
-    radius: Option<f64>,
-    ballmode: Option<BallMode>,
-    ballradius: Option<f64>,
-    clippingmode: Option<ClippingMode>,
-    clippingmesh: Option<usize>,
-    representationmesh: Option<usize>,
-    pid: Option<usize>,
-    pindex: Option<usize>,
-    cap: Option<CapMode>,
-    beams: Vec<Beam>,
-    balls: Vec<Ball>,
-    beamsets: Vec<BeamSet>,
-}
-
-impl BeamLatticeBuilder {
-    fn new() -> Self {
-        Self {
-            minlength: None,
-            radius: None,
-            ballmode: None,
-            ballradius: None,
-            clippingmode: None,
-            clippingmesh: None,
-            representationmesh: None,
-            pid: None,
-            pindex: None,
-            cap: None,
-            beams: Vec::new(),
-            balls: Vec::new(),
-            beamsets: Vec::new(),
-        }
-    }
-
-    /// Set the minimum length for beams (default: 0.0001).
-    ///
-    /// Beams shorter than this length may be ignored during processing.
-    pub fn minlength(&mut self, minlength: f64) -> &mut Self {
-        self.minlength = Some(minlength);
-        self
-    }
-
-    /// Set the default radius for all beams (default: 0.0001).
-    ///
-    /// Individual beams can override this with their own radius values.
-    pub fn radius(&mut self, radius: f64) -> &mut Self {
-        self.radius = Some(radius);
-        self
-    }
-
-    /// Set how balls (nodes) are rendered at beam connections.
-    ///
-    /// See [`BallMode`] for available options.
-    pub fn ballmode(&mut self, mode: BallMode) -> &mut Self {
-        self.ballmode = Some(mode);
-        self
-    }
-
-    /// Set the default radius for all balls.
-    ///
-    /// Individual balls can override this with their own radius values.
-    pub fn ballradius(&mut self, radius: f64) -> &mut Self {
-        self.ballradius = Some(radius);
-        self
-    }
-
-    /// Set how the lattice is clipped by the clipping mesh.
-    ///
-    /// See [`ClippingMode`] for available options.
-    pub fn clippingmode(&mut self, mode: ClippingMode) -> &mut Self {
-        self.clippingmode = Some(mode);
-        self
-    }
-
-    /// Set the mesh object used for clipping the lattice.
-    ///
-    /// The lattice will be clipped to the bounds of this mesh based on the clipping mode.
-    pub fn clippingmesh(&mut self, object_id: ObjectId) -> &mut Self {
-        self.clippingmesh = Some(object_id.0);
-        self
-    }
-
-    /// Set an alternative mesh for visualization.
-    ///
-    /// This mesh can be used as a simplified representation of the lattice.
-    pub fn representationmesh(&mut self, object_id: ObjectId) -> &mut Self {
-        self.representationmesh = Some(object_id.0);
-        self
-    }
-
-    /// Set the property ID for the lattice.
-    pub fn pid(&mut self, pid: usize) -> &mut Self {
-        self.pid = Some(pid);
-        self
-    }
-
-    /// Set the property index for the lattice.
-    pub fn pindex(&mut self, pindex: usize) -> &mut Self {
-        self.pindex = Some(pindex);
-        self
-    }
-
-    /// Set the default cap mode for beam ends.
-    ///
-    /// Individual beams can override this. See [`CapMode`] for available options.
-    pub fn cap(&mut self, cap: CapMode) -> &mut Self {
-        self.cap = Some(cap);
-        self
-    }
-
-    /// Add a simple beam connecting two vertices.
-    ///
-    /// The beam will use default properties (radius, cap mode, etc.).
-    ///
-    /// # Parameters
-    ///
-    /// - `v1`: Index of the first vertex
-    /// - `v2`: Index of the second vertex
-    pub fn add_beam(&mut self, v1: usize, v2: usize) -> &mut Self {
-        let beam = BeamBuilder::new(v1, v2).build();
-        self.beams.push(beam);
-        self
-    }
-
-    /// Add a beam with custom configuration.
-    ///
-    /// Use this method to configure individual beam properties like radius, cap modes, etc.
-    ///
-    /// # Parameters
-    ///
-    /// - `v1`: Index of the first vertex
-    /// - `v2`: Index of the second vertex
-    /// - `f`: A closure that configures the [`BeamBuilder`]
-    pub fn add_beam_advanced<F>(&mut self, v1: usize, v2: usize, f: F) -> &mut Self
-    where
-        F: FnOnce(BeamBuilder) -> BeamBuilder,
-    {
-        let builder = BeamBuilder::new(v1, v2);
-        let beam = f(builder).build();
-        self.beams.push(beam);
-        self
-    }
-
-    /// Add multiple simple beams from vertex pairs.
-    ///
-    /// # Parameters
-    ///
-    /// - `vertex_pairs`: Slice of `(v1, v2)` vertex index pairs
-    pub fn add_beams(&mut self, vertex_pairs: &[(usize, usize)]) -> &mut Self {
-        for &(v1, v2) in vertex_pairs {
-            self.add_beam(v1, v2);
-        }
-        self
-    }
-
-    /// Add a simple ball (spherical node) at a vertex.
-    ///
-    /// The ball will use default properties (radius, etc.).
-    ///
-    /// # Parameters
-    ///
-    /// - `vindex`: Index of the vertex where the ball is located
-    pub fn add_ball(&mut self, vindex: usize) -> &mut Self {
-        let ball = BallBuilder::new(vindex).build();
-        self.balls.push(ball);
-        self
+        self.sub_models.push((path, model));
+        Ok(self)
     }
 
-    /// Add a ball with custom configuration.
-    ///
-    /// Use this method to configure individual ball properties like radius.
-    ///
-    /// # Parameters
-    ///
-    /// - `vindex`: Index of the vertex where the ball is located
-    /// - `f`: A closure that configures the [`BallBuilder`]
-    pub fn add_ball_advanced<F>(&mut self, vindex: usize, f: F) -> &mut Self
-    where
-        F: FnOnce(BallBuilder) -> BallBuilder,
-    {
-        let builder = BallBuilder::new(vindex);
-        let ball = f(builder).build();
-        self.balls.push(ball);
-        self
+    /// Add a thumbnail of the whole package, referenced from `_rels/.rels`.
+    pub fn add_package_thumbnail(
+        &mut self,
+        path: &str,
+        thumbnail: ThumbnailHandle,
+    ) -> Result<&mut Self, PackageError> {
+        self.add_thumbnail(None, path, thumbnail)
     }
 
-    /// Add multiple simple balls from vertex indices.
-    ///
-    /// # Parameters
+    /// Add a thumbnail referenced from the relationships of the model at `model_path`.
     ///
-    /// - `vindices`: Slice of vertex indices where balls should be placed
-    pub fn add_balls(&mut self, vindices: &[usize]) -> &mut Self {
-        for &vindex in vindices {
-            self.add_ball(vindex);
+    /// The model must be the root model or a previously added sub model.
+    pub fn add_model_thumbnail(
+        &mut self,
+        model_path: &str,
+        path: &str,
+        thumbnail: ThumbnailHandle,
+    ) -> Result<&mut Self, PackageError> {
+        let model_path = Self::part_path(model_path);
+        if model_path != ROOT_MODEL_PATH && !self.sub_models.iter().any(|(p, _)| *p == model_path) {
+            return Err(PackageError::ModelNotFound(model_path));
         }
-        self
-    }
 
-    /// Add a beam set to organize beams and balls into named groups.
-    ///
-    /// # Parameters
-    ///
-    /// - `f`: A closure that configures the [`BeamSetBuilder`]
-    pub fn add_beamset<F>(&mut self, f: F) -> &mut Self
-    where
-        F: FnOnce(&mut BeamSetBuilder),
-    {
-        let mut builder = BeamSetBuilder::new();
-        f(&mut builder);
-        self.beamsets.push(builder.build());
-        self
+        self.add_thumbnail(Some(model_path), path, thumbnail)
     }
 
-    fn build(self) -> BeamLattice {
-        let beams = Beams { beam: self.beams };
-
-        let balls = if self.balls.is_empty() {
-            None
-        } else {
-            Some(Balls { ball: self.balls })
-        };
-
-        let beamsets = if self.beamsets.is_empty() {
-            None
-        } else {
-            Some(BeamSets {
-                beamset: self.beamsets,
-            })
-        };
-
-        BeamLattice {
-            minlength: self.minlength.unwrap_or(0.0001),
-            radius: self.radius.unwrap_or(0.0001),
-            ballmode: self.ballmode,
-            ballradius: self.ballradius,
-            clippingmode: self.clippingmode,
-            clippingmesh: self.clippingmesh,
-            representationmesh: self.representationmesh,
-            pid: self.pid,
-            pindex: self.pindex,
-            cap: self.cap,
-            beams,
-            balls,
-            beamsets,
-        }
-    }
-}
-
-/// Builder for individual beams in a beam lattice
-pub struct BeamBuilder {
-    v1: usize,
-    v2: usize,
-    r1: Option<f64>,
-    r2: Option<f64>,
-    p1: Option<usize>,
-    p2: Option<usize>,
-    pid: Option<usize>,
-    cap1: Option<CapMode>,
-    cap2: Option<CapMode>,
-}
-
-impl BeamBuilder {
-    /// Create a new beam with the specified vertex indices
-    pub fn new(v1: usize, v2: usize) -> Self {
-        Self {
-            v1,
-            v2,
-            r1: None,
-            r2: None,
-            p1: None,
-            p2: None,
-            pid: None,
-            cap1: None,
-            cap2: None,
+    fn add_thumbnail(
+        &mut self,
+        owner: Option<String>,
+        path: &str,
+        thumbnail: ThumbnailHandle,
+    ) -> Result<&mut Self, PackageError> {
+        let path = Self::part_path(path);
+        self.ensure_unused(&path)?;
+        if thumbnail.format == ImageFormat::Unknown {
+            return Err(PackageError::UnknownThumbnailFormat(path));
         }
-    }
-
-    /// Set the radius at the first vertex
-    pub fn radius_1(mut self, radius: f64) -> Self {
-        self.r1 = Some(radius);
-        self
-    }
-
-    /// Set the radius at the second vertex
-    pub fn radius_2(mut self, radius: f64) -> Self {
-        self.r2 = Some(radius);
-        self
-    }
-
-    /// Set the property index for the first vertex
-    pub fn pindex_1(mut self, pindex: usize) -> Self {
-        self.p1 = Some(pindex);
-        self
-    }
-
-    /// Set the property index for the second vertex
-    pub fn pindex_2(mut self, pindex: usize) -> Self {
-        self.p2 = Some(pindex);
-        self
-    }
 
-    /// Set the property ID for the beam
-    pub fn pid(mut self, pid: usize) -> Self {
-        self.pid = Some(pid);
-        self
-    }
-
-    /// Set the cap mode for the first end of the beam
-    pub fn cap_1(mut self, cap: CapMode) -> Self {
-        self.cap1 = Some(cap);
-        self
-    }
-
-    /// Set the cap mode for the second end of the beam
-    pub fn cap_2(mut self, cap: CapMode) -> Self {
-        self.cap2 = Some(cap);
-        self
+        self.thumbnails.push((owner, path, thumbnail));
+        Ok(self)
     }
 
-    fn build(self) -> Beam {
-        Beam {
-            v1: self.v1,
-            v2: self.v2,
-            r1: self.r1,
-            r2: self.r2,
-            p1: self.p1,
-            p2: self.p2,
-            pid: self.pid,
-            cap1: self.cap1,
-            cap2: self.cap2,
+    /// Build the package together with all of its relationship files and content types.
+    pub fn build(self) -> ThreemfPackage {
+        let mut relationships = HashMap::from([(
+            "_rels/.rels".to_owned(),
+            Relationships::root_with_model(ROOT_MODEL_PATH),
+        )]);
+        let mut add_relationship =
+            |rels_path: String, target: &str, relationship_type: RelationshipType| {
+                let rels = relationships.entry(rels_path).or_insert(Relationships {
+                    relationships: vec![],
+                });
+                rels.relationships.push(Relationship {
+                    id: format!("rel{}", rels.relationships.len()),
+                    target: target.to_owned(),
+                    relationship_type,
+                });
+            };
+
+        for (path, _) in &self.sub_models {
+            add_relationship(
+                Self::rels_path(ROOT_MODEL_PATH),
+                path,
+                RelationshipType::Model,
+            );
         }
-    }
-}
 
-/// Builder for balls in a beam lattice
-pub struct BallBuilder {
-    vindex: usize,
-    r: Option<f64>,
-    p: Option<usize>,
-    pid: Option<usize>,
-}
+        let mut content_types = ContentTypes::standard_3mf(false);
+
+        let mut thumbnails = HashMap::new();
+        for (owner, path, thumbnail) in self.thumbnails {
+            let rels_path = match &owner {
+                Some(model_path) => Self::rels_path(model_path),
+                None => "_rels/.rels".to_owned(),
+            };
+            add_relationship(rels_path, &path, RelationshipType::Thumbnail);
+
+            let extension = path
+                .rsplit_once('.')
+                .map(|(_, ext)| ext.to_lowercase())
+                .unwrap_or_default();
+            if !content_types
+                .defaults
+                .iter()
+                .any(|d| d.extension == extension)
+            {
+                let content_type = match thumbnail.format {
+                    ImageFormat::Jpeg => DefaultContentTypeEnum::ImageJPEG,
+                    _ => DefaultContentTypeEnum::ImagePng,
+                };
+                content_types.defaults.push(DefaultContentTypes {
+                    extension,
+                    content_type,
+                });
+            }
 
-impl BallBuilder {
-    /// Create a new ball at the specified vertex index
-    pub fn new(vindex: usize) -> Self {
-        Self {
-            vindex,
-            r: None,
-            p: None,
-            pid: None,
+            thumbnails.insert(path, thumbnail);
         }
-    }
-
-    /// Set the radius of the ball
-    pub fn radius(mut self, radius: f64) -> Self {
-        self.r = Some(radius);
-        self
-    }
 
-    /// Set the property index for the ball
-    pub fn pindex(mut self, pindex: usize) -> Self {
-        self.p = Some(pindex);
-        self
-    }
-
-    /// Set the property ID for the ball
-    pub fn pid(mut self, pid: usize) -> Self {
-        self.pid = Some(pid);
-        self
-    }
-
-    fn build(self) -> Ball {
-        Ball {
-            vindex: self.vindex,
-            r: self.r,
-            p: self.p,
-            pid: self.pid,
+        ThreemfPackage::new(
+            self.root,
+            self.sub_models.into_iter().collect(),
+            thumbnails,
+            HashMap::new(),
+            relationships,
+            content_types,
+        )
+    }
+
+    fn ensure_unused(&self, path: &str) -> Result<(), PackageError> {
+        let used = path == ROOT_MODEL_PATH
+            || self.sub_models.iter().any(|(p, _)| p == path)
+            || self.thumbnails.iter().any(|(_, p, _)| p == path);
+        if used {
+            Err(PackageError::DuplicatePartPath(path.to_owned()))
+        } else {
+            Ok(())
         }
     }
-}
-
-/// Builder for beam sets in a beam lattice
-pub struct BeamSetBuilder {
-    name: Option<String>,
-    identifier: Option<String>,
-    beam_refs: Vec<usize>,
-    ball_refs: Vec<usize>,
-}
 
-impl BeamSetBuilder {
-    fn new() -> Self {
-        Self {
-            name: None,
-            identifier: None,
-            beam_refs: Vec::new(),
-            ball_refs: Vec::new(),
+    /// Part paths are stored with a leading slash, same as the relationship targets.
+    fn part_path(path: &str) -> String {
+        if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/{path}")
         }
     }
 
-    /// Set the name of the beam set
-    pub fn name(&mut self, name: &str) -> &mut Self {
-        self.name = Some(name.to_owned());
-        self
-    }
-
-    /// Set the identifier of the beam set
-    pub fn identifier(&mut self, id: &str) -> &mut Self {
-        self.identifier = Some(id.to_owned());
-        self
-    }
-
-    /// Add a reference to a beam by index
-    pub fn add_beam_ref(&mut self, index: usize) -> &mut Self {
-        self.beam_refs.push(index);
-        self
-    }
-
-    /// Add multiple beam references
-    pub fn add_beam_refs(&mut self, indices: &[usize]) -> &mut Self {
-        self.beam_refs.extend_from_slice(indices);
-        self
-    }
-
-    /// Add a reference to a ball by index
-    pub fn add_ball_ref(&mut self, index: usize) -> &mut Self {
-        self.ball_refs.push(index);
-        self
-    }
-
-    /// Add multiple ball references
-    pub fn add_ball_refs(&mut self, indices: &[usize]) -> &mut Self {
-        self.ball_refs.extend_from_slice(indices);
-        self
-    }
-
-    fn build(self) -> BeamSet {
-        BeamSet {
-            name: self.name,
-            identifier: self.identifier,
-            refs: self
-                .beam_refs
-                .into_iter()
-                .map(|index| BeamRef { index })
-                .collect(),
-            ballref: self
-                .ball_refs
-                .into_iter()
-                .map(|index| BallRef { index })
-                .collect(),
-        }
+    /// Path of the relationship file owned by the given part,
+    /// e.g. `/3D/Objects/_rels/part.model.rels` for `/3D/Objects/part.model`.
+    pub(crate) fn rels_path(part_path: &str) -> String {
+        let (folder, file) = part_path.rsplit_once('/').unwrap_or(("", part_path));
+        format!("{folder}/_rels/{file}.rels")
     }
 }
 
@@ -2341,6 +2204,171 @@ mod tests {
         assert_eq!(model.build.item[0].objectid, 1);
     }
 
+    #[test]
+    fn test_add_assembly() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let existing = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+
+        let translation = Transform([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 20.0, 0.0, 0.0]);
+        let assembly_id = builder
+            .add_assembly(|asm| {
+                asm.name("Assembly");
+                let part = asm.add_inline_mesh(|obj| {
+                    obj.name("Part");
+                    obj.add_vertices(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0], [0.0, 5.0, 0.0]]);
+                    obj.add_triangle(&[0, 1, 2]);
+                    Ok(())
+                })?;
+                let other = asm.add_inline_mesh(|obj| {
+                    obj.name("Other");
+                    obj.add_vertices(&[[0.0, 0.0, 1.0], [5.0, 0.0, 1.0], [0.0, 5.0, 1.0]]);
+                    obj.add_triangle(&[0, 1, 2]);
+                    Ok(())
+                })?;
+                asm.place(part, Transform::IDENTITY)
+                    .place(part, translation.clone())
+                    .place(other, Transform::IDENTITY);
+                asm.add_component(existing);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(assembly_id).unwrap();
+
+        assert_eq!(assembly_id, ObjectId(4));
+        let model = builder.build().unwrap();
+        let objects = &model.resources.object;
+        assert_eq!(
+            objects.iter().map(|o| o.id).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(objects[1].name.as_deref(), Some("Part"));
+        assert_eq!(objects[2].name.as_deref(), Some("Other"));
+        assert!(objects[1].mesh.is_some());
+
+        let assembly = &objects[3];
+        assert_eq!(assembly.name.as_deref(), Some("Assembly"));
+        let components = &assembly.components.as_ref().unwrap().component;
+        assert_eq!(
+            components
+                .iter()
+                .map(|c| (c.objectid, c.transform.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Some(Transform::IDENTITY)),
+                (2, Some(translation)),
+                (3, Some(Transform::IDENTITY)),
+                (1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_assembly_error_keeps_model_unchanged() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+
+        let result = builder.add_assembly(|asm| {
+            let part = asm.add_inline_mesh(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })?;
+            asm.place(part, Transform::IDENTITY);
+            // references an object that doesn't exist
+            asm.add_component(ObjectId(42));
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(AssemblyError::ComponentsObjectError(
+                ComponentsObjectError::ObjectReferenceNotFoundForComponent
+            ))
+        ));
+
+        let result = builder.add_assembly(|asm| {
+            asm.add_inline_mesh(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 1]);
+                Ok(())
+            })?;
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(AssemblyError::MeshObjectError(
+                MeshObjectError::DegenerateTriangleIndices { triangle: 0 }
+            ))
+        ));
+
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(id, ObjectId(1));
+        assert_eq!(builder.build().unwrap().resources.object.len(), 1);
+    }
+
+    #[test]
+    fn test_model_builder_into_package() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let cube_id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[
+                    [0.0, 0.0, 0.0],
+                    [10.0, 0.0, 0.0],
+                    [10.0, 10.0, 0.0],
+                    [0.0, 10.0, 0.0],
+                    [0.0, 0.0, 10.0],
+                    [10.0, 0.0, 10.0],
+                    [10.0, 10.0, 10.0],
+                    [0.0, 10.0, 10.0],
+                ]);
+                obj.add_triangles(&[
+                    [3, 2, 1],
+                    [1, 0, 3],
+                    [4, 5, 6],
+                    [6, 7, 4],
+                    [0, 1, 5],
+                    [5, 4, 0],
+                    [1, 2, 6],
+                    [6, 5, 1],
+                    [2, 3, 7],
+                    [7, 6, 2],
+                    [3, 0, 4],
+                    [4, 7, 3],
+                ]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(cube_id).unwrap();
+
+        let package = builder.into_package().unwrap();
+        assert_eq!(package.root.resources.object.len(), 1);
+        assert_eq!(package.root.build.item[0].objectid, usize::from(cube_id));
+        assert_eq!(
+            package.relationships["_rels/.rels"].relationships[0].target,
+            "/3D/3dmodel.model"
+        );
+
+        let mut writer = std::io::Cursor::new(Vec::<u8>::new());
+        package.write(&mut writer).unwrap();
+
+        let mut archive = zip::ZipArchive::new(writer).unwrap();
+        for name in ["[Content_Types].xml", "_rels/.rels", "3D/3dmodel.model"] {
+            assert!(archive.by_name(name).is_ok(), "{name} is missing");
+        }
+    }
+
     #[test]
     fn test_object_id_assignment() {
         let mut builder = ModelBuilder::new(Unit::Millimeter, false);
@@ -2367,6 +2395,178 @@ mod tests {
         assert_eq!(model.resources.object[1].id, 2);
     }
 
+    #[test]
+    fn test_large_object_ids() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.set_next_object_id(2_000_000_000).unwrap();
+        builder.add_build(None).unwrap();
+
+        let mesh_id = builder.add_mesh_object(|_| Ok(())).unwrap();
+        let assembly_id = builder
+            .add_components_object(|obj| {
+                obj.add_component(mesh_id);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(assembly_id).unwrap();
+
+        assert_eq!(mesh_id.0, 2_000_000_000);
+        assert_eq!(assembly_id.0, 2_000_000_001);
+
+        let model = builder.build().unwrap();
+        let components = model.resources.object[1].components.as_ref().unwrap();
+        assert_eq!(components.component[0].objectid, 2_000_000_000);
+        assert_eq!(model.build.item[0].objectid, 2_000_000_001);
+    }
+
+    #[test]
+    fn test_set_next_object_id_overflow() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+
+        assert!(builder.set_next_object_id(MAX_OBJECT_ID - 1).is_ok());
+        assert!(matches!(
+            builder.set_next_object_id(MAX_OBJECT_ID),
+            Err(ModelError::ObjectIdOverflow(id)) if id == MAX_OBJECT_ID
+        ));
+        assert!(matches!(
+            builder.set_next_object_id(usize::MAX),
+            Err(ModelError::ObjectIdOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_base_materials() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let materials = builder
+            .add_base_materials(|m| {
+                m.add_material("Red", "#FF0000FF")
+                    .add_material("Blue", "#0000ff");
+            })
+            .unwrap();
+        let obj_id = builder
+            .add_mesh_object(|obj| {
+                obj.pid(materials).pindex(1);
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle_with_properties(&[0, 1, 2], materials, &[0, 0, 0]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(obj_id).unwrap();
+
+        // materials and objects share the resource ids
+        assert_eq!(usize::from(materials), 1);
+        assert_eq!(usize::from(obj_id), 2);
+
+        let model = builder.build().unwrap();
+        assert_eq!(
+            model.resources.basematerials,
+            vec![BaseMaterials {
+                id: 1,
+                base: vec![
+                    Base {
+                        name: "Red".to_owned(),
+                        displaycolor: "#FF0000FF".to_owned(),
+                    },
+                    Base {
+                        name: "Blue".to_owned(),
+                        displaycolor: "#0000ff".to_owned(),
+                    },
+                ],
+            }]
+        );
+        let object = &model.resources.object[0];
+        assert_eq!((object.pid, object.pindex), (Some(1), Some(1)));
+        let triangle = &object.mesh.as_ref().unwrap().triangles.triangle[0];
+        assert_eq!((triangle.pid, triangle.p1), (Some(1), Some(0)));
+    }
+
+    #[test]
+    fn test_add_base_materials_errors() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        assert_eq!(
+            builder.add_base_materials(|_| {}),
+            Err(BaseMaterialsError::NoMaterials)
+        );
+
+        for color in ["FF0000", "#FF00", "#FF0000F", "#GG0000"] {
+            assert_eq!(
+                builder.add_base_materials(|m| {
+                    m.add_material("Red", "#FF0000").add_material("Bad", color);
+                }),
+                Err(BaseMaterialsError::InvalidDisplayColor {
+                    name: "Bad".to_owned(),
+                    color: color.to_owned(),
+                })
+            );
+        }
+
+        // failed groups don't use up an id
+        let id = builder
+            .add_base_materials(|m| {
+                m.add_material("Red", "#FF0000");
+            })
+            .unwrap();
+        assert_eq!(usize::from(id), 1);
+    }
+
+    #[test]
+    fn test_reject_empty_objects() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        let empty_id = builder.add_mesh_object(|_| Ok(())).unwrap();
+        builder.reject_empty_objects(true);
+
+        assert!(matches!(
+            builder.build(),
+            Err(ModelError::EmptyObject(id)) if id == empty_id.0
+        ));
+    }
+
+    #[test]
+    fn test_build_with_warnings_large_model_without_production_ext() {
+        let build_model = |object_count: usize, production_ext: bool| {
+            let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+            if production_ext {
+                builder.make_production_extension_required().unwrap();
+            }
+            builder.add_build(Some("build-uuid".to_owned())).unwrap();
+            for i in 0..object_count {
+                let obj_id = builder
+                    .add_mesh_object(|obj| {
+                        obj.uuid(&format!("obj-uuid-{i}"));
+                        Ok(())
+                    })
+                    .unwrap();
+                builder
+                    .add_build_item_advanced(obj_id, |item| {
+                        item.uuid(&format!("item-uuid-{i}"));
+                    })
+                    .unwrap();
+            }
+            builder.build_with_warnings().unwrap()
+        };
+
+        let (model, warnings) = build_model(101, false);
+        assert_eq!(model.resources.object.len(), 101);
+        assert_eq!(
+            warnings,
+            vec![BuildWarning::ConsiderProductionExtension { object_count: 101 }]
+        );
+
+        let (_, warnings) = build_model(100, false);
+        assert!(warnings.is_empty());
+
+        let (_, warnings) = build_model(101, true);
+        assert!(warnings.is_empty());
+    }
+
     #[test]
     fn test_multiple_passes() {
         let mut builder = ModelBuilder::new(Unit::Millimeter, true);
@@ -2530,6 +2730,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_component_with_path_skips_local_object_check() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.make_production_extension_required().unwrap();
+
+        // object 42 only exists in the referenced sub model
+        let assembly = builder.add_components_object(|obj| {
+            obj.uuid("obj-uuid");
+            obj.add_component_advanced(ObjectId::from(42), |c| {
+                c.uuid("comp-uuid").path("/3D/Objects/part.model");
+            });
+            Ok(())
+        });
+        assert!(assembly.is_ok());
+
+        let result = builder.add_components_object(|obj| {
+            obj.uuid("obj-uuid-2");
+            obj.add_component_advanced(ObjectId::from(42), |c| {
+                c.uuid("comp-uuid-2");
+            });
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(ComponentsObjectError::ObjectReferenceNotFoundForComponent)
+        ));
+    }
+
     #[test]
     fn test_extension_tests() {
         let mut builder = ModelBuilder::new(Unit::Millimeter, true);
@@ -2773,6 +3001,55 @@ mod tests {
         assert_eq!(model.metadata[2].value, Some("value3".to_string()));
     }
 
+    #[test]
+    fn test_add_metadata_if_absent() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder
+            .add_metadata_if_absent("Application", Some("First"))
+            .add_metadata_if_absent("Application", Some("Second"))
+            .add_metadata_if_absent("Designer", None);
+        builder.add_build(None).unwrap();
+        let model = builder.build().unwrap();
+
+        let metadata = model
+            .metadata
+            .iter()
+            .map(|m| (m.name.as_str(), m.value.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metadata,
+            vec![("Application", Some("First")), ("Designer", None)]
+        );
+    }
+
+    #[test]
+    fn test_set_metadata() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder
+            .add_metadata("Application", Some("First"))
+            .add_metadata("Title", Some("Part"))
+            .add_metadata("Application", Some("Duplicate"))
+            .set_metadata("Application", Some("Second"))
+            .set_metadata("Designer", Some("Someone"))
+            .set_metadata("Designer", None);
+        builder.add_build(None).unwrap();
+        let model = builder.build().unwrap();
+
+        let metadata = model
+            .metadata
+            .iter()
+            .map(|m| (m.name.as_str(), m.value.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metadata,
+            vec![
+                ("Application", Some("Second")),
+                ("Title", Some("Part")),
+                ("Designer", None)
+            ]
+        );
+    }
+
     #[test]
     fn test_triangle_sets_builder() {
         let mut builder = ModelBuilder::new(Unit::Millimeter, true);
@@ -2811,287 +3088,74 @@ mod tests {
     }
 
     #[test]
-    fn test_object_id_tests() {
-        let id: ObjectId = 42.into();
-        assert_eq!(id.0, 42);
-        let usize_id: usize = id.into();
-        assert_eq!(usize_id, 42);
-    }
-
-    // ========== BeamBuilder Tests ==========
-
-    #[test]
-    fn test_beam_builder_basic() {
-        let beam = BeamBuilder::new(0, 1).build();
-
-        assert_eq!(beam.v1, 0);
-        assert_eq!(beam.v2, 1);
-        assert_eq!(beam.r1, None);
-        assert_eq!(beam.r2, None);
-        assert_eq!(beam.p1, None);
-        assert_eq!(beam.p2, None);
-        assert_eq!(beam.pid, None);
-        assert_eq!(beam.cap1, None);
-        assert_eq!(beam.cap2, None);
-    }
-
-    #[test]
-    fn test_beam_builder_with_all_options() {
-        let beam = BeamBuilder::new(0, 1)
-            .radius_1(1.5)
-            .radius_2(2.0)
-            .pindex_1(10)
-            .pindex_2(20)
-            .pid(5)
-            .cap_1(CapMode::Hemisphere)
-            .cap_2(CapMode::Butt)
-            .build();
-
-        assert_eq!(beam.v1, 0);
-        assert_eq!(beam.v2, 1);
-        assert_eq!(beam.r1, Some(1.5));
-        assert_eq!(beam.r2, Some(2.0));
-        assert_eq!(beam.p1, Some(10));
-        assert_eq!(beam.p2, Some(20));
-        assert_eq!(beam.pid, Some(5));
-        assert_eq!(beam.cap1, Some(CapMode::Hemisphere));
-        assert_eq!(beam.cap2, Some(CapMode::Butt));
-    }
-
-    // ========== BallBuilder Tests ==========
-
-    #[test]
-    fn test_ball_builder_basic() {
-        let ball = BallBuilder::new(5).build();
-
-        assert_eq!(ball.vindex, 5);
-        assert_eq!(ball.r, None);
-        assert_eq!(ball.p, None);
-        assert_eq!(ball.pid, None);
-    }
-
-    #[test]
-    fn test_ball_builder_with_options() {
-        let ball = BallBuilder::new(5).radius(0.75).pindex(15).pid(3).build();
-
-        assert_eq!(ball.vindex, 5);
-        assert_eq!(ball.r, Some(0.75));
-        assert_eq!(ball.p, Some(15));
-        assert_eq!(ball.pid, Some(3));
-    }
-
-    // ========== BeamSetBuilder Tests ==========
-
-    #[test]
-    fn test_beamset_builder() {
-        let mut builder = BeamSetBuilder::new();
-        builder
-            .name("Test Set")
-            .identifier("test-set-001")
-            .add_beam_refs(&[0, 1, 2, 3])
-            .add_ball_ref(0)
-            .add_ball_ref(1);
-
-        let beamset = builder.build();
-
-        assert_eq!(beamset.name, Some("Test Set".to_owned()));
-        assert_eq!(beamset.identifier, Some("test-set-001".to_owned()));
-        assert_eq!(beamset.refs.len(), 4);
-        assert_eq!(beamset.refs[0].index, 0);
-        assert_eq!(beamset.refs[3].index, 3);
-        assert_eq!(beamset.ballref.len(), 2);
-        assert_eq!(beamset.ballref[0].index, 0);
-        assert_eq!(beamset.ballref[1].index, 1);
-    }
-
-    // ========== BeamLatticeBuilder Tests ==========
-
-    #[test]
-    fn test_beam_lattice_builder_minimal() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder.add_beam(0, 1);
-
-        let beamlattice = builder.build();
-
-        // Should use default values
-        assert_eq!(beamlattice.minlength, 0.0001);
-        assert_eq!(beamlattice.radius, 0.0001);
-        assert_eq!(beamlattice.beams.beam.len(), 1);
-        assert_eq!(beamlattice.beams.beam[0].v1, 0);
-        assert_eq!(beamlattice.beams.beam[0].v2, 1);
-        assert_eq!(beamlattice.balls, None);
-        assert_eq!(beamlattice.beamsets, None);
-    }
-
-    #[test]
-    fn test_beam_lattice_default_values() {
-        let mut builder = BeamLatticeBuilder::new();
-        // Don't set minlength or radius
-        builder.add_beam(0, 1);
-
-        let beamlattice = builder.build();
-
-        assert_eq!(beamlattice.minlength, 0.0001);
-        assert_eq!(beamlattice.radius, 0.0001);
-    }
-
-    #[test]
-    fn test_beam_lattice_builder_with_custom_values() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .minlength(0.001)
-            .radius(1.0)
-            .add_beam(0, 1)
-            .add_beam(1, 2);
-
-        let beamlattice = builder.build();
-
-        assert_eq!(beamlattice.minlength, 0.001);
-        assert_eq!(beamlattice.radius, 1.0);
-        assert_eq!(beamlattice.beams.beam.len(), 2);
-    }
-
-    #[test]
-    fn test_beam_lattice_builder_with_balls() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .minlength(0.001)
-            .radius(1.0)
-            .ballmode(BallMode::Mixed)
-            .ballradius(0.5)
-            .add_beam(0, 1)
-            .add_ball(0)
-            .add_ball_advanced(1, |b| b.radius(0.75));
-
-        let beamlattice = builder.build();
-
-        assert_eq!(beamlattice.ballmode, Some(BallMode::Mixed));
-        assert_eq!(beamlattice.ballradius, Some(0.5));
-        assert!(beamlattice.balls.is_some());
-
-        let balls = beamlattice.balls.as_ref().unwrap();
-        assert_eq!(balls.ball.len(), 2);
-        assert_eq!(balls.ball[0].vindex, 0);
-        assert_eq!(balls.ball[0].r, None); // Uses default
-        assert_eq!(balls.ball[1].vindex, 1);
-        assert_eq!(balls.ball[1].r, Some(0.75)); // Custom radius
-    }
-
-    #[test]
-    fn test_beam_lattice_builder_with_all_features() {
-        let clip_mesh_id = ObjectId(10);
-        let repr_mesh_id = ObjectId(20);
-
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .minlength(0.002)
-            .radius(2.0)
-            .ballmode(BallMode::All)
-            .ballradius(1.0)
-            .clippingmode(ClippingMode::Inside)
-            .clippingmesh(clip_mesh_id)
-            .representationmesh(repr_mesh_id)
-            .pid(5)
-            .pindex(10)
-            .cap(CapMode::Sphere)
-            .add_beam(0, 1);
-
-        let beamlattice = builder.build();
-
-        assert_eq!(beamlattice.minlength, 0.002);
-        assert_eq!(beamlattice.radius, 2.0);
-        assert_eq!(beamlattice.ballmode, Some(BallMode::All));
-        assert_eq!(beamlattice.ballradius, Some(1.0));
-        assert_eq!(beamlattice.clippingmode, Some(ClippingMode::Inside));
-        assert_eq!(beamlattice.clippingmesh, Some(10));
-        assert_eq!(beamlattice.representationmesh, Some(20));
-        assert_eq!(beamlattice.pid, Some(5));
-        assert_eq!(beamlattice.pindex, Some(10));
-        assert_eq!(beamlattice.cap, Some(CapMode::Sphere));
-    }
-
-    #[test]
-    fn test_beam_lattice_add_beams_bulk() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .radius(1.0)
-            .add_beams(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
-
-        let beamlattice = builder.build();
-
-        assert_eq!(beamlattice.beams.beam.len(), 4);
-        assert_eq!(beamlattice.beams.beam[0].v1, 0);
-        assert_eq!(beamlattice.beams.beam[0].v2, 1);
-        assert_eq!(beamlattice.beams.beam[3].v1, 3);
-        assert_eq!(beamlattice.beams.beam[3].v2, 0);
-    }
-
-    #[test]
-    fn test_beam_lattice_add_balls_bulk() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .radius(1.0)
-            .ballmode(BallMode::Mixed)
-            .ballradius(0.5)
-            .add_beam(0, 1)
-            .add_balls(&[0, 1, 2, 3]);
-
-        let beamlattice = builder.build();
+    fn test_empty_triangle_sets_are_omitted() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        let obj_id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangles(&[[0, 1, 2]]);
+                obj.add_triangle_sets(|_| {});
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build(None).unwrap();
+        builder.add_build_item(obj_id).unwrap();
+        let model = builder.build().unwrap();
 
-        assert!(beamlattice.balls.is_some());
-        let balls = beamlattice.balls.as_ref().unwrap();
-        assert_eq!(balls.ball.len(), 4);
-        assert_eq!(balls.ball[0].vindex, 0);
-        assert_eq!(balls.ball[3].vindex, 3);
+        let mesh = model.resources.object[0].mesh.as_ref().unwrap();
+        assert_eq!(mesh.trianglesets, None);
+        assert_eq!(model.recommendedextensions, None);
     }
 
     #[test]
-    fn test_beam_lattice_with_multiple_beamsets() {
-        let mut builder = BeamLatticeBuilder::new();
-        builder
-            .radius(1.0)
-            .add_beams(&[(0, 1), (1, 2), (2, 3), (3, 0)])
-            .add_beamset(|bs| {
-                bs.name("Bottom")
-                    .identifier("bottom-001")
-                    .add_beam_refs(&[0, 1]);
-            })
-            .add_beamset(|bs| {
-                bs.name("Top").identifier("top-001").add_beam_refs(&[2, 3]);
-            });
-
-        let beamlattice = builder.build();
+    fn test_triangle_sets_extension_recommended_once() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_recommended_extension(XmlNamespace {
+            prefix: Some("x".to_owned()),
+            uri: "http://example.com/extension".to_owned(),
+        });
+        builder.add_build(None).unwrap();
+        for sets in [0, 1, 2] {
+            let obj_id = builder
+                .add_mesh_object(|obj| {
+                    obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                    obj.add_triangles(&[[0, 1, 2]]);
+                    obj.add_triangle_sets(|ts| {
+                        for i in 0..sets {
+                            ts.add_set("Set", &format!("id{i}"), &[0], &[]);
+                        }
+                    });
+                    Ok(())
+                })
+                .unwrap();
+            builder.add_build_item(obj_id).unwrap();
+        }
+        let model = builder.build().unwrap();
 
-        assert!(beamlattice.beamsets.is_some());
-        let beamsets = beamlattice.beamsets.as_ref().unwrap();
-        assert_eq!(beamsets.beamset.len(), 2);
-        assert_eq!(beamsets.beamset[0].name, Some("Bottom".to_owned()));
-        assert_eq!(beamsets.beamset[0].refs.len(), 2);
-        assert_eq!(beamsets.beamset[1].name, Some("Top".to_owned()));
-        assert_eq!(beamsets.beamset[1].refs.len(), 2);
+        assert_eq!(
+            model.resources.object[0]
+                .mesh
+                .as_ref()
+                .unwrap()
+                .trianglesets,
+            None
+        );
+        let mut extensions = model
+            .recommendedextensions
+            .as_deref()
+            .unwrap()
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        extensions.sort();
+        assert_eq!(extensions, vec!["t", "x"]);
     }
 
     #[test]
-    fn test_mesh_with_beam_lattice() {
-        let mut mesh_builder = MeshBuilder::new();
-        mesh_builder
-            .add_vertices(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [10.0, 10.0, 0.0]])
-            .add_beam_lattice(|bl| {
-                bl.minlength(0.001)
-                    .radius(1.0)
-                    .add_beam(0, 1)
-                    .add_beam(1, 2);
-            });
-
-        let mesh = mesh_builder.build_mesh().unwrap();
-
-        assert_eq!(mesh.vertices.vertex.len(), 3);
-        assert!(mesh.beamlattice.is_some());
-
-        let bl = mesh.beamlattice.as_ref().unwrap();
-        assert_eq!(bl.minlength, 0.001);
-        assert_eq!(bl.radius, 1.0);
-        assert_eq!(bl.beams.beam.len(), 2);
+    fn test_object_id_tests() {
+        let id: ObjectId = 42.into();
+        assert_eq!(id.0, 42);
+        let usize_id: usize = id.into();
+        assert_eq!(usize_id, 42);
     }
 
     #[test]
@@ -3132,6 +3196,31 @@ mod tests {
         assert_eq!(model.requiredextensions, Some("b ".to_owned()));
     }
 
+    #[test]
+    fn test_model_with_beam_lattice_defaults_in_inch() {
+        let mut builder = ModelBuilder::new(Unit::Inch, true);
+
+        let obj_id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]])
+                    .add_beam_lattice(|bl| {
+                        bl.add_beam(0, 1);
+                    });
+                Ok(())
+            })
+            .unwrap();
+
+        builder.add_build(None).unwrap();
+        builder.add_build_item(obj_id).unwrap();
+
+        let model = builder.build().unwrap();
+
+        let mesh = model.resources.object[0].mesh.as_ref().unwrap();
+        let beamlattice = mesh.beamlattice.as_ref().unwrap();
+        assert_eq!(beamlattice.minlength, 0.0001 / 25.4);
+        assert_eq!(beamlattice.radius, 0.0001 / 25.4);
+    }
+
     #[test]
     fn test_model_with_beam_lattice_and_balls() {
         let mut builder = ModelBuilder::new(Unit::Millimeter, true);
@@ -3168,4 +3257,264 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_package_builder_per_model_relationships() {
+        let mut root = ModelBuilder::new(Unit::Millimeter, true);
+        root.add_build(None).unwrap();
+        let sub = ModelBuilder::new(Unit::Millimeter, false);
+
+        let png = ThumbnailHandle {
+            data: vec![1, 2, 3],
+            format: ImageFormat::Png,
+        };
+        let jpeg = ThumbnailHandle {
+            data: vec![4, 5, 6],
+            format: ImageFormat::Jpeg,
+        };
+
+        let mut builder = PackageBuilder::new(root.build().unwrap());
+        builder
+            .add_sub_model("3D/Objects/part.model", sub.build().unwrap())
+            .unwrap()
+            .add_package_thumbnail("Metadata/thumbnail.png", png.clone())
+            .unwrap()
+            .add_model_thumbnail("/3D/Objects/part.model", "/3D/Objects/part.jpeg", jpeg)
+            .unwrap();
+
+        let result =
+            builder.add_model_thumbnail("/3D/missing.model", "/3D/missing.png", png.clone());
+        assert_eq!(
+            result.err(),
+            Some(PackageError::ModelNotFound("/3D/missing.model".to_owned()))
+        );
+        let result = builder.add_package_thumbnail("/3D/Objects/part.model", png);
+        assert_eq!(
+            result.err(),
+            Some(PackageError::DuplicatePartPath(
+                "/3D/Objects/part.model".to_owned()
+            ))
+        );
+
+        let package = builder.build();
+        assert!(package.sub_models.contains_key("/3D/Objects/part.model"));
+        assert_eq!(package.thumbnails.len(), 2);
+        assert_eq!(package.content_types.defaults.len(), 4);
+
+        let targets = |rels_path: &str| {
+            package.relationships[rels_path]
+                .relationships
+                .iter()
+                .map(|r| (r.target.as_str(), r.relationship_type.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            targets("_rels/.rels"),
+            vec![
+                ("/3D/3dmodel.model", RelationshipType::Model),
+                ("/Metadata/thumbnail.png", RelationshipType::Thumbnail),
+            ]
+        );
+        assert_eq!(
+            targets("/3D/_rels/3dmodel.model.rels"),
+            vec![("/3D/Objects/part.model", RelationshipType::Model)]
+        );
+        assert_eq!(
+            targets("/3D/Objects/_rels/part.model.rels"),
+            vec![("/3D/Objects/part.jpeg", RelationshipType::Thumbnail)]
+        );
+    }
+
+    #[test]
+    fn test_degenerate_triangle_indices() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_triangles(&[[0, 1, 2], [0, 0, 1]]);
+            Ok(())
+        });
+        assert_eq!(
+            result,
+            Err(MeshObjectError::DegenerateTriangleIndices { triangle: 1 })
+        );
+
+        let result = builder.add_mesh_object(|obj| {
+            obj.allow_degenerate_triangles(true);
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_triangles(&[[0, 1, 2], [0, 0, 1]]);
+            Ok(())
+        });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_other_object_type_skips_geometry_checks() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.object_type(ObjectType::Other);
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_triangles(&[[0, 1, 2], [0, 0, 1]]);
+            Ok(())
+        });
+        assert!(result.is_ok());
+
+        builder
+            .add_mesh_object(|obj| {
+                obj.object_type(ObjectType::Other);
+                Ok(())
+            })
+            .unwrap();
+        builder.reject_empty_objects(true);
+        assert!(builder.build().is_ok());
+
+        // out of range indices are still rejected
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.object_type(ObjectType::Other);
+            obj.add_triangles(&[[0, 1, 2]]);
+            Ok(())
+        });
+        assert_eq!(result, Err(MeshObjectError::TrianglesWithoutVertices));
+    }
+
+    #[test]
+    fn test_triangle_index_out_of_bounds() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_triangle(&[0, 1, 99]);
+            Ok(())
+        });
+        assert_eq!(
+            result,
+            Err(MeshObjectError::TriangleIndexOutOfBounds {
+                triangle: 0,
+                index: 99
+            })
+        );
+
+        let result = builder.add_mesh_object(|obj| {
+            obj.skip_validation(true);
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_triangle(&[0, 1, 99]);
+            Ok(())
+        });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_triangles_without_vertices() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.add_triangles(&[[0, 1, 2]]);
+            Ok(())
+        });
+        assert_eq!(result, Err(MeshObjectError::TrianglesWithoutVertices));
+
+        // an empty mesh is still accepted
+        let result = builder.add_mesh_object(|_| Ok(()));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_beam_lattice_vertex_index_out_of_range() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        let result = builder.add_mesh_object(|obj| {
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+            obj.add_beam_lattice(|bl| {
+                bl.add_beams(&[(0, 1), (1, 2)]).add_balls(&[0, 3]);
+            });
+            Ok(())
+        });
+        assert_eq!(
+            result,
+            Err(MeshObjectError::BeamLattice(
+                BeamLatticeError::VertexIndexOutOfRange {
+                    kind: LatticeElementKind::Ball,
+                    index: 1,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_remove_degenerate_triangles() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        let obj_id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [2.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [5.0, 5.0, 5.0],
+                ]);
+                obj.add_triangles(&[[0, 1, 3], [0, 1, 2], [1, 1, 3], [1, 2, 3]]);
+                obj.add_triangle_sets(|sets| {
+                    sets.add_set("All", "all", &[1, 3], &[(0, 3)]);
+                });
+
+                // collinear triangle and triangle with a repeated vertex
+                assert_eq!(obj.remove_degenerate_triangles(1e-12), 2);
+                assert_eq!(obj.remove_degenerate_triangles(1e-12), 0);
+
+                assert_eq!(obj.remove_unused_vertices(), 1);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build(None).unwrap();
+        builder.add_build_item(obj_id).unwrap();
+
+        let model = builder.build().unwrap();
+        let mesh = model.resources.object[0].mesh.as_ref().unwrap();
+        let triangles = mesh
+            .triangles
+            .triangle
+            .iter()
+            .map(|t| [t.v1, t.v2, t.v3])
+            .collect::<Vec<_>>();
+        assert_eq!(triangles, vec![[0, 1, 3], [1, 2, 3]]);
+        assert_eq!(mesh.vertices.vertex.len(), 4);
+
+        let set = &mesh.trianglesets.as_ref().unwrap().trianglesets[0];
+        let refs = set.triangle_ref.iter().map(|r| r.index).collect::<Vec<_>>();
+        assert_eq!(refs, vec![1]);
+        let ranges = set
+            .triangle_refrange
+            .iter()
+            .map(|r| (r.startindex, r.endindex))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_remove_unused_vertices_keeps_beam_references() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[
+                    [9.0, 9.0, 9.0],
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [0.0, 0.0, 1.0],
+                ]);
+                obj.add_triangle(&[1, 2, 3]);
+                obj.add_beam_lattice(|bl| {
+                    bl.radius(1.0).add_beams(&[(1, 4)]).add_balls(&[4]);
+                });
+
+                assert_eq!(obj.remove_unused_vertices(), 1);
+                Ok(())
+            })
+            .unwrap();
+
+        let model = builder.build().unwrap();
+        let mesh = model.resources.object[0].mesh.as_ref().unwrap();
+        let t = &mesh.triangles.triangle[0];
+        assert_eq!([t.v1, t.v2, t.v3], [0, 1, 2]);
+        let bl = mesh.beamlattice.as_ref().unwrap();
+        assert_eq!((bl.beams.beam[0].v1, bl.beams.beam[0].v2), (0, 3));
+        assert_eq!(bl.balls.as_ref().unwrap().ball[0].vindex, 3);
+    }
 }
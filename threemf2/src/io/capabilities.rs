@@ -0,0 +1,64 @@
+/// The read and write modes of the [`io`](crate::io) module compiled into this build.
+///
+/// The modes are selected through the crate features, see [`capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Writing packages, enabled by the `io-write` feature.
+    pub write: bool,
+
+    /// Reading packages with the instant-xml based deserializer,
+    /// enabled by the `io-memory-optimized-read` feature.
+    pub memory_optimized_read: bool,
+
+    /// Reading packages with the serde-roxmltree based deserializer,
+    /// enabled by the `io-speed-optimized-read` feature.
+    pub speed_optimized_read: bool,
+
+    /// Lazily reading packages with `ThreemfPackageLazyReader`, enabled by the `io-lazy-read`
+    /// feature. The feature enables `io-memory-optimized-read` as well.
+    pub lazy_read: bool,
+}
+
+impl Capabilities {
+    /// Returns true if packages can be read with any of the deserializers.
+    pub fn can_read(&self) -> bool {
+        self.memory_optimized_read || self.speed_optimized_read
+    }
+}
+
+/// Returns the read and write modes compiled into this build.
+///
+/// Useful for tools that are built with different feature combinations and want to check
+/// at runtime what they support.
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        write: cfg!(feature = "io-write"),
+        memory_optimized_read: cfg!(feature = "io-memory-optimized-read"),
+        speed_optimized_read: cfg!(feature = "io-speed-optimized-read"),
+        lazy_read: cfg!(feature = "io-lazy-read"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capabilities;
+
+    #[test]
+    fn capabilities_match_features_test() {
+        let capabilities = capabilities();
+
+        assert_eq!(capabilities.write, cfg!(feature = "io-write"));
+        assert_eq!(
+            capabilities.memory_optimized_read,
+            cfg!(feature = "io-memory-optimized-read")
+        );
+        assert_eq!(
+            capabilities.speed_optimized_read,
+            cfg!(feature = "io-speed-optimized-read")
+        );
+        assert_eq!(capabilities.lazy_read, cfg!(feature = "io-lazy-read"));
+        if capabilities.lazy_read {
+            assert!(capabilities.memory_optimized_read);
+        }
+    }
+}
@@ -11,9 +11,11 @@ use instant_xml::{FromXml, Kind};
 use serde::Deserialize;
 
 /// Content types for the Open Packaging Conventions (OPC).
-/// Contains a collection of [DefaultContentTypes].
+/// Contains a collection of [DefaultContentTypes] and [OverrideContentTypes].
 /// [DefaultContentTypes] contains the [DefaultContentTypeEnum] specifying the content type.
 /// [DefaultContentTypes] contains the file extension that is used for the specified content type.
+/// [OverrideContentTypes] sets the content type of a single part, taking precedence over the
+/// default for its extension.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "speed-optimized-read", serde(rename = "Types"))]
 #[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
@@ -26,6 +28,65 @@ use serde::Deserialize;
 pub struct ContentTypes {
     #[cfg_attr(feature = "speed-optimized-read", serde(rename = "Default"))]
     pub defaults: Vec<DefaultContentTypes>,
+
+    #[cfg_attr(feature = "speed-optimized-read", serde(rename = "Override", default))]
+    pub overrides: Vec<OverrideContentTypes>,
+}
+
+impl ContentTypes {
+    /// Returns the default content types of a 3mf package, for the `model` and `rels`
+    /// extensions. If `with_images` is true, the `png` and `jpeg` extensions of thumbnails
+    /// are added as well.
+    pub fn standard_3mf(with_images: bool) -> Self {
+        let mut defaults = vec![
+            DefaultContentTypes {
+                extension: "model".to_owned(),
+                content_type: DefaultContentTypeEnum::Model,
+            },
+            DefaultContentTypes {
+                extension: "rels".to_owned(),
+                content_type: DefaultContentTypeEnum::Relationship,
+            },
+        ];
+        if with_images {
+            defaults.push(DefaultContentTypes {
+                extension: "png".to_owned(),
+                content_type: DefaultContentTypeEnum::ImagePng,
+            });
+            defaults.push(DefaultContentTypes {
+                extension: "jpeg".to_owned(),
+                content_type: DefaultContentTypeEnum::ImageJPEG,
+            });
+        }
+
+        Self {
+            defaults,
+            overrides: vec![],
+        }
+    }
+
+    /// Returns the content type of the part at `path` in the package.
+    ///
+    /// An override for the part takes precedence over the default for its extension.
+    /// Part names and extensions are compared case-insensitively, as required by OPC.
+    pub fn content_type_for_part(&self, path: &str) -> Option<&DefaultContentTypeEnum> {
+        let part_name = path.trim_start_matches('/');
+        let overridden = self.overrides.iter().find(|o| {
+            o.part_name
+                .trim_start_matches('/')
+                .eq_ignore_ascii_case(part_name)
+        });
+        if let Some(content_type) = overridden {
+            return Some(&content_type.content_type);
+        }
+
+        let file_name = part_name.rsplit('/').next().unwrap_or(part_name);
+        let (_, extension) = file_name.rsplit_once('.')?;
+        self.defaults
+            .iter()
+            .find(|d| d.extension.eq_ignore_ascii_case(extension))
+            .map(|d| &d.content_type)
+    }
 }
 
 /// Predefined content types supported by this library currently.
@@ -147,8 +208,77 @@ pub struct DefaultContentTypes {
     pub content_type: DefaultContentTypeEnum,
 }
 
+/// Internal structure for serde of [ContentTypes].
+#[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
+#[cfg_attr(feature = "speed-optimized-read", serde(rename = "Override"))]
+#[cfg_attr(feature = "memory-optimized-read", derive(FromXml))]
+#[cfg_attr(feature = "write", derive(ToXml))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    any(feature = "write", feature = "memory-optimized-read"),
+    xml(ns(CONTENT_TYPES_NS), rename = "Override")
+)]
+pub struct OverrideContentTypes {
+    /// The absolute path of the part in the package, e.g. `/3D/Textures/texture`.
+    #[cfg_attr(
+        any(feature = "write", feature = "memory-optimized-read"),
+        xml(attribute, rename = "PartName")
+    )]
+    #[cfg_attr(feature = "speed-optimized-read", serde(rename = "PartName"))]
+    pub part_name: String,
+
+    #[cfg_attr(
+        any(feature = "write", feature = "memory-optimized-read"),
+        xml(attribute, rename = "ContentType")
+    )]
+    #[cfg_attr(feature = "speed-optimized-read", serde(rename = "ContentType"))]
+    pub content_type: DefaultContentTypeEnum,
+}
+
 const CONTENT_TYPES_NS: &str = "http://schemas.openxmlformats.org/package/2006/content-types";
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ContentTypes, DefaultContentTypeEnum, DefaultContentTypes, OverrideContentTypes};
+
+    #[test]
+    pub fn content_type_for_part_test() {
+        let content_types = ContentTypes {
+            defaults: vec![
+                DefaultContentTypes {
+                    extension: "model".to_owned(),
+                    content_type: DefaultContentTypeEnum::Model,
+                },
+                DefaultContentTypes {
+                    extension: "png".to_owned(),
+                    content_type: DefaultContentTypeEnum::ImagePng,
+                },
+            ],
+            overrides: vec![OverrideContentTypes {
+                part_name: "/3D/Textures/texture.bin".to_owned(),
+                content_type: DefaultContentTypeEnum::ImageJPEG,
+            }],
+        };
+
+        assert_eq!(
+            content_types.content_type_for_part("/3D/3dmodel.model"),
+            Some(&DefaultContentTypeEnum::Model)
+        );
+        assert_eq!(
+            content_types.content_type_for_part("Metadata/thumbnail.PNG"),
+            Some(&DefaultContentTypeEnum::ImagePng)
+        );
+        assert_eq!(
+            content_types.content_type_for_part("3D/textures/Texture.bin"),
+            Some(&DefaultContentTypeEnum::ImageJPEG)
+        );
+        assert_eq!(content_types.content_type_for_part("/3D/other.bin"), None);
+        assert_eq!(content_types.content_type_for_part("/3D.model/part"), None);
+    }
+}
+
 #[cfg(feature = "write")]
 #[cfg(test)]
 mod write_tests {
@@ -157,7 +287,7 @@ mod write_tests {
 
     use super::{
         CONTENT_TYPES_NS, ContentTypes, DefaultContentTypeEnum, DefaultContentTypes, JPEG_NS,
-        MODEL_NS, PNG_NS, RELATIONSHIP_NS,
+        MODEL_NS, OverrideContentTypes, PNG_NS, RELATIONSHIP_NS,
     };
 
     #[test]
@@ -191,6 +321,49 @@ mod write_tests {
                     ),
                 },
             ],
+            overrides: vec![],
+        };
+        let content_string = to_string(&content).unwrap();
+
+        assert_eq!(content_string, xml_string);
+    }
+
+    #[test]
+    pub fn toxml_standard_3mf_content_types_test() {
+        let xml_string = format!(
+            r#"<Types xmlns="{}"><Default Extension="model" ContentType="{}" /><Default Extension="rels" ContentType="{}" /></Types>"#,
+            CONTENT_TYPES_NS, MODEL_NS, RELATIONSHIP_NS
+        );
+        assert_eq!(
+            to_string(&ContentTypes::standard_3mf(false)).unwrap(),
+            xml_string
+        );
+
+        let xml_string = format!(
+            r#"<Types xmlns="{}"><Default Extension="model" ContentType="{}" /><Default Extension="rels" ContentType="{}" /><Default Extension="png" ContentType="{}" /><Default Extension="jpeg" ContentType="{}" /></Types>"#,
+            CONTENT_TYPES_NS, MODEL_NS, RELATIONSHIP_NS, PNG_NS, JPEG_NS
+        );
+        assert_eq!(
+            to_string(&ContentTypes::standard_3mf(true)).unwrap(),
+            xml_string
+        );
+    }
+
+    #[test]
+    pub fn toxml_override_content_types_test() {
+        let xml_string = format!(
+            r#"<Types xmlns="{}"><Default Extension="model" ContentType="{}" /><Override PartName="/3D/Textures/texture.bin" ContentType="{}" /></Types>"#,
+            CONTENT_TYPES_NS, MODEL_NS, PNG_NS
+        );
+        let content = ContentTypes {
+            defaults: vec![DefaultContentTypes {
+                extension: "model".to_owned(),
+                content_type: DefaultContentTypeEnum::Model,
+            }],
+            overrides: vec![OverrideContentTypes {
+                part_name: "/3D/Textures/texture.bin".to_owned(),
+                content_type: DefaultContentTypeEnum::ImagePng,
+            }],
         };
         let content_string = to_string(&content).unwrap();
 
@@ -206,7 +379,7 @@ mod memory_optimized_read_tests {
 
     use super::{
         CONTENT_TYPES_NS, ContentTypes, DefaultContentTypeEnum, DefaultContentTypes, JPEG_NS,
-        MODEL_NS, PNG_NS, RELATIONSHIP_NS,
+        MODEL_NS, OverrideContentTypes, PNG_NS, RELATIONSHIP_NS,
     };
 
     #[test]
@@ -239,6 +412,7 @@ mod memory_optimized_read_tests {
                         content_type: DefaultContentTypeEnum::ImageJPEG,
                     },
                 ],
+                overrides: vec![],
             }
         );
     }
@@ -269,7 +443,30 @@ mod memory_optimized_read_tests {
                             "some/unknown/content".to_owned()
                         ),
                     }
-                ]
+                ],
+                overrides: vec![],
+            }
+        );
+    }
+    #[test]
+    pub fn fromxml_override_content_types_test() {
+        let xml_string = format!(
+            r#"<Types xmlns="{}"><Default Extension="model" ContentType="{}"/><Override PartName="/3D/Textures/texture.bin" ContentType="{}"/></Types>"#,
+            CONTENT_TYPES_NS, MODEL_NS, PNG_NS,
+        );
+        let content = from_str::<ContentTypes>(&xml_string).unwrap();
+
+        assert_eq!(
+            content,
+            ContentTypes {
+                defaults: vec![DefaultContentTypes {
+                    extension: "model".to_owned(),
+                    content_type: DefaultContentTypeEnum::Model,
+                }],
+                overrides: vec![OverrideContentTypes {
+                    part_name: "/3D/Textures/texture.bin".to_owned(),
+                    content_type: DefaultContentTypeEnum::ImagePng,
+                }],
             }
         );
     }
@@ -283,7 +480,7 @@ mod speed_optimized_read_tests {
 
     use super::{
         CONTENT_TYPES_NS, ContentTypes, DefaultContentTypeEnum, DefaultContentTypes, JPEG_NS,
-        MODEL_NS, PNG_NS, RELATIONSHIP_NS,
+        MODEL_NS, OverrideContentTypes, PNG_NS, RELATIONSHIP_NS,
     };
 
     #[test]
@@ -316,6 +513,7 @@ mod speed_optimized_read_tests {
                         content_type: DefaultContentTypeEnum::ImageJPEG,
                     },
                 ],
+                overrides: vec![],
             }
         );
     }
@@ -346,7 +544,30 @@ mod speed_optimized_read_tests {
                             "some/unknown/content".to_owned()
                         ),
                     }
-                ]
+                ],
+                overrides: vec![],
+            }
+        );
+    }
+    #[test]
+    pub fn fromxml_override_content_types_test() {
+        let xml_string = format!(
+            r#"<Types xmlns="{}"><Default Extension="model" ContentType="{}"/><Override PartName="/3D/Textures/texture.bin" ContentType="{}"/></Types>"#,
+            CONTENT_TYPES_NS, MODEL_NS, PNG_NS,
+        );
+        let content = from_str::<ContentTypes>(&xml_string).unwrap();
+
+        assert_eq!(
+            content,
+            ContentTypes {
+                defaults: vec![DefaultContentTypes {
+                    extension: "model".to_owned(),
+                    content_type: DefaultContentTypeEnum::Model,
+                }],
+                overrides: vec![OverrideContentTypes {
+                    part_name: "/3D/Textures/texture.bin".to_owned(),
+                    content_type: DefaultContentTypeEnum::ImagePng,
+                }],
             }
         );
     }
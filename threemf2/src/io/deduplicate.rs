@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use crate::{
+    core::{model::Model, object::Object},
+    io::ThreemfPackage,
+};
+
+/// Maps the model path, `None` for the root model, to the ids of the removed duplicates
+/// and the ids of the objects kept in their place.
+type Replacements = HashMap<Option<String>, HashMap<usize, usize>>;
+
+impl ThreemfPackage {
+    /// Groups the mesh objects of the package that have identical geometry.
+    ///
+    /// Objects are grouped by [Object::geometry_hash] and then compared, so a hash collision
+    /// never groups different meshes. Two objects are duplicates if their meshes are equal,
+    /// including the triangle properties, and they have the same type and default properties.
+    /// Property group ids and component references resolve within a model part, so only
+    /// objects of the same part are grouped.
+    ///
+    /// Objects are identified by their id and the path of their model part, `None` for the
+    /// root model. Only groups with more than one object are returned, the root model comes
+    /// first followed by the sub-models sorted by path, and objects keep the order of the
+    /// resources.
+    pub fn find_duplicate_objects(&self) -> Vec<Vec<(usize, Option<String>)>> {
+        let mut sub_models: Vec<_> = self.sub_models.iter().collect();
+        sub_models.sort_by_key(|(path, _)| *path);
+        let models = std::iter::once((None, &self.root)).chain(
+            sub_models
+                .into_iter()
+                .map(|(path, model)| (Some(path), model)),
+        );
+
+        let mut duplicates = vec![];
+        for (path, model) in models {
+            let mut groups: Vec<Vec<&Object>> = vec![];
+            let mut groups_by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+            for object in model.resources.object.iter().filter(|o| o.mesh.is_some()) {
+                let candidates = groups_by_hash.entry(object.geometry_hash()).or_default();
+                match candidates
+                    .iter()
+                    .find(|&&group| is_duplicate(groups[group][0], object))
+                    .copied()
+                {
+                    Some(group) => groups[group].push(object),
+                    None => {
+                        candidates.push(groups.len());
+                        groups.push(vec![object]);
+                    }
+                }
+            }
+
+            duplicates.extend(groups.into_iter().filter(|g| g.len() > 1).map(|group| {
+                group
+                    .into_iter()
+                    .map(|object| (object.id, path.cloned()))
+                    .collect()
+            }));
+        }
+
+        duplicates
+    }
+
+    /// Removes the duplicates found by [ThreemfPackage::find_duplicate_objects], keeping the
+    /// first object of every group.
+    ///
+    /// Build items, components and the clipping and representation meshes of beam lattices
+    /// referencing a removed object are rewritten to reference the kept object instead.
+    /// Only the name, part number, UUID and thumbnail of the kept object remain, those of the
+    /// removed duplicates are dropped.
+    ///
+    /// Returns the number of removed objects.
+    pub fn deduplicate_objects(&mut self) -> usize {
+        let mut replacements = Replacements::new();
+        let mut removed = 0;
+        for group in self.find_duplicate_objects() {
+            let (kept, _) = group[0];
+            for (id, path) in group.into_iter().skip(1) {
+                replacements.entry(path).or_default().insert(id, kept);
+                removed += 1;
+            }
+        }
+
+        if removed == 0 {
+            return 0;
+        }
+
+        rewrite_references(&mut self.root, None, &replacements);
+        for (path, model) in &mut self.sub_models {
+            rewrite_references(model, Some(path.as_str()), &replacements);
+        }
+
+        for (path, ids) in &replacements {
+            let model = match path {
+                Some(path) => self.sub_models.get_mut(path),
+                None => Some(&mut self.root),
+            };
+            if let Some(model) = model {
+                model
+                    .resources
+                    .object
+                    .retain(|object| !ids.contains_key(&object.id));
+            }
+        }
+
+        removed
+    }
+}
+
+fn is_duplicate(a: &Object, b: &Object) -> bool {
+    a.mesh == b.mesh && a.objecttype == b.objecttype && a.pid == b.pid && a.pindex == b.pindex
+}
+
+/// Rewrites the object references of the model at `model_path` that point to a removed
+/// duplicate. References without a path resolve to the model itself.
+fn rewrite_references(model: &mut Model, model_path: Option<&str>, replacements: &Replacements) {
+    let kept_id = |path: Option<&str>, id: usize| {
+        replacements
+            .get(&path.or(model_path).map(str::to_owned))
+            .and_then(|ids| ids.get(&id))
+            .copied()
+    };
+
+    for item in &mut model.build.item {
+        if let Some(id) = kept_id(item.path.as_deref(), item.objectid) {
+            item.objectid = id;
+        }
+    }
+
+    for object in &mut model.resources.object {
+        if let Some(components) = &mut object.components {
+            for component in &mut components.component {
+                if let Some(id) = kept_id(component.path.as_deref(), component.objectid) {
+                    component.objectid = id;
+                }
+            }
+        }
+
+        if let Some(beamlattice) = object.mesh.as_mut().and_then(|m| m.beamlattice.as_mut()) {
+            for mesh_id in [
+                &mut beamlattice.clippingmesh,
+                &mut beamlattice.representationmesh,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if let Some(id) = kept_id(None, *mesh_id) {
+                    *mesh_id = id;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        core::{component::Components, test_support::cube},
+        io::{
+            ThreemfPackage,
+            test_support::{component, item, model, object},
+        },
+    };
+
+    use std::collections::HashMap;
+
+    const SUB_MODEL_PATH: &str = "/3D/Objects/cubes.model";
+
+    fn package() -> ThreemfPackage {
+        let root = model(
+            vec![
+                object(1, Some(cube(1.0)), None),
+                object(2, Some(cube(1.0)), None),
+                object(3, Some(cube(2.0)), None),
+                object(
+                    4,
+                    None,
+                    Some(vec![component(2, None, None), component(3, None, None)]),
+                ),
+            ],
+            vec![],
+            vec![
+                item(1, None, None),
+                item(2, None, None),
+                item(4, None, None),
+                item(5, None, Some(SUB_MODEL_PATH)),
+            ],
+        );
+        let sub_model = model(
+            vec![
+                object(2, Some(cube(1.0)), None),
+                object(5, Some(cube(1.0)), None),
+            ],
+            vec![],
+            vec![],
+        );
+
+        let mut package = ThreemfPackage::from_root_model(root);
+        package.sub_models = HashMap::from([(SUB_MODEL_PATH.to_owned(), sub_model)]);
+        package
+    }
+
+    #[test]
+    fn find_duplicate_objects_test() {
+        let package = package();
+
+        assert_eq!(
+            package.find_duplicate_objects(),
+            vec![
+                vec![(1, None), (2, None)],
+                vec![
+                    (2, Some(SUB_MODEL_PATH.to_owned())),
+                    (5, Some(SUB_MODEL_PATH.to_owned()))
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn find_duplicate_objects_compares_properties_test() {
+        let mut package = package();
+        package.sub_models.clear();
+        package.root.resources.object[1].pid = Some(7);
+
+        assert!(package.find_duplicate_objects().is_empty());
+    }
+
+    #[test]
+    fn deduplicate_objects_test() {
+        let mut package = package();
+
+        assert_eq!(package.deduplicate_objects(), 2);
+
+        let root = &package.root;
+        let ids: Vec<_> = root.resources.object.iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        let items: Vec<_> = root.build.item.iter().map(|i| i.objectid).collect();
+        assert_eq!(items, vec![1, 1, 4, 2]);
+        assert_eq!(
+            root.resources.object[2].components,
+            Some(Components {
+                component: vec![component(1, None, None), component(3, None, None)],
+            })
+        );
+
+        let sub_model = &package.sub_models[SUB_MODEL_PATH];
+        assert_eq!(sub_model.resources.object.len(), 1);
+        assert_eq!(sub_model.resources.object[0].id, 2);
+
+        assert!(package.find_duplicate_objects().is_empty());
+        assert_eq!(package.deduplicate_objects(), 0);
+    }
+}
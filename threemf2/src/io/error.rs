@@ -1,6 +1,8 @@
 use thiserror::Error;
 use zip::result::ZipError;
 
+use crate::core::beamlattice::BeamLatticeError;
+
 /// An error that can occur while writing a 3MF file
 #[derive(Debug, Error)]
 pub enum Error {
@@ -25,10 +27,136 @@ pub enum Error {
     #[error("Thumbnail error: {0}")]
     ThumbnailError(String),
 
+    /// An object references a thumbnail that is not part of the package.
+    #[error("Thumbnail {path} of object {object_id} is missing from the package")]
+    MissingThumbnail { object_id: usize, path: String },
+
     #[error("Resource not found: {0}")]
     ResourceNotFound(String),
 
+    /// An object defines both a mesh and components, which is forbidden by the 3MF Core specification.
+    #[error("Object {id} has both a mesh and components")]
+    ObjectHasMeshAndComponents { id: usize },
+
+    /// An object has no geometry, i.e. neither a non-empty mesh nor any component.
+    #[error("Object {id} has no geometry")]
+    EmptyObject { id: usize },
+
+    /// A triangle references the same vertex more than once, e.g. `v1 == v2`.
+    #[error("Triangle {triangle} of object {object_id} references the same vertex more than once")]
+    DegenerateTriangleIndices { object_id: usize, triangle: usize },
+
+    /// A triangle references a vertex that is not part of its mesh.
+    #[error(
+        "Triangle {triangle} of object {object_id} references vertex {index}, which does not exist"
+    )]
+    TriangleIndexOutOfBounds {
+        object_id: usize,
+        triangle: usize,
+        index: usize,
+    },
+
+    /// A mesh has triangles but no vertices, e.g. from a truncated write.
+    #[error("Mesh of object {object_id} has triangles but no vertices")]
+    TrianglesWithoutVertices { object_id: usize },
+
+    /// The beam lattice of a mesh is invalid, e.g. a beam references a vertex that is not
+    /// part of the mesh.
+    #[error("Beam lattice of object {object_id} is invalid: {source}")]
+    BeamLattice {
+        object_id: usize,
+        source: BeamLatticeError,
+    },
+
+    /// An object or one of its triangles references a property group that is not a resource
+    /// of its model.
+    #[error("Object {object_id} references property group {pid}, which does not exist")]
+    UnknownPropertyGroup { object_id: usize, pid: usize },
+
+    /// An object or one of its triangles references a base material that is not part of its
+    /// base materials group.
+    #[error(
+        "Object {object_id} references material {index} of base materials {pid}, which does not exist"
+    )]
+    PropertyIndexOutOfBounds {
+        object_id: usize,
+        pid: usize,
+        index: usize,
+    },
+
+    /// A prefix listed in `requiredextensions` or `recommendedextensions` of a model has no
+    /// matching `xmlns` declaration on the model element.
+    #[error("Extension prefix {0} is not declared on its model")]
+    UndeclaredExtensionPrefix(String),
+
+    /// An object references itself through its components.
+    #[error("Object {id} references itself through its components")]
+    RecursiveComponent { id: usize },
+
+    /// Components are nested deeper than the maximum depth of a traversal.
+    #[error("Components are nested deeper than {max_depth} objects")]
+    ComponentDepthExceeded { max_depth: usize },
+
+    /// The package exceeds a limit of the [ReadLimits](crate::io::ReadLimits) it was read with.
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    #[error("The package has more than {limit} {kind}")]
+    ComplexityLimitExceeded {
+        kind: crate::io::ComplexityLimit,
+        limit: usize,
+    },
+
+    /// A model part path is not absolute or doesn't have the `.model` extension.
+    #[error("Model path {0} must be absolute and end with .model")]
+    InvalidModelPath(String),
+
+    /// Reading a part of the package failed with an I/O, ZIP or XML error.
+    #[error("failed reading {path}")]
+    PartRead { path: String, source: Box<Error> },
+
+    /// Writing a part of the package failed with an I/O, ZIP or XML error.
+    #[error("failed writing {path}")]
+    PartWrite { path: String, source: Box<Error> },
+
     #[cfg(feature = "speed-optimized-read")]
     #[error("Deserialization error from serde-roxmltree")]
     SerdeRoxmltreeError(#[from] serde_roxmltree::Error),
 }
+
+impl Error {
+    /// Wraps I/O, ZIP and XML errors, which don't tell which part they were raised for, into
+    /// [Error::PartRead] with the absolute `path` of the part. Other errors are returned as
+    /// they are.
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    pub(crate) fn reading_part(self, path: &str) -> Self {
+        self.in_part(path, |path, source| Error::PartRead { path, source })
+    }
+
+    /// Same as `reading_part` but wraps into [Error::PartWrite].
+    #[cfg(feature = "io-write")]
+    pub(crate) fn writing_part(self, path: &str) -> Self {
+        self.in_part(path, |path, source| Error::PartWrite { path, source })
+    }
+
+    fn in_part(self, path: &str, wrap: fn(String, Box<Error>) -> Error) -> Self {
+        let lacks_path = match self {
+            Error::Io(_) | Error::Zip(_) => true,
+            #[cfg(any(feature = "write", feature = "memory-optimized-read"))]
+            Error::InstantXmlError(_) => true,
+            #[cfg(feature = "speed-optimized-read")]
+            Error::SerdeRoxmltreeError(_) => true,
+            _ => false,
+        };
+        if lacks_path {
+            let path = format!("/{}", path.trim_start_matches('/'));
+            wrap(path, Box::new(self))
+        } else {
+            self
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::{collections::HashSet, fs::File, path::Path};
+
+use crate::{
+    core::{
+        build::{Build, Item},
+        model::Model,
+        transform::Transform,
+    },
+    io::{
+        PackageBuilder, ThreemfPackage,
+        error::Error,
+        query::{DEFAULT_MAX_COMPONENT_DEPTH, ItemRef, locate_object, walk_components},
+        relationship::{Relationship, RelationshipType, Relationships},
+        utils,
+    },
+};
+
+/// How [ThreemfPackage::split_into_single_part_packages] and [ThreemfPackage::write_split_to_dir]
+/// name the package of each build item.
+///
+/// Names taken from the model are made safe for file systems and zip archives: characters
+/// invalid in their paths, like `/`, `\` or `:`, and whitespace are replaced with `_`, and
+/// leading or trailing `_` and `.` are removed. Names reserved for devices on Windows, like
+/// `CON` or `COM1.v2`, get a `_` appended to the part before the first `.`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NamingScheme {
+    /// `object-<id>` with the id of the object of the build item.
+    #[default]
+    ObjectId,
+
+    /// The name of the object of the build item. Objects without a name fall back to
+    /// [NamingScheme::ObjectId].
+    Name,
+
+    /// The part number of the build item, or of its object if the item has none. Items
+    /// without a part number fall back to [NamingScheme::ObjectId].
+    PartNumber,
+}
+
+impl ThreemfPackage {
+    /// Splits the package into one self-contained package per build item of the root model,
+    /// see [ThreemfPackage::export_item].
+    ///
+    /// The packages are returned in the order of the build items together with a file name
+    /// ending with `.3mf` according to `scheme`. File names are unique ignoring case, later
+    /// items with a name already taken get a `_2`, `_3`, ... suffix.
+    ///
+    /// Fails with the errors of [ThreemfPackage::export_item].
+    pub fn split_into_single_part_packages(
+        &self,
+        scheme: NamingScheme,
+    ) -> Result<Vec<(String, ThreemfPackage)>, Error> {
+        let mut taken = HashSet::new();
+        self.root
+            .build
+            .item
+            .iter()
+            .map(|item| {
+                let package = self.export_item(&ItemRef {
+                    item,
+                    origin_model_path: None,
+                })?;
+
+                let object = locate_object(self, item.objectid, item.path.as_deref())
+                    .map(|(object, _)| object.object);
+                let name = match scheme {
+                    NamingScheme::ObjectId => None,
+                    NamingScheme::Name => object.and_then(|o| o.name.as_deref()),
+                    NamingScheme::PartNumber => item
+                        .partnumber
+                        .as_deref()
+                        .or(object.and_then(|o| o.partnumber.as_deref())),
+                }
+                .map(sanitize_file_name)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("object-{}", item.objectid));
+
+                Ok((format!("{}.3mf", unique_name(name, &mut taken)), package))
+            })
+            .collect()
+    }
+
+    /// Splits the package like [ThreemfPackage::split_into_single_part_packages] and writes
+    /// each package as a `.3mf` file named according to `scheme` into `dir`.
+    ///
+    /// A missing `dir` is created and existing files are overwritten.
+    pub fn write_split_to_dir(&self, dir: &Path, scheme: NamingScheme) -> Result<(), Error> {
+        std::fs::create_dir_all(dir)?;
+        for (name, package) in self.split_into_single_part_packages(scheme)? {
+            package.write(File::create(dir.join(name))?)?;
+        }
+        Ok(())
+    }
+
+    /// Exports a single build item into a new, self-contained package.
+    ///
+    /// The package holds the objects reachable from the item through its components,
+    /// including the ones in other model parts which are kept at their paths, and the
+    /// clipping and representation meshes of their beam lattices. The root model part keeps
+    /// its path, unit and metadata, and its build consists of a copy of `item` with its
+    /// transform, part number and UUID. If the object of the item lives in another model
+    /// part, the copy references it through its path.
+    ///
+    /// Only the base materials referenced by the kept objects are carried over, other
+    /// resources unknown to this library are kept as they are. The thumbnails of the kept
+    /// objects and the content types are copied from this package.
+    ///
+    /// Fails with [Error::ResourceNotFound] if a referenced object or model part doesn't
+    /// exist, with [Error::RecursiveComponent] if components reference each other recursively
+    /// or with [Error::ComponentDepthExceeded] if they are nested too deeply.
+    pub fn export_item(&self, item: &ItemRef) -> Result<ThreemfPackage, Error> {
+        let root_path = self.root_model_path();
+        // items without a path reference the model containing them
+        let object_path = item
+            .item
+            .path
+            .as_deref()
+            .or(item.origin_model_path)
+            .filter(|path| *path != root_path);
+
+        let mut reachable: Vec<(Option<&str>, Vec<usize>)> = vec![];
+        walk_components(
+            self,
+            object_path,
+            item.objectid(),
+            Transform::IDENTITY,
+            DEFAULT_MAX_COMPONENT_DEPTH,
+            |path, model, object, _| {
+                let ids = match reachable.iter().position(|(p, _)| *p == path) {
+                    Some(index) => &mut reachable[index].1,
+                    None => {
+                        reachable.push((path, vec![]));
+                        &mut reachable.last_mut().expect("just pushed").1
+                    }
+                };
+                let lattice = object.mesh.as_ref().and_then(|m| m.beamlattice.as_ref());
+                let meshes = lattice
+                    .into_iter()
+                    .flat_map(|l| [l.clippingmesh, l.representationmesh])
+                    .flatten()
+                    .filter(|id| model.resources.object.iter().any(|o| o.id == *id));
+                for id in std::iter::once(object.id).chain(meshes) {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        let root_ids = reachable
+            .iter()
+            .find(|(path, _)| path.is_none())
+            .map_or(&[][..], |(_, ids)| ids.as_slice());
+        let mut root = export_model(&self.root, root_ids);
+        root.build = Build {
+            uuid: self.root.build.uuid.clone(),
+            item: vec![Item {
+                path: object_path.map(str::to_owned),
+                ..item.item.clone()
+            }],
+        };
+        root.keep_used_extensions(&self.root);
+
+        let mut package = ThreemfPackage::from_root_model_at(root, root_path)?;
+        package.content_types = self.content_types.clone();
+        for (path, ids) in reachable
+            .iter()
+            .filter_map(|(path, ids)| Some(((*path)?, ids)))
+        {
+            // the traversal already failed if the model part doesn't exist
+            let model = export_model(&self.sub_models[path], ids);
+            package.sub_models.insert(path.to_owned(), model);
+            add_relationship(&mut package, root_path, path, RelationshipType::Model);
+        }
+
+        let models = std::iter::once((root_path.to_owned(), &package.root))
+            .chain(package.sub_models.iter().map(|(p, m)| (p.clone(), m)));
+        let mut thumbnails = vec![];
+        for (model_path, model) in models {
+            for thumbnail in model
+                .resources
+                .object
+                .iter()
+                .filter_map(|o| o.thumbnail.as_ref())
+            {
+                let path_in_package = utils::try_strip_leading_slash(thumbnail);
+                if let Some((path, handle)) = self
+                    .thumbnails
+                    .iter()
+                    .find(|(path, _)| utils::try_strip_leading_slash(path) == path_in_package)
+                {
+                    thumbnails.push((model_path.clone(), path.clone(), handle.clone()));
+                }
+            }
+        }
+        for (model_path, path, handle) in thumbnails {
+            if package.thumbnails.insert(path.clone(), handle).is_none() {
+                add_relationship(
+                    &mut package,
+                    &model_path,
+                    &path,
+                    RelationshipType::Thumbnail,
+                );
+            }
+        }
+
+        Ok(package)
+    }
+}
+
+/// Copies the objects with the given ids of `source` into a model with an empty build
+/// holding only the base materials referenced by those objects.
+fn export_model(source: &Model, object_ids: &[usize]) -> Model {
+    let mut model = source.subset(object_ids);
+    model.build = Build::default();
+
+    let mut used = HashSet::new();
+    for object in &model.resources.object {
+        used.extend(object.pid);
+        if let Some(mesh) = &object.mesh {
+            used.extend(mesh.triangles.triangle.iter().filter_map(|t| t.pid));
+            if let Some(lattice) = &mesh.beamlattice {
+                used.extend(lattice.pid);
+                used.extend(lattice.beams.beam.iter().filter_map(|b| b.pid));
+                used.extend(
+                    lattice
+                        .balls
+                        .iter()
+                        .flat_map(|b| &b.ball)
+                        .filter_map(|b| b.pid),
+                );
+            }
+        }
+    }
+    model
+        .resources
+        .basematerials
+        .retain(|materials| used.contains(&materials.id));
+    model.keep_used_extensions(source);
+    model
+}
+
+/// Adds a relationship to `target` to the relationship file of the part at `part_path`.
+fn add_relationship(
+    package: &mut ThreemfPackage,
+    part_path: &str,
+    target: &str,
+    relationship_type: RelationshipType,
+) {
+    let rels = package
+        .relationships
+        .entry(PackageBuilder::rels_path(part_path))
+        .or_insert(Relationships {
+            relationships: vec![],
+        });
+    rels.relationships.push(Relationship {
+        id: format!("rel{}", rels.relationships.len()),
+        target: target.to_owned(),
+        relationship_type,
+    });
+}
+
+/// Device names that Windows reserves regardless of case and extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Replaces the characters invalid in file system and zip paths and whitespace with `_`,
+/// and appends `_` to the stem of names reserved on Windows.
+fn sanitize_file_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_whitespace() || c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+    let name = name.trim_matches(['_', '.']);
+
+    let stem = name.split('.').next().unwrap_or_default();
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        format!("{stem}_{}", &name[stem.len()..])
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Returns `name`, or `name` with the first free `_2`, `_3`, ... suffix if it is already
+/// in `taken` ignoring case, and adds the result to `taken`.
+fn unique_name(name: String, taken: &mut HashSet<String>) -> String {
+    let mut unique = name.clone();
+    let mut suffix = 1;
+    while !taken.insert(unique.to_lowercase()) {
+        suffix += 1;
+        unique = format!("{name}_{suffix}");
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{NamingScheme, sanitize_file_name, unique_name};
+    use crate::{
+        core::model::Unit,
+        io::{ModelBuilder, ThreemfPackage},
+    };
+
+    #[test]
+    fn sanitize_file_name_test() {
+        assert_eq!(sanitize_file_name("Bracket"), "Bracket");
+        assert_eq!(
+            sanitize_file_name("Left/Right Bracket"),
+            "Left_Right_Bracket"
+        );
+        assert_eq!(
+            sanitize_file_name(r#"C:\parts\a*b?"<v1>|"#),
+            "C__parts_a_b___v1"
+        );
+        assert_eq!(sanitize_file_name(" ../hidden\t"), "hidden");
+        assert_eq!(sanitize_file_name("v1.2"), "v1.2");
+        assert_eq!(sanitize_file_name("/ /"), "");
+        assert_eq!(sanitize_file_name("con"), "con_");
+        assert_eq!(sanitize_file_name("NUL.v2"), "NUL_.v2");
+        assert_eq!(sanitize_file_name("Com1"), "Com1_");
+        assert_eq!(sanitize_file_name("COM10"), "COM10");
+        assert_eq!(sanitize_file_name("console"), "console");
+    }
+
+    #[test]
+    fn unique_name_test() {
+        let mut taken = HashSet::new();
+        let names = ["part", "part", "Part", "part_2", "part"]
+            .map(|name| unique_name(name.to_owned(), &mut taken));
+        assert_eq!(names, ["part", "part_2", "Part_3", "part_2_2", "part_4"]);
+    }
+
+    #[test]
+    fn split_into_single_part_packages_test() {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, false);
+        builder.add_build(None).unwrap();
+        let mut ids = vec![];
+        for name in ["Left/Bracket", "Left Bracket", ""] {
+            let id = builder
+                .add_mesh_object(|obj| {
+                    if !name.is_empty() {
+                        obj.name(name);
+                    }
+                    obj.part_number("P 100");
+                    obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                    obj.add_triangle(&[0, 1, 2]);
+                    Ok(())
+                })
+                .unwrap();
+            ids.push(id);
+        }
+        for &id in &ids {
+            builder.add_build_item(id).unwrap();
+        }
+        builder
+            .add_build_item_advanced(ids[0], |item| {
+                item.partnumber("Q/1");
+            })
+            .unwrap();
+        let package = ThreemfPackage::from(builder.build().unwrap());
+
+        let names = |scheme| {
+            package
+                .split_into_single_part_packages(scheme)
+                .unwrap()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(NamingScheme::ObjectId),
+            [
+                "object-1.3mf",
+                "object-2.3mf",
+                "object-3.3mf",
+                "object-1_2.3mf"
+            ]
+        );
+        assert_eq!(
+            names(NamingScheme::Name),
+            [
+                "Left_Bracket.3mf",
+                "Left_Bracket_2.3mf",
+                "object-3.3mf",
+                "Left_Bracket_3.3mf"
+            ]
+        );
+        assert_eq!(
+            names(NamingScheme::PartNumber),
+            ["P_100.3mf", "P_100_2.3mf", "P_100_3.3mf", "Q_1.3mf"]
+        );
+
+        let packages = package
+            .split_into_single_part_packages(NamingScheme::default())
+            .unwrap();
+        for ((_, exported), item) in packages.iter().zip(&package.root.build.item) {
+            assert_eq!(exported.root.build.item, vec![item.clone()]);
+            assert_eq!(exported.root.resources.object.len(), 1);
+        }
+
+        let dir = std::env::temp_dir().join(format!("threemf2-write-split-{}", std::process::id()));
+        package
+            .write_split_to_dir(&dir, NamingScheme::PartNumber)
+            .unwrap();
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(&dir).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            ["P_100.3mf", "P_100_2.3mf", "P_100_3.3mf", "Q_1.3mf"]
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use crate::{
+    core::{
+        build::{Build, Item},
+        mesh::{Mesh, Triangle, Triangles, Vertex, Vertices},
+        model::Model,
+        object::Object,
+        resources::{Base, BaseMaterials, Resources},
+        transform::Transform,
+    },
+    io::{
+        ThreemfPackage,
+        error::Error,
+        query::{DEFAULT_MAX_COMPONENT_DEPTH, walk_components},
+    },
+};
+
+/// Id of the single object of a flattened model.
+const FLATTENED_OBJECT_ID: usize = 1;
+
+/// Id of the merged base materials group of a flattened model.
+const FLATTENED_MATERIALS_ID: usize = 2;
+
+impl ThreemfPackage {
+    /// Flattens the build of the package into a new root [`Model`] holding a single mesh
+    /// object that is referenced by a single build item.
+    ///
+    /// Every build item is resolved through its components, including the ones referencing
+    /// another model part, and the accumulated transforms are applied to the vertices.
+    /// Referenced objects are looked up by id, independent of their order in the resources.
+    /// Triangles of mirrored geometry are reoriented so their normals keep pointing outwards.
+    ///
+    /// The base materials referenced by the triangles are merged into a single group and the
+    /// property indices are remapped, identical bases are only added once. If any triangle has
+    /// a material, the object defaults to the first base of the merged group.
+    /// Properties referencing other property groups, beam lattices and triangle sets are not
+    /// carried over.
+    ///
+    /// The unit and the metadata of the root model are kept.
+    ///
+    /// Returns an error if a build item or component references an object or model part
+    /// that does not exist, if components reference each other recursively, or if a build
+    /// item resolves through more than [DEFAULT_MAX_COMPONENT_DEPTH] nested objects.
+    pub fn flatten_to_single_object(&self) -> Result<Model, Error> {
+        self.flatten_to_single_object_with_max_depth(DEFAULT_MAX_COMPONENT_DEPTH)
+    }
+
+    /// Same as [ThreemfPackage::flatten_to_single_object] but fails with
+    /// [Error::ComponentDepthExceeded] if a build item resolves through more than `max_depth`
+    /// nested objects.
+    pub fn flatten_to_single_object_with_max_depth(
+        &self,
+        max_depth: usize,
+    ) -> Result<Model, Error> {
+        let mut flattener = Flattener::new(self);
+        for item in &self.root.build.item {
+            let transform = item.transform.clone().unwrap_or(Transform::IDENTITY);
+            walk_components(
+                self,
+                item.path.as_deref(),
+                item.objectid,
+                transform,
+                max_depth,
+                |path, model, object, transform| match &object.mesh {
+                    Some(mesh) => flattener.add_mesh(path, model, object, mesh, transform),
+                    None => Ok(()),
+                },
+            )?;
+        }
+
+        Ok(flattener.into_model())
+    }
+}
+
+/// Accumulates the geometry of the build items of a package into one mesh.
+struct Flattener<'a> {
+    package: &'a ThreemfPackage,
+    vertices: Vec<Vertex>,
+    triangles: Vec<Triangle>,
+    bases: Vec<Base>,
+    /// Maps (model path, basematerials id, index) to the index in the merged bases.
+    base_indices: HashMap<(Option<&'a str>, usize, usize), usize>,
+}
+
+impl<'a> Flattener<'a> {
+    fn new(package: &'a ThreemfPackage) -> Self {
+        Self {
+            package,
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+            bases: Vec::new(),
+            base_indices: HashMap::new(),
+        }
+    }
+
+    fn add_mesh(
+        &mut self,
+        path: Option<&'a str>,
+        model: &'a Model,
+        object: &Object,
+        mesh: &Mesh,
+        transform: &Transform,
+    ) -> Result<(), Error> {
+        let offset = self.vertices.len();
+        let vertex_count = mesh.vertices.vertex.len();
+        let mirrored = transform.determinant() < 0.0;
+
+        for (index, triangle) in mesh.triangles.triangle.iter().enumerate() {
+            if [triangle.v1, triangle.v2, triangle.v3]
+                .iter()
+                .any(|&v| v >= vertex_count)
+            {
+                return Err(Error::ResourceNotFound(format!(
+                    "Vertex of triangle {index} in object {}",
+                    object.id
+                )));
+            }
+
+            let properties = triangle
+                .resolved_properties(object.pid, object.pindex)
+                .and_then(|(pid, indices)| self.merge_bases(path, model, pid, indices));
+
+            let [p1, p2, p3] = match properties {
+                Some(indices) => indices.map(Some),
+                None => [None; 3],
+            };
+            let mut flattened = Triangle {
+                v1: offset + triangle.v1,
+                v2: offset + triangle.v2,
+                v3: offset + triangle.v3,
+                p1,
+                p2,
+                p3,
+                pid: properties.map(|_| FLATTENED_MATERIALS_ID),
+            };
+            if mirrored {
+                std::mem::swap(&mut flattened.v2, &mut flattened.v3);
+                std::mem::swap(&mut flattened.p2, &mut flattened.p3);
+            }
+            self.triangles.push(flattened);
+        }
+
+        self.vertices.extend(mesh.vertices.vertex.iter().map(|v| {
+            let [x, y, z] = transform.transform_point([v.x, v.y, v.z]);
+            Vertex { x, y, z }
+        }));
+
+        Ok(())
+    }
+
+    /// Returns the indices into the merged bases, or `None` if `pid` is not a base materials
+    /// group of the model or an index is out of range.
+    fn merge_bases(
+        &mut self,
+        path: Option<&'a str>,
+        model: &Model,
+        pid: usize,
+        indices: [usize; 3],
+    ) -> Option<[usize; 3]> {
+        let group = model.resources.basematerials.iter().find(|g| g.id == pid)?;
+        if indices.iter().any(|&i| i >= group.base.len()) {
+            return None;
+        }
+
+        Some(indices.map(|index| {
+            *self
+                .base_indices
+                .entry((path, pid, index))
+                .or_insert_with(|| {
+                    let base = &group.base[index];
+                    match self.bases.iter().position(|b| b == base) {
+                        Some(position) => position,
+                        None => {
+                            self.bases.push(base.clone());
+                            self.bases.len() - 1
+                        }
+                    }
+                })
+        }))
+    }
+
+    fn into_model(self) -> Model {
+        let has_materials = !self.bases.is_empty();
+
+        let object = Object {
+            id: FLATTENED_OBJECT_ID,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: has_materials.then_some(FLATTENED_MATERIALS_ID),
+            pindex: has_materials.then_some(0),
+            uuid: None,
+            mesh: Some(Mesh {
+                vertices: Vertices {
+                    vertex: self.vertices,
+                },
+                triangles: Triangles {
+                    triangle: self.triangles,
+                },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: None,
+        };
+
+        let basematerials = if has_materials {
+            vec![BaseMaterials {
+                id: FLATTENED_MATERIALS_ID,
+                base: self.bases,
+            }]
+        } else {
+            vec![]
+        };
+
+        Model {
+            unit: self.package.root.unit.clone(),
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: self.package.root.metadata.clone(),
+            resources: Resources {
+                unknown_resources: vec![],
+                object: vec![object],
+                basematerials,
+            },
+            build: Build {
+                uuid: None,
+                item: vec![Item {
+                    objectid: FLATTENED_OBJECT_ID,
+                    ..Default::default()
+                }],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        core::{
+            mesh::{Mesh, Triangle, Triangles, Vertex, Vertices},
+            resources::{Base, BaseMaterials},
+            transform::Transform,
+        },
+        io::{
+            ThreemfPackage,
+            error::Error,
+            test_support::{component, item, model, object},
+        },
+    };
+
+    fn triangle_mesh(pid: Option<usize>, p1: Option<usize>) -> Mesh {
+        Mesh {
+            vertices: Vertices {
+                vertex: vec![
+                    Vertex {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    Vertex {
+                        x: 1.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    Vertex {
+                        x: 0.0,
+                        y: 1.0,
+                        z: 0.0,
+                    },
+                ],
+            },
+            triangles: Triangles {
+                triangle: vec![Triangle {
+                    v1: 0,
+                    v2: 1,
+                    v3: 2,
+                    p1,
+                    p2: None,
+                    p3: None,
+                    pid,
+                }],
+            },
+            trianglesets: None,
+            beamlattice: None,
+        }
+    }
+
+    fn base(name: &str) -> Base {
+        Base {
+            name: name.to_owned(),
+            displaycolor: "#FFFFFF".to_owned(),
+        }
+    }
+
+    #[rustfmt::skip]
+    fn translation(x: f64, y: f64, z: f64) -> Transform {
+        Transform([
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            x, y, z,
+        ])
+    }
+
+    #[test]
+    fn flatten_to_single_object_test() {
+        #[rustfmt::skip]
+        let mirror = Transform([
+            -1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0,
+        ]);
+
+        let sub_model = model(
+            vec![object(1, Some(triangle_mesh(Some(5), Some(0))), None)],
+            vec![BaseMaterials {
+                id: 5,
+                base: vec![base("Red")],
+            }],
+            vec![],
+        );
+        let root = model(
+            vec![
+                object(1, Some(triangle_mesh(Some(2), Some(1))), None),
+                object(
+                    3,
+                    None,
+                    Some(vec![
+                        component(1, Some(mirror), None),
+                        component(1, None, Some("/3D/Objects/part.model")),
+                    ]),
+                ),
+            ],
+            vec![BaseMaterials {
+                id: 2,
+                base: vec![base("Blue"), base("Red")],
+            }],
+            vec![
+                item(1, Some(translation(10.0, 0.0, 0.0)), None),
+                item(3, Some(translation(0.0, 0.0, 5.0)), None),
+            ],
+        );
+
+        let mut package = ThreemfPackage::from(root);
+        package
+            .sub_models
+            .insert("/3D/Objects/part.model".to_owned(), sub_model);
+
+        let flattened = package.flatten_to_single_object().unwrap();
+        assert_eq!(flattened.build.item.len(), 1);
+        assert_eq!(flattened.resources.object.len(), 1);
+        assert_eq!(
+            flattened.resources.basematerials,
+            vec![BaseMaterials {
+                id: 2,
+                base: vec![base("Red")],
+            }]
+        );
+
+        let object = &flattened.resources.object[0];
+        assert_eq!(flattened.build.item[0].objectid, object.id);
+        assert_eq!((object.pid, object.pindex), (Some(2), Some(0)));
+
+        let mesh = object.mesh.as_ref().unwrap();
+        assert_eq!(mesh.vertices.vertex.len(), 9);
+        assert_eq!(mesh.vertices.vertex[1].x, 11.0);
+        // mirrored copy
+        assert_eq!(mesh.vertices.vertex[4].x, -1.0);
+        assert_eq!(mesh.vertices.vertex[4].z, 5.0);
+
+        let triangles = &mesh.triangles.triangle;
+        assert_eq!(triangles.len(), 3);
+        assert_eq!(
+            [triangles[0].v1, triangles[0].v2, triangles[0].v3],
+            [0, 1, 2]
+        );
+        // the orientation of the mirrored copy is flipped
+        assert_eq!(
+            [triangles[1].v1, triangles[1].v2, triangles[1].v3],
+            [3, 5, 4]
+        );
+        assert_eq!(
+            [triangles[2].v1, triangles[2].v2, triangles[2].v3],
+            [6, 7, 8]
+        );
+        // Red of both models is merged into one base
+        for triangle in triangles {
+            assert_eq!(triangle.pid, Some(2));
+            assert_eq!([triangle.p1, triangle.p2, triangle.p3], [Some(0); 3]);
+        }
+    }
+
+    #[test]
+    fn flatten_to_single_object_errors_test() {
+        let missing = ThreemfPackage::from(model(vec![], vec![], vec![item(1, None, None)]));
+        assert!(matches!(
+            missing.flatten_to_single_object(),
+            Err(Error::ResourceNotFound(_))
+        ));
+
+        let recursive = ThreemfPackage::from(model(
+            vec![
+                object(1, None, Some(vec![component(2, None, None)])),
+                object(2, None, Some(vec![component(1, None, None)])),
+            ],
+            vec![],
+            vec![item(1, None, None)],
+        ));
+        assert!(matches!(
+            recursive.flatten_to_single_object(),
+            Err(Error::RecursiveComponent { id: 1 })
+        ));
+    }
+}
@@ -9,6 +9,9 @@ pub use utils::parse_xmlns_attributes;
 
 pub mod thumbnail_handle;
 
+mod capabilities;
+pub use capabilities::{Capabilities, capabilities};
+
 /// Represents an XML namespace declaration with its prefix and URI
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct XmlNamespace {
@@ -32,18 +35,70 @@ pub struct ModelNamespaces {
 ))]
 mod zip_utils;
 
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+mod read_options;
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub use read_options::{ComplexityLimit, ReadLimits, ReadOptions};
+
+#[cfg(feature = "io-write")]
+mod write_options;
+#[cfg(feature = "io-write")]
+pub use write_options::{CompressionMethod, WriteOptions};
+
 #[cfg(any(
     feature = "io-write",
     feature = "io-memory-optimized-read",
     feature = "io-speed-optimized-read"
 ))]
 mod threemf_package;
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub use threemf_package::OpcView;
+#[cfg(any(
+    feature = "io-write",
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub use threemf_package::{Part, ThreemfPackage};
+#[cfg(feature = "io-write")]
+pub use threemf_package::{PartDiff, PartDigest, PartEmitter};
+
 #[cfg(any(
     feature = "io-write",
     feature = "io-memory-optimized-read",
     feature = "io-speed-optimized-read"
 ))]
-pub use threemf_package::ThreemfPackage;
+mod flatten;
+
+#[cfg(any(
+    feature = "io-write",
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+mod deduplicate;
+
+#[cfg(all(
+    test,
+    any(
+        feature = "io-write",
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    )
+))]
+mod test_support;
+
+#[cfg(feature = "io-write")]
+mod export;
+#[cfg(feature = "io-write")]
+pub use export::NamingScheme;
 
 #[cfg(any(
     feature = "io-write",
@@ -69,6 +124,20 @@ mod threemf_package_lazy_reader;
 ))]
 pub use threemf_package_lazy_reader::{CachePolicy, ThreemfPackageLazyReader};
 
+#[cfg(feature = "thumbnail-render")]
+mod thumbnail_render;
+#[cfg(feature = "thumbnail-render")]
+pub use thumbnail_render::MAX_THUMBNAIL_SIZE;
+
+#[cfg(all(
+    feature = "thumbnail-decode",
+    any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    )
+))]
+mod thumbnail_decode;
+
 #[cfg(feature = "io-write")]
 mod builder;
 #[cfg(feature = "io-write")]
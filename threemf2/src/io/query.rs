@@ -15,6 +15,16 @@
 //! - [`ItemRef`] - References to build items (objects to be manufactured)
 //! - [`ComponentRef`] - References to components within composed parts
 //! - [`ModelRef`] - References to models with their path information
+//! - [`TriangleKey`] - Package-unique identifier of a triangle of a mesh object
+//!
+//! [`topological_object_order()`] orders all objects of a package so that referenced objects
+//! come before the composed objects referencing them. [`object_material_report()`] lists the
+//! base materials used by every printable object. [`resolved_build_instances()`] lists the mesh
+//! objects placed by the build with their world transforms, [`resolve_mesh_instances()`] along
+//! with their meshes. [`objects_in_build_order()`] lists
+//! the build items with their objects in the declared order. [`iter_uuids()`] lists the production
+//! extension UUIDs with the entity they belong to, [`duplicate_uuids()`] finds the ones used
+//! more than once.
 //!
 //! # Common Patterns
 //!
@@ -56,10 +66,9 @@
 //! ```rust,ignore
 //! // Get only mesh objects (filters out composed parts)
 //! for mesh_ref in get_mesh_objects(&package) {
-//!     let mesh = mesh_ref.mesh();
 //!     println!("Mesh with {} vertices, {} triangles",
-//!         mesh.vertices.vertex.len(),
-//!         mesh.triangles.triangle.len()
+//!         mesh_ref.vertex_count(),
+//!         mesh_ref.triangle_count()
 //!     );
 //!     
 //!     // Access object metadata via Deref
@@ -149,6 +158,9 @@
 //! - All query functions return iterators, enabling lazy evaluation
 //! - Reference types are lightweight wrappers with no data copying
 //! - Queries work directly on the loaded package data with no additional allocations
+//! - [`find_object`], [`any_object`] and [`count_objects_where`] make short-circuiting
+//!   searches explicit, stopping at the first match where possible
+//! - [`locate_object`] finds the model containing an object ID, starting at a path hint
 //!
 //! # See Also
 //!
@@ -157,18 +169,22 @@
 
 #![allow(clippy::needless_lifetimes)]
 
-use std::ops::Deref;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+};
 
 use crate::{
     core::{
         build::Item,
-        component::Components,
-        mesh::Mesh,
+        component::{Component, Components},
+        mesh::{Mesh, Triangle},
         model::Model,
         object::{Object, ObjectType},
+        resources::Base,
         transform::Transform,
     },
-    io::ThreemfPackage,
+    io::{ThreemfPackage, error::Error},
 };
 
 /// A reference to an object within a 3MF model, including its path if from a sub-model.
@@ -220,6 +236,34 @@ pub struct ObjectRef<'a> {
     pub path: Option<&'a str>,
 }
 
+/// The kind of geometry an [`Object`] holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// The object holds a mesh only.
+    Mesh,
+    /// The object holds components only.
+    Components,
+    /// The object holds both a mesh and components. This is forbidden by the
+    /// 3MF Core specification. Every reader rejects such objects, or keeps only the mesh
+    /// with `ReadOptions::prefer_mesh_over_components`, so they are only observable in
+    /// models assembled in code.
+    MeshAndComponents,
+    /// The object holds neither a mesh nor components.
+    Empty,
+}
+
+impl<'a> ObjectRef<'a> {
+    /// Returns the [`ObjectKind`] of the referenced object.
+    pub fn kind(&self) -> ObjectKind {
+        match (self.object.mesh.is_some(), self.object.components.is_some()) {
+            (true, false) => ObjectKind::Mesh,
+            (false, true) => ObjectKind::Components,
+            (true, true) => ObjectKind::MeshAndComponents,
+            (false, false) => ObjectKind::Empty,
+        }
+    }
+}
+
 /// Retrieves an object by ID from a given model.
 ///
 /// Object IDs are unique within a single model but may be duplicated across
@@ -322,6 +366,47 @@ pub fn get_objects<'a>(package: &'a ThreemfPackage) -> impl Iterator<Item = Obje
     iter_objects_from(package, get_objects_from_model_ref)
 }
 
+/// Returns the first object in the package matching the predicate.
+///
+/// Objects are visited in the same order as [`get_objects()`] and the search stops at the
+/// first match, so the remaining objects and models are never visited.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// if let Some(gear) = find_object(&package, |r| r.object.name.as_deref() == Some("Gear")) {
+///     println!("Found gear with ID {}", gear.object.id);
+/// }
+/// ```
+pub fn find_object<'a, F>(package: &'a ThreemfPackage, mut predicate: F) -> Option<ObjectRef<'a>>
+where
+    F: FnMut(&ObjectRef<'a>) -> bool,
+{
+    get_objects(package).find(|r| predicate(r))
+}
+
+/// Returns true if any object in the package matches the predicate.
+///
+/// Stops at the first match, see [`find_object()`].
+pub fn any_object<'a, F>(package: &'a ThreemfPackage, predicate: F) -> bool
+where
+    F: FnMut(&ObjectRef<'a>) -> bool,
+{
+    find_object(package, predicate).is_some()
+}
+
+/// Counts the objects in the package matching the predicate without collecting them.
+pub fn count_objects_where<'a, F>(package: &'a ThreemfPackage, mut predicate: F) -> usize
+where
+    F: FnMut(&ObjectRef<'a>) -> bool,
+{
+    get_objects(package).filter(|r| predicate(r)).count()
+}
+
 /// Returns an iterator over all objects in a specific model.
 ///
 /// Unlike [`get_objects()`], this only queries a single model instance.
@@ -421,13 +506,12 @@ pub struct GenericObjectRef<'a, T> {
 ///     }
 ///     
 ///     // Access mesh geometry
-///     let mesh = mesh_ref.mesh();
-///     println!("  Vertices: {}", mesh.vertices.vertex.len());
-///     println!("  Triangles: {}", mesh.triangles.triangle.len());
+///     println!("  Vertices: {}", mesh_ref.vertex_count());
+///     println!("  Triangles: {}", mesh_ref.triangle_count());
 ///     
 ///     // Check for beam lattice
-///     if let Some(beamlattice) = &mesh.beamlattice {
-///         println!("  Has beam lattice with {} beams", beamlattice.beams.beam.len());
+///     if mesh_ref.mesh().beamlattice.is_some() {
+///         println!("  Has beam lattice with {} beams", mesh_ref.beam_count());
 ///     }
 ///     
 ///     // Check model origin
@@ -445,9 +529,9 @@ pub struct GenericObjectRef<'a, T> {
 pub struct MeshObjectRef<'a>(GenericObjectRef<'a, Mesh>);
 
 impl<'a> MeshObjectRef<'a> {
-    fn new(o: ObjectRef<'a>) -> Self {
-        MeshObjectRef(GenericObjectRef {
-            entity: o.object.mesh.as_ref().unwrap(),
+    fn new(o: ObjectRef<'a>) -> Option<Self> {
+        Some(MeshObjectRef(GenericObjectRef {
+            entity: o.object.mesh.as_ref()?,
             id: o.object.id,
             object_type: o.object.objecttype.unwrap_or(ObjectType::Model),
             thumbnail: o.object.thumbnail.clone(),
@@ -457,7 +541,7 @@ impl<'a> MeshObjectRef<'a> {
             pindex: o.object.pindex,
             uuid: o.object.uuid.clone(),
             origin_model_path: o.path,
-        })
+        }))
     }
 
     /// Returns a reference to the mesh geometry data.
@@ -485,6 +569,168 @@ impl<'a> MeshObjectRef<'a> {
     pub fn mesh(&self) -> &'a Mesh {
         self.entity
     }
+
+    /// Returns the number of vertices of the mesh.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let vertex_count: usize = get_mesh_objects(&package)
+    ///     .map(|mesh_ref| mesh_ref.vertex_count())
+    ///     .sum();
+    /// ```
+    pub fn vertex_count(&self) -> usize {
+        self.entity.vertices.vertex.len()
+    }
+
+    /// Returns the number of triangles of the mesh.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for mesh_ref in get_mesh_objects(&package) {
+    ///     println!("Object {} has {} triangles", mesh_ref.id, mesh_ref.triangle_count());
+    /// }
+    /// ```
+    pub fn triangle_count(&self) -> usize {
+        self.entity.triangles.triangle.len()
+    }
+
+    /// Returns the number of beams of the beam lattice of the mesh, 0 if the mesh has no
+    /// beam lattice.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let lattices = get_mesh_objects(&package).filter(|mesh_ref| mesh_ref.beam_count() > 0);
+    /// ```
+    pub fn beam_count(&self) -> usize {
+        self.entity
+            .beamlattice
+            .as_ref()
+            .map_or(0, |beamlattice| beamlattice.beams.beam.len())
+    }
+
+    /// Returns the minimum and maximum corner of the axis aligned box enclosing the mesh
+    /// after applying `transform`, or `None` if the mesh has no vertices.
+    ///
+    /// The eight corners of the [`Mesh::bounding_box()`] are transformed and enclosed, so a
+    /// rotated mesh may get a box larger than its transformed vertices need.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for item in get_items(&package) {
+    ///     if let Some(object) = get_mesh_objects(&package).find(|m| m.id == item.objectid()) {
+    ///         let plate_box = object.world_bounding_box(item.transform());
+    ///     }
+    /// }
+    /// ```
+    pub fn world_bounding_box(
+        &self,
+        transform: Option<&Transform>,
+    ) -> Option<([f64; 3], [f64; 3])> {
+        let (min, max) = self.entity.bounding_box()?;
+        let Some(transform) = transform else {
+            return Some((min, max));
+        };
+
+        let corner = |i: usize| {
+            let pick = |axis: usize| {
+                if (i >> axis) & 1 == 1 {
+                    max[axis]
+                } else {
+                    min[axis]
+                }
+            };
+            transform.transform_point([pick(0), pick(1), pick(2)])
+        };
+        let first = corner(0);
+        Some(
+            (1..8)
+                .map(corner)
+                .fold((first, first), |(mut min, mut max), p| {
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(p[axis]);
+                        max[axis] = max[axis].max(p[axis]);
+                    }
+                    (min, max)
+                }),
+        )
+    }
+
+    /// Returns one unit normal per vertex for smooth shading.
+    ///
+    /// See [`Mesh::vertex_normals()`] for details.
+    pub fn vertex_normals(&self) -> Vec<[f64; 3]> {
+        self.entity.vertex_normals()
+    }
+
+    /// Returns the holes of the mesh as loops of vertex indices.
+    ///
+    /// See [`Mesh::boundary_loops()`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for mesh_ref in get_mesh_objects(&package) {
+    ///     for boundary_loop in mesh_ref.boundary_loops() {
+    ///         println!("Object {} has a hole of {} edges", mesh_ref.id, boundary_loop.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        self.entity.boundary_loops()
+    }
+
+    /// Returns the triangles of the mesh together with a [`TriangleKey`] identifying each
+    /// of them uniquely within the package.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for mesh_ref in get_mesh_objects(&package) {
+    ///     for (key, triangle) in mesh_ref.triangle_keys() {
+    ///         if triangle.v1 == triangle.v2 {
+    ///             println!("Degenerate triangle: {:?}", key);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn triangle_keys(&self) -> impl Iterator<Item = (TriangleKey, &'a Triangle)> {
+        let model_path = self.origin_model_path;
+        let object_id = self.id;
+
+        self.entity
+            .triangles
+            .triangle
+            .iter()
+            .enumerate()
+            .map(move |(triangle_index, triangle)| {
+                let key = TriangleKey {
+                    model_path: model_path.map(str::to_owned),
+                    object_id,
+                    triangle_index,
+                };
+                (key, triangle)
+            })
+    }
+}
+
+/// Identifies a triangle of a mesh object uniquely within a package.
+///
+/// Object IDs are only unique within a model, so the key combines the path of the model,
+/// the ID of the object and the index of the triangle in [`Triangles`](crate::core::mesh::Triangles).
+///
+/// See [`MeshObjectRef::triangle_keys()`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TriangleKey {
+    /// The path to the model containing the object, if None then it is the root model.
+    pub model_path: Option<String>,
+    /// ID of the mesh object.
+    pub object_id: usize,
+    /// Index of the triangle in the mesh.
+    pub triangle_index: usize,
 }
 
 impl<'a> Deref for MeshObjectRef<'a> {
@@ -517,9 +763,8 @@ impl<'a> Deref for MeshObjectRef<'a> {
 ///
 /// // Analyze mesh complexity
 /// for mesh_ref in get_mesh_objects(&package) {
-///     let mesh = mesh_ref.mesh();
-///     let vertex_count = mesh.vertices.vertex.len();
-///     let triangle_count = mesh.triangles.triangle.len();
+///     let vertex_count = mesh_ref.vertex_count();
+///     let triangle_count = mesh_ref.triangle_count();
 ///     
 ///     println!("Mesh {} ({:?}): {} vertices, {} triangles",
 ///         mesh_ref.id,
@@ -549,7 +794,7 @@ impl<'a> Deref for MeshObjectRef<'a> {
 pub fn get_mesh_objects<'a>(
     package: &'a ThreemfPackage,
 ) -> impl Iterator<Item = MeshObjectRef<'a>> {
-    iter_objects_from(package, get_mesh_objects_from_model_ref).map(MeshObjectRef::new)
+    iter_objects_from(package, get_mesh_objects_from_model_ref).filter_map(MeshObjectRef::new)
 }
 
 /// Returns an iterator over mesh objects in a specific model.
@@ -587,7 +832,7 @@ pub fn get_mesh_objects<'a>(
 pub fn get_mesh_objects_from_model<'a>(
     model: &'a Model,
 ) -> impl Iterator<Item = MeshObjectRef<'a>> {
-    get_mesh_objects_from_model_ref(ModelRef { model, path: None }).map(MeshObjectRef::new)
+    get_mesh_objects_from_model_ref(ModelRef { model, path: None }).filter_map(MeshObjectRef::new)
 }
 
 /// Returns an iterator over mesh objects in the model reference.
@@ -658,9 +903,9 @@ pub fn get_mesh_objects_from_model_ref<'a>(
 pub struct ComponentsObjectRef<'a>(GenericObjectRef<'a, Components>);
 
 impl<'a> ComponentsObjectRef<'a> {
-    fn new(o: ObjectRef<'a>) -> Self {
-        ComponentsObjectRef(GenericObjectRef {
-            entity: o.object.components.as_ref().unwrap(),
+    fn new(o: ObjectRef<'a>) -> Option<Self> {
+        Some(ComponentsObjectRef(GenericObjectRef {
+            entity: o.object.components.as_ref()?,
             id: o.object.id,
             object_type: o.object.objecttype.unwrap_or(ObjectType::Model),
             thumbnail: o.object.thumbnail.clone(),
@@ -670,7 +915,7 @@ impl<'a> ComponentsObjectRef<'a> {
             pindex: o.object.pindex,
             uuid: o.object.uuid.clone(),
             origin_model_path: o.path,
-        })
+        }))
     }
 
     /// Returns an iterator over the components within this composed part.
@@ -783,6 +1028,137 @@ pub struct ComponentRef {
     /// UUID of the component.
     pub uuid: Option<String>,
 }
+
+/// Resolves a component to the object it references.
+///
+/// The object is looked up in the sub-model named by
+/// [`path_to_look_for`](ComponentRef::path_to_look_for), or in the root model if it is `None`.
+/// The referenced object may be declared before or after the composed object.
+///
+/// # Arguments
+///
+/// * `package` - The 3MF package to search in
+/// * `comp` - The component to resolve
+///
+/// # Returns
+///
+/// `Some(ObjectRef)` if the referenced object exists, `None` otherwise. The returned
+/// reference has `path` set to the model the object was found in.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for composed in get_components_objects(&package) {
+///     for component in composed.components() {
+///         if let Some(target) = resolve_component(&package, &component) {
+///             println!("Component -> {:?}", target.object.name);
+///         }
+///     }
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`ComponentsObjectRef::components()`] - Get components from a composed part
+/// * [`get_object_from_model()`] - Look up an object in a single model
+pub fn resolve_component<'a>(
+    package: &'a ThreemfPackage,
+    comp: &ComponentRef,
+) -> Option<ObjectRef<'a>> {
+    let (path, model) = match &comp.path_to_look_for {
+        Some(path) => {
+            let (path, model) = package.sub_models.get_key_value(path)?;
+            (Some(path.as_str()), model)
+        }
+        None => (None, &package.root),
+    };
+
+    get_object_from_model(comp.objectid, model).map(|o| ObjectRef {
+        object: o.object,
+        path,
+    })
+}
+
+/// Finds the model containing the object `object_id`, starting at a path hint.
+///
+/// Object IDs are only unique within a single model, so the same ID may exist in several
+/// models of a package. The model at `preferred_path` is searched first, then the root model
+/// and finally the sub-models ordered by their path. `preferred_path` may name the root model
+/// by its path, a path not naming any model of the package is skipped.
+///
+/// # Returns
+///
+/// The first match together with the path to the model it was found in, `None` for the root
+/// model, or `None` if no model contains the object.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// if let Some((object, path)) = locate_object(&package, 1, Some("/3D/Objects/parts.model")) {
+///     println!("Object {} lives in {:?}", object.object.id, path);
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`resolve_component()`] - Look up the object of a component in its model only
+/// * [`get_object_from_model()`] - Look up an object in a single model
+pub fn locate_object<'a>(
+    package: &'a ThreemfPackage,
+    object_id: usize,
+    preferred_path: Option<&str>,
+) -> Option<(ObjectRef<'a>, Option<String>)> {
+    let preferred = preferred_path.and_then(|path| {
+        if path == package.root_model_path() {
+            Some(ModelRef {
+                model: &package.root,
+                path: None,
+            })
+        } else {
+            let (path, model) = package.sub_models.get_key_value(path)?;
+            Some(ModelRef {
+                model,
+                path: Some(path),
+            })
+        }
+    });
+    let mut sub_models = package
+        .sub_models
+        .iter()
+        .map(|(path, model)| ModelRef {
+            model,
+            path: Some(path),
+        })
+        .collect::<Vec<_>>();
+    sub_models.sort_by_key(|m| m.path);
+
+    preferred
+        .into_iter()
+        .chain(std::iter::once(ModelRef {
+            model: &package.root,
+            path: None,
+        }))
+        .chain(sub_models)
+        .find_map(|model_ref| {
+            let object = get_object_from_model(object_id, model_ref.model)?;
+            Some((
+                ObjectRef {
+                    object: object.object,
+                    path: model_ref.path,
+                },
+                model_ref.path.map(str::to_owned),
+            ))
+        })
+}
+
 /// A reference to a build item with convenient accessor methods.
 ///
 /// Build items specify which objects should be manufactured and optionally
@@ -801,6 +1177,7 @@ pub struct ComponentRef {
 /// * [`partnumber()`](ItemRef::partnumber) - Get optional part number
 /// * [`uuid()`](ItemRef::uuid) - Get UUID (production extension)
 /// * [`path()`](ItemRef::path) - Get path for cross-model references (production extension)
+/// * [`effective_partnumber()`](ItemRef::effective_partnumber) - Get the item part number or the one of the referenced object
 ///
 /// # Examples
 ///
@@ -930,6 +1307,45 @@ impl<'a> ItemRef<'a> {
     pub fn uuid(&self) -> Option<&str> {
         self.item.uuid.as_deref()
     }
+
+    /// Returns the UUID of this item in the canonical form of [`normalize_uuid()`], e.g.
+    /// for comparing UUIDs written by different tools. [`uuid()`](ItemRef::uuid) keeps the
+    /// original string.
+    pub fn uuid_normalized(&self) -> Option<String> {
+        self.uuid().map(normalize_uuid)
+    }
+
+    /// Returns the part number of this item, falling back to the part number of the
+    /// referenced object if the item does not override it.
+    ///
+    /// The referenced object is looked up in the model given by the item's
+    /// [`path()`](ItemRef::path), or else in the model containing the item.
+    /// Returns `None` if neither has a part number or the object cannot be resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for item in get_items(&package) {
+    ///     if let Some(partnumber) = item.effective_partnumber(&package) {
+    ///         println!("Part number: {}", partnumber);
+    ///     }
+    /// }
+    /// ```
+    pub fn effective_partnumber(&self, package: &ThreemfPackage) -> Option<String> {
+        if let Some(partnumber) = self.partnumber() {
+            return Some(partnumber.to_owned());
+        }
+
+        let model = match self.path().or(self.origin_model_path) {
+            Some(path) => package.sub_models.get(path)?,
+            None => &package.root,
+        };
+
+        get_object_from_model(self.objectid(), model)?
+            .object
+            .partnumber
+            .clone()
+    }
 }
 
 /// Returns an iterator over composed part objects (assemblies) in the package.
@@ -993,7 +1409,8 @@ impl<'a> ItemRef<'a> {
 pub fn get_components_objects<'a>(
     package: &'a ThreemfPackage,
 ) -> impl Iterator<Item = ComponentsObjectRef<'a>> {
-    iter_objects_from(package, get_components_objects_from_model_ref).map(ComponentsObjectRef::new)
+    iter_objects_from(package, get_components_objects_from_model_ref)
+        .filter_map(ComponentsObjectRef::new)
 }
 
 /// Returns an iterator over composed part objects in a specific model.
@@ -1034,7 +1451,7 @@ pub fn get_components_objects_from_model<'a>(
     model: &'a Model,
 ) -> impl Iterator<Item = ComponentsObjectRef<'a>> {
     get_components_objects_from_model_ref(ModelRef { model, path: None })
-        .map(ComponentsObjectRef::new)
+        .filter_map(ComponentsObjectRef::new)
 }
 
 /// Returns an iterator over composed part objects in the model reference.
@@ -1224,6 +1641,9 @@ pub fn get_items_by_objectid<'a>(
 /// This is part of the 3MF production extension. UUIDs should be unique across
 /// the entire package, so this function returns at most one item.
 ///
+/// UUIDs are compared in the canonical form of [`normalize_uuid()`], so an item is found
+/// regardless of the case and hyphenation used by the file or by `uuid`.
+///
 /// # Arguments
 ///
 /// * `package` - The 3MF package to query
@@ -1265,15 +1685,128 @@ pub fn get_items_by_objectid<'a>(
 /// * [`ItemRef::uuid()`] - Get UUID from an item reference
 /// * [`get_items()`] - Get all items (to find items with UUIDs)
 pub fn get_item_by_uuid<'a>(package: &'a ThreemfPackage, uuid: &str) -> Option<ItemRef<'a>> {
-    get_items(package).find(|item_ref| {
-        if let Some(item_uuid) = &item_ref.item.uuid {
-            item_uuid == uuid
-        } else {
-            false
-        }
+    let uuid = normalize_uuid(uuid);
+    get_items(package).find(|item_ref| item_ref.uuid_normalized().is_some_and(|u| u == uuid))
+}
+
+/// Finds an object by its UUID (production extension) in the root model and the sub-models.
+///
+/// UUIDs are compared in their normalized form, see [`normalize_uuid()`]. The returned
+/// reference has `path` set to the model the object was found in, so components and build
+/// items of that model can be resolved from it.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// if let Some(object) = get_object_by_uuid(&package, "0876d4f1-6d50-4a6f-9215-6d88058bb346") {
+///     println!("Object {} in {:?}", object.object.id, object.path);
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`get_item_by_uuid()`] - Find a build item by its UUID
+/// * [`get_component_by_uuid()`] - Find a component by its UUID
+pub fn get_object_by_uuid<'a>(package: &'a ThreemfPackage, uuid: &str) -> Option<ObjectRef<'a>> {
+    let uuid = normalize_uuid(uuid);
+    get_objects(package).find(|o| {
+        o.object
+            .uuid
+            .as_deref()
+            .is_some_and(|u| normalize_uuid(u) == uuid)
+    })
+}
+
+/// Finds a component by its UUID (production extension) in the root model and the
+/// sub-models.
+///
+/// Returns the component together with the composed object containing it. UUIDs are
+/// compared in their normalized form, see [`normalize_uuid()`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// if let Some((parent, component)) = get_component_by_uuid(&package, uuid) {
+///     println!("Object {} references object {}", parent.id, component.objectid);
+///     let target = resolve_component(&package, &component);
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`resolve_component()`] - Resolve the component to the object it references
+/// * [`get_object_by_uuid()`] - Find an object by its UUID
+pub fn get_component_by_uuid<'a>(
+    package: &'a ThreemfPackage,
+    uuid: &str,
+) -> Option<(ComponentsObjectRef<'a>, ComponentRef)> {
+    let uuid = normalize_uuid(uuid);
+    get_components_objects(package).find_map(|parent| {
+        let component = parent
+            .components()
+            .find(|c| c.uuid.as_deref().is_some_and(|u| normalize_uuid(u) == uuid))?;
+        Some((parent, component))
     })
 }
 
+/// Returns the build items of the root model in their declared order, each with the object
+/// it references.
+///
+/// The build of the root model is the only one that is manufactured, and the order of its items
+/// is the order in which they are printed one after another on sequential printers. Items
+/// referencing another model part with the production extension `path` are resolved in that
+/// model. The object is `None` if the item references an object or model part that does not
+/// exist.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for (position, (item, object)) in objects_in_build_order(&package).iter().enumerate() {
+///     match object {
+///         Some(object) => println!("{position}: {:?}", object.object.name),
+///         None => println!("{position}: missing object {}", item.objectid()),
+///     }
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`get_items_from_model()`] - Get the build items without resolving them
+/// * [`resolved_build_instances()`] - Resolve the build items down to the placed mesh objects
+pub fn objects_in_build_order<'a>(
+    package: &'a ThreemfPackage,
+) -> Vec<(ItemRef<'a>, Option<ObjectRef<'a>>)> {
+    get_items_from_model(&package.root)
+        .map(|item| {
+            let object = match item.path() {
+                Some(path) => package
+                    .sub_models
+                    .get_key_value(path)
+                    .and_then(|(path, model)| {
+                        get_object_from_model(item.objectid(), model).map(|o| ObjectRef {
+                            object: o.object,
+                            path: Some(path.as_str()),
+                        })
+                    }),
+                None => get_object_from_model(item.objectid(), &package.root),
+            };
+            (item, object)
+        })
+        .collect()
+}
+
 /// A reference to a model within a package, with path information for sub-models.
 ///
 /// 3MF packages can contain multiple model files: one root model and zero or more sub-models.
@@ -1387,30 +1920,728 @@ pub fn iter_models<'a>(package: &'a ThreemfPackage) -> impl Iterator<Item = Mode
     }))
 }
 
-fn iter_objects_from<'a, I, F>(
-    package: &'a ThreemfPackage,
-    f: F,
-) -> impl Iterator<Item = ObjectRef<'a>>
-where
-    F: Fn(ModelRef<'a>) -> I + Copy,
-    I: IntoIterator<Item = ObjectRef<'a>>,
-{
-    iter_models(package).flat_map(f)
+/// Error returned by [`topological_object_order()`] if objects reference each other
+/// recursively through their components.
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+#[error("Objects reference each other recursively: {chain:?}")]
+pub struct CycleError {
+    /// The objects forming the cycle as object id and model path (`None` for the root model).
+    /// The chain starts and ends with the same object, e.g. `[(1, None), (2, None), (1, None)]`.
+    pub chain: Vec<(usize, Option<String>)>,
 }
 
-#[cfg(feature = "io-memory-optimized-read")]
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    use std::path::PathBuf;
-
-    #[test]
-    fn test_get_object_ref_from_package() {
-        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
-        let file = std::fs::File::open(path).unwrap();
-        let package =
+/// Returns all objects of the package ordered so that every object appears after the
+/// objects it references via components.
+///
+/// Components referencing objects in other models are followed as well. Objects are
+/// identified by their id and the path of the model they are declared in (`None` for the
+/// root model). Apart from the dependency order, objects of the root model come first,
+/// followed by the sub-models sorted by path, each in declaration order.
+/// Components referencing an object that does not exist are ignored.
+///
+/// # Errors
+///
+/// Returns [`CycleError`] with the offending chain of objects if components reference
+/// each other recursively.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// // meshes are processed before the assemblies referencing them
+/// for (id, path) in topological_object_order(&package)? {
+///     println!("Object {} in {}", id, path.as_deref().unwrap_or("root"));
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`resolve_component()`] - Resolve a single component to its object
+/// * [`get_components_objects()`] - Find composed parts in a package
+pub fn topological_object_order(
+    package: &ThreemfPackage,
+) -> Result<Vec<(usize, Option<String>)>, CycleError> {
+    let mut sub_model_paths = package.sub_models.keys().collect::<Vec<_>>();
+    sub_model_paths.sort();
+    let models = std::iter::once((None, &package.root)).chain(
+        sub_model_paths
+            .into_iter()
+            .map(|path| (Some(path.as_str()), &package.sub_models[path])),
+    );
+
+    let mut sorter = TopologicalSorter {
+        package,
+        done: HashSet::new(),
+        order: vec![],
+    };
+    for (path, model) in models {
+        for object in &model.resources.object {
+            sorter.visit(path, object)?;
+        }
+    }
+
+    Ok(sorter
+        .order
+        .into_iter()
+        .map(|(id, path)| (id, path.map(str::to_owned)))
+        .collect())
+}
+
+/// Depth first traversal of the component references, objects are added to the order
+/// once all objects they reference are added.
+///
+/// The traversal keeps its own stack instead of recursing, so deeply nested components
+/// can't overflow the call stack.
+struct TopologicalSorter<'a> {
+    package: &'a ThreemfPackage,
+    done: HashSet<(usize, Option<&'a str>)>,
+    order: Vec<(usize, Option<&'a str>)>,
+}
+
+impl<'a> TopologicalSorter<'a> {
+    fn visit(&mut self, path: Option<&'a str>, object: &'a Object) -> Result<(), CycleError> {
+        // the objects being visited with the index of their next component
+        let mut stack: Vec<((usize, Option<&'a str>), &'a Object, usize)> = vec![];
+        let mut on_stack = HashSet::new();
+        let mut next = Some((path, object));
+
+        loop {
+            if let Some((path, object)) = next.take() {
+                let key = (object.id, path);
+                if on_stack.contains(&key) {
+                    let start = stack.iter().position(|(k, ..)| *k == key).unwrap_or(0);
+                    let chain = stack[start..]
+                        .iter()
+                        .map(|(k, ..)| k)
+                        .chain(std::iter::once(&key))
+                        .map(|(id, path)| (*id, path.map(str::to_owned)))
+                        .collect();
+                    return Err(CycleError { chain });
+                }
+                if !self.done.contains(&key) {
+                    on_stack.insert(key);
+                    stack.push((key, object, 0));
+                }
+            }
+
+            let Some(frame) = stack.last_mut() else {
+                return Ok(());
+            };
+            let (key, object) = (frame.0, frame.1);
+            match object
+                .components
+                .as_ref()
+                .and_then(|c| c.component.get(frame.2))
+            {
+                Some(component) => {
+                    frame.2 += 1;
+                    next = self.component_target(key.1, component);
+                }
+                None => {
+                    stack.pop();
+                    on_stack.remove(&key);
+                    self.done.insert(key);
+                    self.order.push(key);
+                }
+            }
+        }
+    }
+
+    /// Returns the object referenced by a component of an object in the model at `path`,
+    /// `None` if it doesn't exist.
+    fn component_target(
+        &self,
+        path: Option<&'a str>,
+        component: &Component,
+    ) -> Option<(Option<&'a str>, &'a Object)> {
+        // components without a path reference the model containing them
+        let (target_path, model) = match component.path.as_deref().or(path) {
+            Some(component_path) => self
+                .package
+                .sub_models
+                .get_key_value(component_path)
+                .map(|(p, model)| (Some(p.as_str()), model))?,
+            None => (None, &self.package.root),
+        };
+        let target = model
+            .resources
+            .object
+            .iter()
+            .find(|o| o.id == component.objectid)?;
+        Some((target_path, target))
+    }
+}
+
+/// The base materials used by a printable object, see [`object_material_report()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMaterials {
+    /// The id of the object.
+    pub id: usize,
+    /// The path to the model containing the object, if None then it is the root model.
+    pub path: Option<String>,
+    /// The name of the object.
+    pub name: Option<String>,
+    /// The distinct base materials used by the object in the order they are first used.
+    pub materials: Vec<Base>,
+}
+
+/// Returns the base materials used by each printable object of the package.
+///
+/// Printable objects are the mesh objects of type [`ObjectType::Model`] in the root model and
+/// the sub-models, in the order of [`iter_models()`]. The materials are resolved from the
+/// object `pid`/`pindex` and the properties of the triangles, with triangles without
+/// properties inheriting the ones of the object. Materials are looked up in the base materials
+/// groups of the model containing the object. Properties referencing other property groups
+/// or indices out of range are ignored, identical bases are only listed once.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for report in object_material_report(&package) {
+///     let names = report.materials.iter().map(|b| b.name.as_str()).collect::<Vec<_>>();
+///     println!("Object {}: {}", report.id, names.join(", "));
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`get_mesh_objects()`] - Get all mesh objects from a package
+/// * [`Triangle::resolved_properties()`] - Resolve the properties of a single triangle
+pub fn object_material_report(package: &ThreemfPackage) -> Vec<ObjectMaterials> {
+    let mut report = vec![];
+    for model_ref in iter_models(package) {
+        for object in &model_ref.model.resources.object {
+            if object.objecttype.unwrap_or(ObjectType::Model) == ObjectType::Model
+                && object.mesh.is_some()
+            {
+                report.push(ObjectMaterials {
+                    id: object.id,
+                    path: model_ref.path.map(str::to_owned),
+                    name: object.name.clone(),
+                    materials: object_materials(object, model_ref.model),
+                });
+            }
+        }
+    }
+    report
+}
+
+/// Collects the distinct bases referenced by the object and its triangles.
+fn object_materials(object: &Object, model: &Model) -> Vec<Base> {
+    let object_properties = object
+        .pid
+        .zip(object.pindex)
+        .map(|(pid, index)| (pid, [index; 3]));
+    let triangle_properties = object
+        .mesh
+        .iter()
+        .flat_map(|mesh| &mesh.triangles.triangle)
+        .filter_map(|t| t.resolved_properties(object.pid, object.pindex));
+
+    let mut materials: Vec<Base> = vec![];
+    for (pid, indices) in object_properties.into_iter().chain(triangle_properties) {
+        let Some(group) = model.resources.basematerials.iter().find(|g| g.id == pid) else {
+            continue;
+        };
+        for base in indices.iter().filter_map(|&i| group.base.get(i)) {
+            if !materials.contains(base) {
+                materials.push(base.clone());
+            }
+        }
+    }
+    materials
+}
+
+/// Returns the total volume of the printable objects placed by the build, grouped by the id
+/// of the property group holding their material.
+///
+/// Every placement of a mesh object of type [`ObjectType::Model`] is counted, see
+/// [`resolved_build_instances()`], with its [`Mesh::signed_volume()`] scaled by the transform
+/// of the placement. The material of an object is its `pid`, or the `pid` of its first
+/// triangle with properties if it has none. Objects without a material are left out.
+///
+/// The report assumes closed meshes with outwards pointing normals and a uniform material per
+/// object, triangles referencing other property groups count towards the group of the object.
+/// Property group ids are only unique within a model part, the volumes of objects in
+/// different parts using the same id are summed up.
+///
+/// Returns the errors of [`resolved_build_instances()`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for (pid, volume) in material_volume_report(&package)? {
+///     println!("Material group {}: {:.1} cubic units", pid, volume);
+/// }
+/// ```
+pub fn material_volume_report(package: &ThreemfPackage) -> Result<HashMap<usize, f64>, Error> {
+    let mut volumes = HashMap::new();
+    for item in &package.root.build.item {
+        let transform = item.transform.clone().unwrap_or(Transform::IDENTITY);
+        walk_components(
+            package,
+            item.path.as_deref(),
+            item.objectid,
+            transform,
+            DEFAULT_MAX_COMPONENT_DEPTH,
+            |_, _, object, transform| {
+                if let Some(mesh) = &object.mesh
+                    && object.objecttype.unwrap_or_default() == ObjectType::Model
+                    && let Some(pid) = object
+                        .pid
+                        .or_else(|| mesh.triangles.triangle.iter().find_map(|t| t.pid))
+                {
+                    let volume = mesh.signed_volume() * transform.determinant().abs();
+                    *volumes.entry(pid).or_insert(0.0) += volume;
+                }
+                Ok(())
+            },
+        )?;
+    }
+    Ok(volumes)
+}
+
+/// A mesh object placed by the build, see [`resolved_build_instances()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildInstance {
+    /// The id of the mesh object.
+    pub objectid: usize,
+    /// The path to the model containing the mesh object, if None then it is the root model.
+    pub path: Option<String>,
+    /// The index of the build item placing the mesh object.
+    pub item: usize,
+    /// The transform from the mesh object to the world, accumulated from the build item
+    /// down through the components.
+    pub transform: Transform,
+}
+
+/// Returns every mesh object placed by the build of the root model with its world transform.
+///
+/// Each build item is resolved down through its components, including the ones referencing
+/// another model part, to the mesh objects. The transform of each instance is the component
+/// transforms applied in order, followed by the item transform. A mesh object referenced
+/// several times is listed once per placement, in the order of the build items.
+///
+/// Returns an error if a build item or component references an object or model part that does
+/// not exist, or if components reference each other recursively.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for instance in resolved_build_instances(&package)? {
+///     let origin = instance.transform.transform_point([0.0, 0.0, 0.0]);
+///     println!("Object {} placed at {:?}", instance.objectid, origin);
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`get_items()`] - Get the build items without resolving them
+/// * [`ThreemfPackage::flatten_to_single_object()`] - Merge the placed geometry into one mesh
+pub fn resolved_build_instances(package: &ThreemfPackage) -> Result<Vec<BuildInstance>, Error> {
+    resolved_build_instances_with_max_depth(package, DEFAULT_MAX_COMPONENT_DEPTH)
+}
+
+/// Same as [`resolved_build_instances()`] but fails with [`Error::ComponentDepthExceeded`]
+/// if a build item resolves through more than `max_depth` nested objects, instead of
+/// [`DEFAULT_MAX_COMPONENT_DEPTH`].
+pub fn resolved_build_instances_with_max_depth(
+    package: &ThreemfPackage,
+    max_depth: usize,
+) -> Result<Vec<BuildInstance>, Error> {
+    let mut instances = vec![];
+    for (index, item) in package.root.build.item.iter().enumerate() {
+        let transform = item.transform.clone().unwrap_or(Transform::IDENTITY);
+        walk_components(
+            package,
+            item.path.as_deref(),
+            item.objectid,
+            transform,
+            max_depth,
+            |path, _, object, transform| {
+                if object.mesh.is_some() {
+                    instances.push(BuildInstance {
+                        objectid: object.id,
+                        path: path.map(str::to_owned),
+                        item: index,
+                        transform: transform.clone(),
+                    });
+                }
+                Ok(())
+            },
+        )?;
+    }
+    Ok(instances)
+}
+
+/// A mesh placed by the build with its world transform, see [`resolve_mesh_instances()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedInstance<'a> {
+    /// The id of the mesh object.
+    pub objectid: usize,
+    /// The mesh of the mesh object.
+    pub mesh: &'a Mesh,
+    /// The transform from the mesh to the world, accumulated from the build item down
+    /// through the components.
+    pub transform: Transform,
+    /// The path to the model containing the mesh object, if None then it is the root model.
+    pub path: Option<&'a str>,
+}
+
+/// Same as [`resolved_build_instances()`] but borrows the [`Mesh`] of every placed mesh
+/// object, e.g. to render the build without looking the objects up again.
+///
+/// Fails with [`Error::RecursiveComponent`] if components reference each other recursively.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for instance in resolve_mesh_instances(&package)? {
+///     for vertex in &instance.mesh.vertices.vertex {
+///         let world = instance.transform.transform_point([vertex.x, vertex.y, vertex.z]);
+///         println!("{:?}", world);
+///     }
+/// }
+/// ```
+pub fn resolve_mesh_instances<'a>(
+    package: &'a ThreemfPackage,
+) -> Result<Vec<ResolvedInstance<'a>>, Error> {
+    let mut instances = vec![];
+    for item in &package.root.build.item {
+        let transform = item.transform.clone().unwrap_or(Transform::IDENTITY);
+        walk_components(
+            package,
+            item.path.as_deref(),
+            item.objectid,
+            transform,
+            DEFAULT_MAX_COMPONENT_DEPTH,
+            |path, _, object, transform| {
+                if let Some(mesh) = &object.mesh {
+                    instances.push(ResolvedInstance {
+                        objectid: object.id,
+                        mesh,
+                        transform: transform.clone(),
+                        path,
+                    });
+                }
+                Ok(())
+            },
+        )?;
+    }
+    Ok(instances)
+}
+
+/// The maximum number of nested objects followed through components by
+/// [`resolved_build_instances()`], [`resolve_mesh_instances()`] and
+/// [`ThreemfPackage::flatten_to_single_object()`].
+pub const DEFAULT_MAX_COMPONENT_DEPTH: usize = 1024;
+
+/// Walks depth first through the objects reachable from the object `objectid` in the model
+/// at `path` via components, calling `visit` for every object, before its components, with
+/// the model containing it and its accumulated transform.
+///
+/// The traversal keeps its own stack instead of recursing, so deeply nested components
+/// can't overflow the call stack. Fails if an object or model part doesn't exist, if
+/// components reference each other recursively, or if more than `max_depth` objects are
+/// nested.
+pub(crate) fn walk_components<'a, F>(
+    package: &'a ThreemfPackage,
+    path: Option<&'a str>,
+    objectid: usize,
+    transform: Transform,
+    max_depth: usize,
+    mut visit: F,
+) -> Result<(), Error>
+where
+    F: FnMut(Option<&'a str>, &'a Model, &'a Object, &Transform) -> Result<(), Error>,
+{
+    struct Frame<'a> {
+        path: Option<&'a str>,
+        object: &'a Object,
+        transform: Transform,
+        next_component: usize,
+    }
+
+    let mut stack: Vec<Frame<'a>> = vec![];
+    let mut on_stack = HashSet::new();
+    let mut next = Some((path, objectid, transform));
+
+    loop {
+        if let Some((path, objectid, transform)) = next.take() {
+            let model = match path {
+                Some(path) => package
+                    .sub_models
+                    .get(path)
+                    .ok_or_else(|| Error::ResourceNotFound(format!("Model {path}")))?,
+                None => &package.root,
+            };
+            let object = get_object_from_model(objectid, model).ok_or_else(|| {
+                Error::ResourceNotFound(format!(
+                    "Object {objectid} in {}",
+                    path.unwrap_or("the root model")
+                ))
+            })?;
+
+            if !on_stack.insert((path, objectid)) {
+                return Err(Error::RecursiveComponent { id: objectid });
+            }
+            if stack.len() == max_depth {
+                return Err(Error::ComponentDepthExceeded { max_depth });
+            }
+
+            visit(path, model, object.object, &transform)?;
+            stack.push(Frame {
+                path,
+                object: object.object,
+                transform,
+                next_component: 0,
+            });
+        }
+
+        let Some(frame) = stack.last_mut() else {
+            return Ok(());
+        };
+        let object = frame.object;
+        match object
+            .components
+            .as_ref()
+            .and_then(|c| c.component.get(frame.next_component))
+        {
+            Some(component) => {
+                frame.next_component += 1;
+                let transform = component
+                    .transform
+                    .as_ref()
+                    .unwrap_or(&Transform::IDENTITY)
+                    .then(&frame.transform);
+                // components without a path reference the model containing them
+                let path = component.path.as_deref().or(frame.path);
+                next = Some((path, component.objectid, transform));
+            }
+            None => {
+                on_stack.remove(&(frame.path, object.id));
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// The entity a UUID belongs to, see [`iter_uuids()`].
+///
+/// `path` is the path to the model containing the entity, `None` for the root model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UuidOwner<'a> {
+    /// The build of a model.
+    Build { path: Option<&'a str> },
+    /// A build item, identified by its position in the build.
+    Item {
+        index: usize,
+        objectid: usize,
+        path: Option<&'a str>,
+    },
+    /// An object.
+    Object { id: usize, path: Option<&'a str> },
+    /// A component, identified by the id of the components object and its position in it.
+    Component {
+        object_id: usize,
+        index: usize,
+        path: Option<&'a str>,
+    },
+}
+
+/// Returns an iterator over all UUIDs of the package with the entity they belong to.
+///
+/// The models are visited in the order of [`iter_models()`]. Within a model the build UUID
+/// comes first, followed by the items, then every object followed by its components.
+/// Entities without a UUID are skipped.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for (uuid, owner) in iter_uuids(&package) {
+///     println!("{uuid}: {owner:?}");
+/// }
+/// ```
+///
+/// # See Also
+///
+/// * [`duplicate_uuids()`] - Find UUIDs used by more than one entity
+/// * [`get_item_by_uuid()`] - Find a build item by its UUID
+pub fn iter_uuids<'a>(
+    package: &'a ThreemfPackage,
+) -> impl Iterator<Item = (&'a str, UuidOwner<'a>)> {
+    iter_models(package).flat_map(|model_ref| {
+        let (model, path) = (model_ref.model, model_ref.path);
+        let build = &model.build;
+
+        let build_uuid = build
+            .uuid
+            .as_deref()
+            .map(|uuid| (uuid, UuidOwner::Build { path }));
+
+        let item_uuids = build
+            .item
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, item)| {
+                let owner = UuidOwner::Item {
+                    index,
+                    objectid: item.objectid,
+                    path,
+                };
+                item.uuid.as_deref().map(|uuid| (uuid, owner))
+            });
+
+        let object_uuids = model.resources.object.iter().flat_map(move |object| {
+            let object_uuid = object.uuid.as_deref().map(|uuid| {
+                let owner = UuidOwner::Object {
+                    id: object.id,
+                    path,
+                };
+                (uuid, owner)
+            });
+            let component_uuids = object
+                .components
+                .iter()
+                .flat_map(|components| components.component.iter().enumerate())
+                .filter_map(move |(index, component)| {
+                    let owner = UuidOwner::Component {
+                        object_id: object.id,
+                        index,
+                        path,
+                    };
+                    component.uuid.as_deref().map(|uuid| (uuid, owner))
+                });
+            object_uuid.into_iter().chain(component_uuids)
+        });
+
+        build_uuid.into_iter().chain(item_uuids).chain(object_uuids)
+    })
+}
+
+/// Returns the canonical form of a UUID: lowercase and hyphenated as
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+///
+/// Some tools write UUIDs in uppercase or without hyphens. A string that doesn't consist of
+/// 32 hexadecimal digits, ignoring hyphens, is not a UUID and is only lowercased.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert_eq!(
+///     normalize_uuid("550E8400E29B41D4A716446655440000"),
+///     "550e8400-e29b-41d4-a716-446655440000"
+/// );
+/// ```
+pub fn normalize_uuid(uuid: &str) -> String {
+    let digits = uuid
+        .trim()
+        .chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<String>();
+    if digits.len() != 32 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return uuid.trim().to_lowercase();
+    }
+
+    [
+        &digits[..8],
+        &digits[8..12],
+        &digits[12..16],
+        &digits[16..20],
+        &digits[20..],
+    ]
+    .join("-")
+}
+
+/// Returns the UUIDs used by more than one entity of the package.
+///
+/// The production extension requires every UUID to be unique across the whole package.
+/// UUIDs are compared in the canonical form of [`normalize_uuid()`]. Each duplicate is
+/// listed once, in the order it is first encountered by [`iter_uuids()`], as written at its
+/// first use.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use threemf2::io::{ThreemfPackage, query::*};
+///
+/// let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true)?;
+///
+/// for uuid in duplicate_uuids(&package) {
+///     let owners = iter_uuids(&package)
+///         .filter(|(u, _)| normalize_uuid(u) == normalize_uuid(&uuid))
+///         .count();
+///     println!("{uuid} is used {owners} times");
+/// }
+/// ```
+pub fn duplicate_uuids(package: &ThreemfPackage) -> Vec<String> {
+    let mut first_uses = HashMap::new();
+    let mut duplicates = HashSet::new();
+    let mut result = vec![];
+    for (uuid, _) in iter_uuids(package) {
+        let normalized = normalize_uuid(uuid);
+        match first_uses.get(&normalized) {
+            Some(&first_use) => {
+                if duplicates.insert(normalized) {
+                    result.push(String::from(first_use));
+                }
+            }
+            None => {
+                first_uses.insert(normalized, uuid);
+            }
+        }
+    }
+    result
+}
+
+fn iter_objects_from<'a, I, F>(
+    package: &'a ThreemfPackage,
+    f: F,
+) -> impl Iterator<Item = ObjectRef<'a>>
+where
+    F: Fn(ModelRef<'a>) -> I + Copy,
+    I: IntoIterator<Item = ObjectRef<'a>>,
+{
+    iter_models(package).flat_map(f)
+}
+
+#[cfg(feature = "io-memory-optimized-read")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_get_object_ref_from_package() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
             ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
 
         let object_ref = get_objects(&package)
@@ -1426,6 +2657,193 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mesh_object_vertex_normals() {
+        use crate::core::mesh::{Triangle, Triangles, Vertex, Vertices};
+
+        let mut vertex = (0..8)
+            .map(|i| Vertex {
+                x: (i & 1) as f64,
+                y: ((i >> 1) & 1) as f64,
+                z: ((i >> 2) & 1) as f64,
+            })
+            .collect::<Vec<_>>();
+        // unreferenced vertex
+        vertex.push(Vertex {
+            x: 5.0,
+            y: 5.0,
+            z: 5.0,
+        });
+        let triangle = [
+            [0, 2, 1],
+            [1, 2, 3],
+            [4, 5, 6],
+            [5, 7, 6],
+            [0, 1, 4],
+            [1, 5, 4],
+            [2, 6, 3],
+            [3, 6, 7],
+            [0, 4, 2],
+            [2, 4, 6],
+            [1, 3, 5],
+            [3, 7, 5],
+        ]
+        .iter()
+        .map(|&[v1, v2, v3]| Triangle {
+            v1,
+            v2,
+            v3,
+            p1: None,
+            p2: None,
+            p3: None,
+            pid: None,
+        })
+        .collect();
+        let object = Object {
+            id: 1,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: Some(Mesh {
+                vertices: Vertices { vertex },
+                triangles: Triangles { triangle },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: None,
+        };
+        let mesh_ref = MeshObjectRef::new(ObjectRef {
+            object: &object,
+            path: None,
+        })
+        .unwrap();
+
+        let normals = mesh_ref.vertex_normals();
+        assert_eq!(normals.len(), 9);
+        assert_eq!(normals[8], [0.0, 0.0, 0.0]);
+        for (i, normal) in normals[..8].iter().enumerate() {
+            let diagonal = [
+                if i & 1 == 1 { 1.0 } else { -1.0 },
+                if (i >> 1) & 1 == 1 { 1.0 } else { -1.0 },
+                if (i >> 2) & 1 == 1 { 1.0 } else { -1.0 },
+            ];
+            let cos = normal.iter().zip(diagonal).map(|(n, d)| n * d).sum::<f64>() / 3.0_f64.sqrt();
+            assert!(
+                cos > 0.9,
+                "vertex {i} normal {normal:?} is off the diagonal"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mesh_object_world_bounding_box() {
+        use crate::core::mesh::{Triangles, Vertex, Vertices};
+
+        let object = |vertex: Vec<Vertex>| Object {
+            id: 1,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: Some(Mesh {
+                vertices: Vertices { vertex },
+                triangles: Triangles { triangle: vec![] },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: None,
+        };
+        let cube = object(
+            (0..8)
+                .map(|i| Vertex {
+                    x: (i & 1) as f64,
+                    y: ((i >> 1) & 1) as f64,
+                    z: ((i >> 2) & 1) as f64,
+                })
+                .collect(),
+        );
+        let cube_ref = MeshObjectRef::new(ObjectRef {
+            object: &cube,
+            path: None,
+        })
+        .unwrap();
+
+        assert_eq!(
+            cube_ref.world_bounding_box(None),
+            Some(([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]))
+        );
+        let translation = Transform::from_translation([10.0, -20.0, 30.0]);
+        assert_eq!(
+            cube_ref.world_bounding_box(Some(&translation)),
+            Some(([10.0, -20.0, 30.0], [11.0, -19.0, 31.0]))
+        );
+        // rotation of 90 degrees around z
+        let rotation = Transform([0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(
+            cube_ref.world_bounding_box(Some(&rotation)),
+            Some(([-1.0, 0.0, 0.0], [0.0, 1.0, 1.0]))
+        );
+        let huge = Transform::from_translation([1e300, -1e300, 0.0]);
+        assert_eq!(
+            cube_ref.world_bounding_box(Some(&huge)),
+            Some(([1e300, -1e300, 0.0], [1e300, -1e300, 1.0]))
+        );
+
+        let point = object(vec![Vertex {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        }]);
+        let point_ref = MeshObjectRef::new(ObjectRef {
+            object: &point,
+            path: None,
+        })
+        .unwrap();
+        assert_eq!(
+            point_ref.world_bounding_box(Some(&translation)),
+            Some(([11.0, -18.0, 33.0], [11.0, -18.0, 33.0]))
+        );
+
+        let empty = object(vec![]);
+        let empty_ref = MeshObjectRef::new(ObjectRef {
+            object: &empty,
+            path: None,
+        })
+        .unwrap();
+        assert_eq!(empty_ref.world_bounding_box(Some(&translation)), None);
+    }
+
+    #[test]
+    fn test_mesh_object_triangle_keys() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let mut keys = std::collections::HashSet::new();
+        for mesh_ref in get_mesh_objects(&package) {
+            let triangles = &mesh_ref.mesh().triangles.triangle;
+            let mut count = 0;
+            for (key, triangle) in mesh_ref.triangle_keys() {
+                assert_eq!(key.model_path.as_deref(), mesh_ref.origin_model_path);
+                assert_eq!(key.object_id, mesh_ref.id);
+                assert!(std::ptr::eq(triangle, &triangles[key.triangle_index]));
+                assert!(keys.insert(key));
+                count += 1;
+            }
+            assert_eq!(count, triangles.len());
+        }
+        assert!(!keys.is_empty());
+    }
+
     #[test]
     fn test_get_objects_from_package() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -1476,6 +2894,31 @@ mod tests {
         assert_eq!(objects, 2);
     }
 
+    #[test]
+    fn test_mesh_object_counts() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let mut lattices = 0;
+        for mesh_ref in get_mesh_objects(&package) {
+            let mesh = mesh_ref.mesh();
+            assert_eq!(mesh_ref.vertex_count(), mesh.vertices.vertex.len());
+            assert_eq!(mesh_ref.triangle_count(), mesh.triangles.triangle.len());
+            match &mesh.beamlattice {
+                Some(beamlattice) => {
+                    assert_eq!(mesh_ref.beam_count(), beamlattice.beams.beam.len());
+                    assert!(mesh_ref.beam_count() > 0);
+                    lattices += 1;
+                }
+                None => assert_eq!(mesh_ref.beam_count(), 0),
+            }
+        }
+        assert_eq!(lattices, 2);
+    }
+
     #[test]
     fn test_iter_models_from_package() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -1511,6 +2954,149 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_component() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let composed_objects = get_components_objects(&package).collect::<Vec<_>>();
+        let names = composed_objects[0]
+            .components()
+            .map(|comp| {
+                let target = resolve_component(&package, &comp).unwrap();
+                assert_eq!(target.object.id, comp.objectid);
+                assert_eq!(target.path, Some("/3D/Objects/Object(2).model"));
+                target.object.name.clone().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Torus_1", "Torus_2"]);
+
+        let missing = ComponentRef {
+            objectid: 1,
+            path_to_look_for: Some("/3D/Objects/Missing.model".to_owned()),
+            transform: None,
+            uuid: None,
+        };
+        assert!(resolve_component(&package, &missing).is_none());
+    }
+
+    #[test]
+    fn test_locate_object() {
+        use crate::core::{build::Build, resources::Resources};
+
+        let model = |objects: &[(usize, &str)]| Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                object: objects
+                    .iter()
+                    .map(|(id, name)| Object {
+                        id: *id,
+                        objecttype: None,
+                        thumbnail: None,
+                        partnumber: None,
+                        name: Some(name.to_string()),
+                        pid: None,
+                        pindex: None,
+                        uuid: None,
+                        mesh: None,
+                        components: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            build: Build::default(),
+        };
+        let mut package = ThreemfPackage::from_root_model(model(&[(1, "root 1"), (2, "root 2")]));
+        package
+            .sub_models
+            .insert("/3D/b.model".to_owned(), model(&[(1, "b 1"), (3, "b 3")]));
+        package
+            .sub_models
+            .insert("/3D/a.model".to_owned(), model(&[(1, "a 1"), (3, "a 3")]));
+
+        let locate = |id, preferred_path| {
+            locate_object(&package, id, preferred_path).map(|(object, path)| {
+                assert_eq!(object.path, path.as_deref());
+                (object.object.name.clone().unwrap(), path)
+            })
+        };
+
+        // the preferred model is searched first
+        assert_eq!(
+            locate(1, Some("/3D/b.model")),
+            Some(("b 1".to_owned(), Some("/3D/b.model".to_owned())))
+        );
+        // then the root model
+        assert_eq!(locate(1, None), Some(("root 1".to_owned(), None)));
+        assert_eq!(
+            locate(2, Some("/3D/b.model")),
+            Some(("root 2".to_owned(), None))
+        );
+        assert_eq!(
+            locate(1, Some("/3D/Missing.model")),
+            Some(("root 1".to_owned(), None))
+        );
+        // the root model may be named by its path
+        assert_eq!(
+            locate(1, Some(package.root_model_path())),
+            Some(("root 1".to_owned(), None))
+        );
+        // and finally the sub-models in the order of their paths
+        assert_eq!(
+            locate(3, None),
+            Some(("a 3".to_owned(), Some("/3D/a.model".to_owned())))
+        );
+        assert_eq!(
+            locate(3, Some("/3D/b.model")),
+            Some(("b 3".to_owned(), Some("/3D/b.model".to_owned())))
+        );
+        assert_eq!(locate(4, Some("/3D/b.model")), None);
+    }
+
+    #[test]
+    fn test_find_object_short_circuits() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let total = get_objects(&package).count();
+        assert!(total > 1);
+
+        let first = get_objects(&package).next().unwrap();
+        let mut visited = 0;
+        let found = find_object(&package, |r| {
+            visited += 1;
+            r.object.id == first.object.id && r.path == first.path
+        });
+        assert!(found.is_some());
+        assert_eq!(visited, 1);
+
+        let mut visited = 0;
+        assert!(any_object(&package, |_| {
+            visited += 1;
+            true
+        }));
+        assert_eq!(visited, 1);
+
+        let mut visited = 0;
+        assert!(!any_object(&package, |_| {
+            visited += 1;
+            false
+        }));
+        assert_eq!(visited, total);
+
+        let mesh_count = count_objects_where(&package, |r| r.object.mesh.is_some());
+        assert_eq!(mesh_count, get_mesh_objects(&package).count());
+    }
+
     #[test]
     fn test_get_items_from_package() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -1560,4 +3146,837 @@ mod tests {
             .count();
         assert!(root_items > 0);
     }
+
+    #[test]
+    fn test_item_ref_effective_partnumber() {
+        fn set_object_partnumber(
+            package: &mut ThreemfPackage,
+            path: Option<&str>,
+            objectid: usize,
+            partnumber: Option<&str>,
+        ) {
+            let model = match path {
+                Some(path) => package.sub_models.get_mut(path).unwrap(),
+                None => &mut package.root,
+            };
+            let object = model
+                .resources
+                .object
+                .iter_mut()
+                .find(|o| o.id == objectid)
+                .unwrap();
+            object.partnumber = partnumber.map(str::to_owned);
+        }
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let item = get_items(&package).next().unwrap();
+        let (objectid, object_path) = (item.objectid(), item.path().map(str::to_owned));
+
+        // neither the item nor the object has a part number
+        set_object_partnumber(&mut package, object_path.as_deref(), objectid, None);
+        package.root.build.item[0].partnumber = None;
+        let item = get_items(&package).next().unwrap();
+        assert_eq!(item.effective_partnumber(&package), None);
+
+        // inherited from the object
+        set_object_partnumber(
+            &mut package,
+            object_path.as_deref(),
+            objectid,
+            Some("object-part"),
+        );
+        let item = get_items(&package).next().unwrap();
+        assert_eq!(item.partnumber(), None);
+        assert_eq!(
+            item.effective_partnumber(&package).as_deref(),
+            Some("object-part")
+        );
+
+        // overridden on the item
+        package.root.build.item[0].partnumber = Some("item-part".to_owned());
+        let item = get_items(&package).next().unwrap();
+        assert_eq!(
+            item.effective_partnumber(&package).as_deref(),
+            Some("item-part")
+        );
+    }
+
+    #[test]
+    fn test_topological_object_order() {
+        use crate::core::component::Component;
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let component = |objectid: usize, path: Option<&str>| Component {
+            objectid,
+            transform: None,
+            path: path.map(str::to_owned),
+            uuid: None,
+        };
+
+        // an assembly in the root model referencing the composed part of another model
+        package.root.resources.object.push(Object {
+            id: 10,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: None,
+            components: Some(Components {
+                component: vec![component(3, Some("/3D/Objects/Object(2).model"))],
+            }),
+        });
+
+        let order = topological_object_order(&package).unwrap();
+        let object = |id: usize, path: &str| (id, Some(path.to_owned()));
+        assert_eq!(
+            order,
+            vec![
+                object(1, "/3D/Objects/Object(2).model"),
+                object(2, "/3D/Objects/Object(2).model"),
+                object(3, "/3D/Objects/Object(2).model"),
+                (10, None),
+                object(1, "/3D/Objects/Object(3).model"),
+                object(1, "/3D/Objects/Object(4).model"),
+                object(1, "/3D/Objects/Object.model"),
+            ]
+        );
+
+        // Torus_1 referencing the composed part containing it
+        let torus = package
+            .sub_models
+            .get_mut("/3D/Objects/Object(2).model")
+            .unwrap()
+            .resources
+            .object
+            .iter_mut()
+            .find(|o| o.id == 1)
+            .unwrap();
+        torus.components = Some(Components {
+            component: vec![component(3, None)],
+        });
+
+        let err = topological_object_order(&package).unwrap_err();
+        assert_eq!(
+            err.chain,
+            vec![
+                object(3, "/3D/Objects/Object(2).model"),
+                object(1, "/3D/Objects/Object(2).model"),
+                object(3, "/3D/Objects/Object(2).model"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolved_build_instances() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let sub_model = "/3D/Objects/Object(2).model";
+        let offset = Transform([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 5.0, 0.0, 0.0]);
+        let composed = package
+            .sub_models
+            .get_mut(sub_model)
+            .unwrap()
+            .resources
+            .object
+            .iter_mut()
+            .find(|o| o.id == 3)
+            .unwrap();
+        composed.components.as_mut().unwrap().component[0].transform = Some(offset.clone());
+
+        let item_transforms = package
+            .root
+            .build
+            .item
+            .iter()
+            .map(|item| item.transform.clone().unwrap())
+            .collect::<Vec<_>>();
+
+        let instances = resolved_build_instances(&package).unwrap();
+        assert_eq!(
+            instances,
+            vec![
+                BuildInstance {
+                    objectid: 1,
+                    path: Some("/3D/Objects/Object.model".to_owned()),
+                    item: 0,
+                    transform: item_transforms[0].clone(),
+                },
+                BuildInstance {
+                    objectid: 1,
+                    path: Some(sub_model.to_owned()),
+                    item: 1,
+                    transform: offset.then(&item_transforms[1]),
+                },
+                BuildInstance {
+                    objectid: 2,
+                    path: Some(sub_model.to_owned()),
+                    item: 1,
+                    transform: item_transforms[1].clone(),
+                },
+            ]
+        );
+
+        // a component referencing the composed part it belongs to
+        let composed = package
+            .sub_models
+            .get_mut(sub_model)
+            .unwrap()
+            .resources
+            .object
+            .iter_mut()
+            .find(|o| o.id == 3)
+            .unwrap();
+        composed.components.as_mut().unwrap().component[0].objectid = 3;
+        assert!(matches!(
+            resolved_build_instances(&package),
+            Err(Error::RecursiveComponent { id: 3 })
+        ));
+
+        package.root.build.item[0].objectid = 42;
+        assert!(matches!(
+            resolved_build_instances(&package),
+            Err(Error::ResourceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_mesh_instances() {
+        use crate::core::{
+            build::Build,
+            mesh::{Triangles, Vertex, Vertices},
+            resources::Resources,
+        };
+
+        let object = |id: usize, mesh: Option<Mesh>, components: Vec<Component>| Object {
+            id,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh,
+            components: (!components.is_empty()).then_some(Components {
+                component: components,
+            }),
+        };
+        let component = |objectid: usize, path: Option<&str>, transform: Transform| Component {
+            objectid,
+            transform: Some(transform),
+            path: path.map(str::to_owned),
+            uuid: None,
+        };
+        let model = |objects: Vec<Object>, items: Vec<Item>| Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                object: objects,
+                ..Default::default()
+            },
+            build: Build {
+                uuid: None,
+                item: items,
+            },
+        };
+        let mesh = Mesh {
+            vertices: Vertices {
+                vertex: vec![Vertex {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                }],
+            },
+            triangles: Triangles { triangle: vec![] },
+            trianglesets: None,
+            beamlattice: None,
+        };
+
+        // the assembly 3 holds the sub-assembly 2 scaled, which holds the mesh of the
+        // sub-model moved along x, the build item moves the assembly along z
+        let parts = "/3D/parts.model";
+        let scale = Transform::from_scale([2.0, 2.0, 2.0]);
+        let offset = Transform::from_translation([1.0, 0.0, 0.0]);
+        let item_transform = Transform::from_translation([0.0, 0.0, 10.0]);
+        let mut package = ThreemfPackage::from_root_model(model(
+            vec![
+                object(2, None, vec![component(1, Some(parts), offset.clone())]),
+                object(3, None, vec![component(2, None, scale.clone())]),
+                object(4, None, vec![component(5, None, Transform::IDENTITY)]),
+                object(5, None, vec![component(4, None, Transform::IDENTITY)]),
+            ],
+            vec![Item {
+                objectid: 3,
+                transform: Some(item_transform.clone()),
+                ..Default::default()
+            }],
+        ));
+        package.sub_models.insert(
+            parts.to_owned(),
+            model(vec![object(1, Some(mesh.clone()), vec![])], vec![]),
+        );
+
+        let instances = resolve_mesh_instances(&package).unwrap();
+        assert_eq!(
+            instances,
+            vec![ResolvedInstance {
+                objectid: 1,
+                mesh: &mesh,
+                transform: offset.then(&scale).then(&item_transform),
+                path: Some(parts),
+            }]
+        );
+        assert_eq!(
+            instances[0].transform.transform_point([1.0, 0.0, 0.0]),
+            [4.0, 0.0, 10.0]
+        );
+
+        // the objects 4 and 5 reference each other
+        package.root.build.item.push(Item {
+            objectid: 4,
+            ..Default::default()
+        });
+        assert!(matches!(
+            resolve_mesh_instances(&package),
+            Err(Error::RecursiveComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_deeply_nested_components() {
+        use crate::core::{
+            build::Build,
+            mesh::{Triangles, Vertex, Vertices},
+            resources::Resources,
+        };
+
+        const DEPTH: usize = 10_000;
+
+        // object 1 references object 2 and so on, the last object holds the mesh
+        let object = |id: usize| Object {
+            id,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: (id == DEPTH).then(|| Mesh {
+                vertices: Vertices {
+                    vertex: vec![Vertex {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    }],
+                },
+                triangles: Triangles { triangle: vec![] },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: (id < DEPTH).then(|| Components {
+                component: vec![Component {
+                    objectid: id + 1,
+                    transform: None,
+                    path: None,
+                    uuid: None,
+                }],
+            }),
+        };
+        let package = ThreemfPackage::from_root_model(Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                object: (1..=DEPTH).map(object).collect(),
+                ..Default::default()
+            },
+            build: Build {
+                uuid: None,
+                item: vec![Item {
+                    objectid: 1,
+                    ..Default::default()
+                }],
+            },
+        });
+
+        assert!(matches!(
+            resolved_build_instances(&package),
+            Err(Error::ComponentDepthExceeded {
+                max_depth: DEFAULT_MAX_COMPONENT_DEPTH
+            })
+        ));
+        assert!(matches!(
+            package.flatten_to_single_object(),
+            Err(Error::ComponentDepthExceeded { .. })
+        ));
+
+        let instances = resolved_build_instances_with_max_depth(&package, DEPTH).unwrap();
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].objectid, DEPTH);
+        assert!(
+            package
+                .flatten_to_single_object_with_max_depth(DEPTH)
+                .is_ok()
+        );
+
+        let order = topological_object_order(&package).unwrap();
+        assert_eq!(order.len(), DEPTH);
+        assert_eq!(order[0], (DEPTH, None));
+        assert_eq!(order[DEPTH - 1], (1, None));
+    }
+
+    #[test]
+    fn test_material_volume_report() {
+        use crate::core::{
+            build::Build,
+            mesh::{Triangles, Vertex, Vertices},
+            resources::Resources,
+        };
+
+        #[rustfmt::skip]
+        let scale = Transform([
+            2.0, 0.0, 0.0,
+            0.0, 2.0, 0.0,
+            0.0, 0.0, 2.0,
+            10.0, 0.0, 0.0,
+        ]);
+        let cube = |triangle_pid: Option<usize>| Mesh {
+            vertices: Vertices {
+                vertex: (0..8)
+                    .map(|i| Vertex {
+                        x: (i & 1) as f64,
+                        y: ((i >> 1) & 1) as f64,
+                        z: ((i >> 2) & 1) as f64,
+                    })
+                    .collect(),
+            },
+            triangles: Triangles {
+                triangle: [
+                    [0, 2, 1],
+                    [1, 2, 3],
+                    [4, 5, 6],
+                    [5, 7, 6],
+                    [0, 1, 4],
+                    [1, 5, 4],
+                    [2, 6, 3],
+                    [3, 6, 7],
+                    [0, 4, 2],
+                    [2, 4, 6],
+                    [1, 3, 5],
+                    [3, 7, 5],
+                ]
+                .into_iter()
+                .map(|[v1, v2, v3]| Triangle {
+                    v1,
+                    v2,
+                    v3,
+                    p1: triangle_pid.map(|_| 0),
+                    p2: None,
+                    p3: None,
+                    pid: triangle_pid,
+                })
+                .collect(),
+            },
+            trianglesets: None,
+            beamlattice: None,
+        };
+        let object = |id, pid, mesh, components| Object {
+            id,
+            objecttype: None,
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid,
+            pindex: pid.map(|_| 0),
+            uuid: None,
+            mesh,
+            components,
+        };
+        let component = |transform: &Transform| Component {
+            objectid: 1,
+            transform: Some(transform.clone()),
+            path: None,
+            uuid: None,
+        };
+        let mut support = object(5, Some(5), Some(cube(None)), None);
+        support.objecttype = Some(ObjectType::Support);
+
+        let package = ThreemfPackage::from_root_model(Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                object: vec![
+                    object(1, Some(5), Some(cube(None)), None),
+                    object(2, None, Some(cube(Some(7))), None),
+                    object(3, None, Some(cube(None)), None),
+                    object(
+                        4,
+                        None,
+                        None,
+                        Some(Components {
+                            component: vec![component(&scale), component(&Transform::IDENTITY)],
+                        }),
+                    ),
+                    support,
+                ],
+                ..Default::default()
+            },
+            build: Build {
+                uuid: None,
+                item: (1..=5)
+                    .map(|objectid| Item {
+                        objectid,
+                        ..Default::default()
+                    })
+                    .collect(),
+            },
+        });
+
+        let report = material_volume_report(&package).unwrap();
+        assert_eq!(report, HashMap::from([(5, 10.0), (7, 1.0)]));
+    }
+
+    #[test]
+    fn test_objects_in_build_order() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let order = |package: &ThreemfPackage| {
+            objects_in_build_order(package)
+                .iter()
+                .map(|(item, object)| {
+                    (
+                        item.objectid(),
+                        object
+                            .as_ref()
+                            .map(|o| (o.object.id, o.path.map(str::to_owned))),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            order(&package),
+            vec![
+                (1, Some((1, Some("/3D/Objects/Object.model".to_owned())))),
+                (3, Some((3, Some("/3D/Objects/Object(2).model".to_owned())))),
+            ]
+        );
+
+        package.root.build.item.reverse();
+        package.root.build.item[0].objectid = 42;
+        assert_eq!(
+            order(&package),
+            vec![
+                (42, None),
+                (1, Some((1, Some("/3D/Objects/Object.model".to_owned())))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_material_report() {
+        use crate::core::{
+            build::Build,
+            mesh::{Triangles, Vertex, Vertices},
+            resources::{BaseMaterials, Resources},
+        };
+
+        let base = |name: &str, color: &str| Base {
+            name: name.to_owned(),
+            displaycolor: color.to_owned(),
+        };
+        let triangle = |pid: Option<usize>, p1: Option<usize>| Triangle {
+            v1: 0,
+            v2: 1,
+            v3: 2,
+            p1,
+            p2: None,
+            p3: None,
+            pid,
+        };
+        let object = |id: usize,
+                      objecttype: Option<ObjectType>,
+                      pindex: Option<usize>,
+                      triangles: Vec<Triangle>| Object {
+            id,
+            objecttype,
+            thumbnail: None,
+            partnumber: None,
+            name: Some(format!("Object {id}")),
+            pid: pindex.map(|_| 1),
+            pindex,
+            uuid: None,
+            mesh: Some(Mesh {
+                vertices: Vertices {
+                    vertex: vec![
+                        Vertex {
+                            x: 0.0,
+                            y: 0.0,
+                            z: 0.0
+                        };
+                        3
+                    ],
+                },
+                triangles: Triangles {
+                    triangle: triangles,
+                },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: None,
+        };
+
+        let package = ThreemfPackage::from(Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                unknown_resources: vec![],
+                object: vec![
+                    object(
+                        2,
+                        None,
+                        Some(0),
+                        vec![
+                            // inherits the object material
+                            triangle(None, None),
+                            triangle(Some(1), Some(2)),
+                            // same base as the object material
+                            triangle(Some(1), Some(0)),
+                            // out of range and unknown property group
+                            triangle(Some(1), Some(7)),
+                            triangle(Some(9), Some(0)),
+                        ],
+                    ),
+                    object(3, Some(ObjectType::Support), Some(1), vec![]),
+                    object(4, None, None, vec![triangle(None, None)]),
+                ],
+                basematerials: vec![BaseMaterials {
+                    id: 1,
+                    base: vec![
+                        base("Red", "#FF0000"),
+                        base("Green", "#00FF00"),
+                        base("Blue", "#0000FF"),
+                    ],
+                }],
+            },
+            build: Build::default(),
+        });
+
+        let report = object_material_report(&package);
+        assert_eq!(
+            report,
+            vec![
+                ObjectMaterials {
+                    id: 2,
+                    path: None,
+                    name: Some("Object 2".to_owned()),
+                    materials: vec![base("Red", "#FF0000"), base("Blue", "#0000FF")],
+                },
+                ObjectMaterials {
+                    id: 4,
+                    path: None,
+                    name: Some("Object 4".to_owned()),
+                    materials: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iter_uuids() {
+        let path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/mesh-composedpart.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let uuids = iter_uuids(&package).collect::<Vec<_>>();
+        assert_eq!(uuids.len(), 9);
+        assert_eq!(
+            uuids[0],
+            (
+                "b0497bf3-977c-4f27-a12a-5e101ab12b60",
+                UuidOwner::Build { path: None }
+            )
+        );
+        assert_eq!(
+            uuids[2],
+            (
+                "3b0a584b-a446-4445-a60e-5781477ecd56",
+                UuidOwner::Item {
+                    index: 1,
+                    objectid: 4,
+                    path: None
+                }
+            )
+        );
+        assert_eq!(
+            uuids[8],
+            (
+                "7d272a11-4b1e-4730-baca-46e7c18d49bc",
+                UuidOwner::Component {
+                    object_id: 4,
+                    index: 1,
+                    path: None
+                }
+            )
+        );
+        assert_eq!(duplicate_uuids(&package), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_duplicate_uuids() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/duplicate-uuid.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let duplicate = "39dfcd9e-1295-4bd6-89ef-aac46ebeca35";
+        assert_eq!(duplicate_uuids(&package), vec![duplicate.to_owned()]);
+
+        let owners = iter_uuids(&package)
+            .filter(|(uuid, _)| *uuid == duplicate)
+            .map(|(_, owner)| owner)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            owners,
+            vec![
+                UuidOwner::Item {
+                    index: 1,
+                    objectid: 4,
+                    path: None
+                },
+                UuidOwner::Component {
+                    object_id: 4,
+                    index: 0,
+                    path: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_uuid() {
+        let canonical = "550e8400-e29b-41d4-a716-446655440000";
+        for uuid in [
+            canonical,
+            "550E8400-E29B-41D4-A716-446655440000",
+            "550e8400e29b41d4a716446655440000",
+            " 550E8400E29B41D4A716446655440000 ",
+        ] {
+            assert_eq!(normalize_uuid(uuid), canonical);
+        }
+
+        // not a UUID, only lowercased
+        assert_eq!(normalize_uuid("Not-A-UUID"), "not-a-uuid");
+    }
+
+    #[test]
+    fn test_get_object_and_component_by_uuid() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let file = std::fs::File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(file, true).unwrap();
+
+        let slider = get_object_by_uuid(&package, "7310be75-530f-4ef8-b9ad-c5999e2b60d5").unwrap();
+        assert_eq!(slider.object.id, 1);
+        assert_eq!(slider.object.name.as_deref(), Some("Slider"));
+        assert_eq!(slider.path, Some("/3D/Objects/Object(3).model"));
+
+        let composed =
+            get_object_by_uuid(&package, "0876D4F1-6D50-4A6F-9215-6D88058BB346").unwrap();
+        assert_eq!(composed.object.id, 3);
+        assert_eq!(composed.path, Some("/3D/Objects/Object(2).model"));
+
+        // the UUID of a build item, not of an object
+        assert!(get_object_by_uuid(&package, "5fff3f1e-6c01-4bec-a029-2adf47318b42").is_none());
+
+        let (parent, component) =
+            get_component_by_uuid(&package, "7d272a11-4b1e-4730-baca-46e7c18d49bc").unwrap();
+        assert_eq!(parent.id, 3);
+        assert_eq!(
+            parent.origin_model_path,
+            Some("/3D/Objects/Object(2).model")
+        );
+        assert_eq!(
+            component.uuid.as_deref(),
+            Some("7d272a11-4b1e-4730-baca-46e7c18d49bc")
+        );
+        let target = resolve_component(&package, &component).unwrap();
+        assert_eq!(target.object.name.as_deref(), Some("Torus_2"));
+
+        assert!(get_component_by_uuid(&package, "0876d4f1-6d50-4a6f-9215-6d88058bb346").is_none());
+    }
+
+    #[test]
+    fn test_uuid_lookup_ignores_formatting() {
+        use crate::core::{build::Build, resources::Resources};
+
+        let item = |objectid, uuid: &str| Item {
+            objectid,
+            uuid: Some(uuid.to_owned()),
+            ..Default::default()
+        };
+        let package = ThreemfPackage::from_root_model(Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources::default(),
+            build: Build {
+                uuid: None,
+                item: vec![
+                    item(1, "550E8400E29B41D4A716446655440000"),
+                    item(2, "6BA7B810-9DAD-11D1-80B4-00C04FD430C8"),
+                    item(3, "6ba7b8109dad11d180b400c04fd430c8"),
+                ],
+            },
+        });
+
+        let found = get_item_by_uuid(&package, "550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(found.objectid(), 1);
+        // the original string is kept
+        assert_eq!(found.uuid(), Some("550E8400E29B41D4A716446655440000"));
+        assert_eq!(
+            found.uuid_normalized().as_deref(),
+            Some("550e8400-e29b-41d4-a716-446655440000")
+        );
+        let found = get_item_by_uuid(&package, "6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+        assert_eq!(found.objectid(), 2);
+
+        assert_eq!(
+            duplicate_uuids(&package),
+            vec!["6BA7B810-9DAD-11D1-80B4-00C04FD430C8".to_owned()]
+        );
+    }
 }
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::{
+    core::model::Model,
+    io::{error::Error, relationship::Relationships, utils},
+};
+
+/// Options that control how the models of a 3mf package are validated and
+/// normalized while reading.
+///
+/// The [Default] options are strict and follow the 3MF Core specification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// The 3MF Core specification forbids an [Object](crate::core::object::Object) to contain
+    /// both a mesh and components. By default such an object fails the read with
+    /// [Error::ObjectHasMeshAndComponents]. If set to true, the mesh is kept and the
+    /// components are dropped instead.
+    pub prefer_mesh_over_components: bool,
+
+    /// Relationship targets may be relative to the folder of their source part, and the
+    /// `path` of build items and components may be written without the leading slash.
+    /// If set to true, all of them are resolved to package-absolute part names like
+    /// `/3D/Objects/part.model`, so the keys of the sub-models, thumbnails and unknown parts
+    /// of the [ThreemfPackage](crate::io::ThreemfPackage) match the paths in the models.
+    pub normalize_paths: bool,
+
+    /// Limits on the complexity of the package, e.g. for untrusted uploads.
+    /// There are no limits by default.
+    pub limits: ReadLimits,
+}
+
+/// Limits on the complexity of a 3mf package checked while reading, summed up over the
+/// root model and all sub-models.
+///
+/// The objects and triangles of a model part are counted while scanning its XML once the
+/// part has been read into memory, before it is deserialized. The scan stops at the first
+/// element exceeding a limit and the read fails with [Error::ComplexityLimitExceeded]
+/// without deserializing the model, but the size of the part itself is not limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// Maximum number of objects in all models of the package.
+    pub max_objects: Option<usize>,
+
+    /// Maximum number of triangles in all meshes of the package.
+    pub max_triangles_total: Option<usize>,
+}
+
+/// The kind of a [ReadLimits] limit exceeded by a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComplexityLimit {
+    /// [ReadLimits::max_objects]
+    Objects,
+
+    /// [ReadLimits::max_triangles_total]
+    Triangles,
+}
+
+impl fmt::Display for ComplexityLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Objects => write!(f, "objects"),
+            Self::Triangles => write!(f, "triangles"),
+        }
+    }
+}
+
+/// The number of objects and triangles read so far, checked against the [ReadLimits].
+#[derive(Debug, Default)]
+pub(crate) struct ComplexityTotals {
+    objects: usize,
+    triangles: usize,
+}
+
+impl ReadLimits {
+    /// Adds the objects and triangles of the model XML to `totals`, failing as soon as a
+    /// limit is exceeded.
+    pub(crate) fn check_model(
+        &self,
+        xml: &str,
+        totals: &mut ComplexityTotals,
+    ) -> Result<(), Error> {
+        if self.max_objects.is_none() && self.max_triangles_total.is_none() {
+            return Ok(());
+        }
+
+        utils::scan_element_local_names(xml, |name| {
+            let (kind, total, limit) = match name {
+                "object" => (
+                    ComplexityLimit::Objects,
+                    &mut totals.objects,
+                    self.max_objects,
+                ),
+                "triangle" => (
+                    ComplexityLimit::Triangles,
+                    &mut totals.triangles,
+                    self.max_triangles_total,
+                ),
+                _ => return Ok(()),
+            };
+
+            *total += 1;
+            match limit {
+                Some(limit) if *total > limit => {
+                    Err(Error::ComplexityLimitExceeded { kind, limit })
+                }
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+impl ReadOptions {
+    /// Validates and normalizes a freshly deserialized model, read from the part at `path`,
+    /// according to these options.
+    pub(crate) fn apply(&self, model: &mut Model, path: &str) -> Result<(), Error> {
+        if self.normalize_paths {
+            let folder = path.rsplit_once('/').map_or("", |(folder, _)| folder);
+            let item_paths = model.build.item.iter_mut().map(|item| &mut item.path);
+            let component_paths = model
+                .resources
+                .object
+                .iter_mut()
+                .filter_map(|object| object.components.as_mut())
+                .flat_map(|components| &mut components.component)
+                .map(|component| &mut component.path);
+            for part_name in item_paths.chain(component_paths).flatten() {
+                *part_name = utils::resolve_part_name(folder, part_name);
+            }
+        }
+
+        for object in &mut model.resources.object {
+            if object.mesh.is_some() && object.components.is_some() {
+                if self.prefer_mesh_over_components {
+                    object.components = None;
+                } else {
+                    return Err(Error::ObjectHasMeshAndComponents { id: object.id });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Normalizes the targets of the relationships read from the file at `rels_path`.
+    pub(crate) fn apply_to_relationships(
+        &self,
+        relationships: &mut Relationships,
+        rels_path: &str,
+    ) {
+        if self.normalize_paths {
+            let folder = utils::relationships_source_folder(rels_path);
+            for relationship in &mut relationships.relationships {
+                relationship.target = utils::resolve_part_name(folder, &relationship.target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ComplexityLimit, ComplexityTotals, ReadLimits};
+    use crate::io::error::Error;
+
+    #[test]
+    fn check_model_stops_at_limit_test() {
+        let xml = r#"<model><resources><object><mesh><triangles><triangle/><triangle/></triangles></mesh></object><object/><object/></resources></model>"#;
+        let limits = ReadLimits {
+            max_objects: None,
+            max_triangles_total: Some(3),
+        };
+
+        let mut totals = ComplexityTotals::default();
+        limits.check_model(xml, &mut totals).unwrap();
+        assert_eq!((totals.objects, totals.triangles), (3, 2));
+
+        let result = limits.check_model(xml, &mut totals);
+        assert!(matches!(
+            result,
+            Err(Error::ComplexityLimitExceeded {
+                kind: ComplexityLimit::Triangles,
+                limit: 3
+            })
+        ));
+        // the objects after the exceeding triangle are not counted
+        assert_eq!((totals.objects, totals.triangles), (4, 4));
+    }
+}
@@ -63,6 +63,20 @@ pub struct Relationships {
     pub relationships: Vec<Relationship>,
 }
 
+impl Relationships {
+    /// Returns the relationships of the package root, `_rels/.rels`, holding a single
+    /// relationship to the root model at `model_path`.
+    pub fn root_with_model(model_path: &str) -> Self {
+        Self {
+            relationships: vec![Relationship {
+                id: "rel0".to_owned(),
+                target: model_path.to_owned(),
+                relationship_type: RelationshipType::Model,
+            }],
+        }
+    }
+}
+
 /// Represents the type of relationship of a part in the 3mf package.
 #[cfg_attr(feature = "speed-optimized-read", derive(Deserialize))]
 #[cfg_attr(feature = "speed-optimized-read", serde(from = "String"))]
@@ -188,6 +202,17 @@ mod write_tests {
 
         assert_eq!(relationships_string, xml_string);
     }
+
+    #[test]
+    pub fn toxml_root_with_model_test() {
+        let xml_string = format!(
+            r#"<Relationships xmlns="{}"><Relationship Id="rel0" Target="/3D/3dmodel.model" Type="{}" /></Relationships>"#,
+            RELATIONSHIP_NS, MODEL_TYPE_NS
+        );
+        let relationships = Relationships::root_with_model("/3D/3dmodel.model");
+
+        assert_eq!(to_string(&relationships).unwrap(), xml_string);
+    }
 }
 
 #[cfg(feature = "memory-optimized-read")]
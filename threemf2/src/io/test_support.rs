@@ -0,0 +1,70 @@
+//! Fixtures shared by the unit tests of the io module.
+
+use crate::core::{
+    build::{Build, Item},
+    component::{Component, Components},
+    mesh::Mesh,
+    model::Model,
+    object::Object,
+    resources::{BaseMaterials, Resources},
+    transform::Transform,
+};
+
+pub(crate) fn object(id: usize, mesh: Option<Mesh>, components: Option<Vec<Component>>) -> Object {
+    Object {
+        id,
+        objecttype: None,
+        thumbnail: None,
+        partnumber: None,
+        name: None,
+        pid: None,
+        pindex: None,
+        uuid: None,
+        mesh,
+        components: components.map(|component| Components { component }),
+    }
+}
+
+pub(crate) fn component(
+    objectid: usize,
+    transform: Option<Transform>,
+    path: Option<&str>,
+) -> Component {
+    Component {
+        objectid,
+        transform,
+        path: path.map(str::to_owned),
+        uuid: None,
+    }
+}
+
+pub(crate) fn item(objectid: usize, transform: Option<Transform>, path: Option<&str>) -> Item {
+    Item {
+        objectid,
+        transform,
+        path: path.map(str::to_owned),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn model(
+    objects: Vec<Object>,
+    basematerials: Vec<BaseMaterials>,
+    items: Vec<Item>,
+) -> Model {
+    Model {
+        unit: None,
+        requiredextensions: None,
+        recommendedextensions: None,
+        metadata: vec![],
+        resources: Resources {
+            unknown_resources: vec![],
+            object: objects,
+            basematerials,
+        },
+        build: Build {
+            uuid: None,
+            item: items,
+        },
+    }
+}
@@ -5,30 +5,41 @@ use zip::write::SimpleFileOptions;
 use instant_xml::ToXml;
 
 #[cfg(feature = "io-write")]
-use crate::threemf_namespaces::ThreemfNamespace;
+use crate::{io::WriteOptions, threemf_namespaces::ThreemfNamespace};
 
 use crate::{
-    core::model::Model,
+    core::{
+        mesh::Triangle,
+        model::Model,
+        object::{Object, ObjectType},
+    },
     io::{
         XmlNamespace,
         content_types::{ContentTypes, DefaultContentTypeEnum, DefaultContentTypes},
         error::Error,
         parse_xmlns_attributes,
         relationship::{Relationship, RelationshipType, Relationships},
-        thumbnail_handle::ThumbnailHandle,
+        thumbnail_handle::{ImageFormat, ThumbnailHandle},
         utils,
     },
+    threemf_namespaces::{SUPPORTED_NAMESPACES, default_prefix_uri},
 };
 
 #[cfg(any(
     feature = "io-memory-optimized-read",
     feature = "io-speed-optimized-read"
 ))]
-use crate::io::zip_utils::XmlDeserializer;
+use crate::io::{ReadOptions, zip_utils::XmlDeserializer};
 
 use std::collections::HashMap;
 use std::io::{self, Read, Seek, Write};
 
+/// Path of the root model part in packages created by [ThreemfPackage::from_root_model].
+const DEFAULT_ROOT_MODEL_PATH: &str = "/3D/3dmodel.model";
+
+/// Key of the root model in the namespaces and attribute orders captured while reading.
+const ROOT_MODEL_KEY: &str = "root model";
+
 /// Represents a 3mf package, the nested folder structure of the parts
 /// in the 3mf package will be flattened into respective dictionaries with
 /// the key being the path of the part in the archive package.
@@ -66,6 +77,8 @@ pub struct ThreemfPackage {
     pub content_types: ContentTypes,
 
     namespaces: HashMap<String, Vec<XmlNamespace>>,
+
+    attribute_orders: HashMap<String, Vec<String>>,
 }
 
 impl ThreemfPackage {
@@ -85,7 +98,361 @@ impl ThreemfPackage {
             relationships,
             content_types,
             namespaces: HashMap::new(),
+            attribute_orders: HashMap::new(),
+        }
+    }
+
+    /// Deconstructs the package into the parts taken by [ThreemfPackage::new], in the same
+    /// order, moving them out without cloning.
+    ///
+    /// The namespaces and attribute orders captured while reading are dropped.
+    #[allow(clippy::type_complexity)]
+    pub fn into_parts(
+        self,
+    ) -> (
+        Model,
+        HashMap<String, Model>,
+        HashMap<String, ThumbnailHandle>,
+        HashMap<String, Vec<u8>>,
+        HashMap<String, Relationships>,
+        ContentTypes,
+    ) {
+        (
+            self.root,
+            self.sub_models,
+            self.thumbnails,
+            self.unknown_parts,
+            self.relationships,
+            self.content_types,
+        )
+    }
+
+    /// Creates a package with `root` as the root model at `/3D/3dmodel.model`.
+    ///
+    /// The package holds the root relationship to the model and the default content
+    /// types for models and relationships.
+    pub fn from_root_model(root: Model) -> Self {
+        Self::root_model_package(root, DEFAULT_ROOT_MODEL_PATH)
+    }
+
+    /// Same as [ThreemfPackage::from_root_model] but places the root model at `path`.
+    ///
+    /// Returns [Error::InvalidModelPath] if `path` is not absolute or doesn't end with `.model`.
+    pub fn from_root_model_at(root: Model, path: &str) -> Result<Self, Error> {
+        let file_name = path.rsplit('/').next().unwrap_or_default();
+        if !path.starts_with('/') || !file_name.ends_with(".model") || file_name == ".model" {
+            return Err(Error::InvalidModelPath(path.to_owned()));
+        }
+
+        Ok(Self::root_model_package(root, path))
+    }
+
+    fn root_model_package(root: Model, path: &str) -> Self {
+        let mut rels = HashMap::new();
+        rels.insert(
+            "_rels/.rels".to_owned(),
+            Relationships::root_with_model(path),
+        );
+        Self::new(
+            root,
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+            rels,
+            ContentTypes::standard_3mf(false),
+        )
+    }
+
+    /// Returns the path of the root model part as referenced by the root relationships,
+    /// falling back to `/3D/3dmodel.model`.
+    pub(crate) fn root_model_path(&self) -> &str {
+        self.relationships
+            .get("_rels/.rels")
+            .and_then(|rels| {
+                rels.relationships
+                    .iter()
+                    .find(|rel| rel.relationship_type == RelationshipType::Model)
+            })
+            .map_or(DEFAULT_ROOT_MODEL_PATH, |rel| rel.target.as_str())
+    }
+
+    /// Returns the edges of the dependency graph of the parts, as declared by the relationship
+    /// files of the package.
+    ///
+    /// Each edge consists of the part owning the relationship file, the targeted part and the
+    /// type of the relationship. The package itself owns `_rels/.rels` and is named `/`. Both
+    /// parts are package-absolute part names with relative targets resolved. The edges are
+    /// sorted by the owning part, the relationships of a file keep their order.
+    ///
+    /// Targets missing from the package are kept, so a tool can report broken relationships
+    /// as well as orphaned parts that no edge targets.
+    pub fn relationship_graph(&self) -> Vec<(String, String, RelationshipType)> {
+        let mut sources = self
+            .relationships
+            .iter()
+            .map(|(rels_path, rels)| (utils::relationships_source_part(rels_path), rels_path, rels))
+            .collect::<Vec<_>>();
+        sources.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        sources
+            .into_iter()
+            .flat_map(|(source, rels_path, rels)| {
+                let folder = utils::relationships_source_folder(rels_path);
+                rels.relationships.iter().map(move |rel| {
+                    (
+                        source.clone(),
+                        utils::resolve_part_name(folder, &rel.target),
+                        rel.relationship_type.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Checks the models of the package for data that is invalid according to the
+    /// 3MF Core specification but is accepted by the reader.
+    ///
+    /// Fails with [Error::EmptyObject] if an object has no geometry (see
+    /// [Object::is_empty](crate::core::object::Object::is_empty)),
+    /// with [Error::TrianglesWithoutVertices] if a mesh has triangles but no vertices,
+    /// with [Error::TriangleIndexOutOfBounds] if a triangle references a vertex that is not
+    /// part of the mesh, with [Error::DegenerateTriangleIndices] if a triangle of a mesh
+    /// references the same vertex more than once, or with [Error::BeamLattice] if a beam or
+    /// ball references a vertex that is not part of the mesh.
+    ///
+    /// The `pid` of objects and triangles has to reference base materials or another property
+    /// group of the same model, e.g. a `<m:colorgroup>` kept in
+    /// [Resources::unknown_resources](crate::core::resources::Resources::unknown_resources),
+    /// else validation fails with [Error::UnknownPropertyGroup]. Property indices into base
+    /// materials are checked against the number of materials and fail with
+    /// [Error::PropertyIndexOutOfBounds], the size of the other property groups isn't known.
+    ///
+    /// Objects of type [ObjectType::Other] may carry nonstandard content for vendor purposes
+    /// and skip the empty object and degenerate triangle checks. Indices outside the vertices
+    /// are still reported.
+    ///
+    /// For models read from a file, fails with [Error::UndeclaredExtensionPrefix] if a prefix
+    /// in `requiredextensions` or `recommendedextensions` isn't declared on the model element.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.validate_extension_prefixes()?;
+
+        let models = std::iter::once(&self.root).chain(self.sub_models.values());
+        for model in models {
+            for object in &model.resources.object {
+                Self::validate_property_references(model, object)?;
+
+                if object.objecttype != Some(ObjectType::Other) && object.is_empty() {
+                    return Err(Error::EmptyObject { id: object.id });
+                }
+
+                let Some(mesh) = &object.mesh else {
+                    continue;
+                };
+
+                if mesh.vertices.vertex.is_empty() && !mesh.triangles.triangle.is_empty() {
+                    return Err(Error::TrianglesWithoutVertices {
+                        object_id: object.id,
+                    });
+                }
+
+                let vertex_count = mesh.vertices.vertex.len();
+                for (triangle, t) in mesh.triangles.triangle.iter().enumerate() {
+                    if let Some(index) = [t.v1, t.v2, t.v3]
+                        .into_iter()
+                        .find(|&index| index >= vertex_count)
+                    {
+                        return Err(Error::TriangleIndexOutOfBounds {
+                            object_id: object.id,
+                            triangle,
+                            index,
+                        });
+                    }
+                }
+
+                if object.objecttype != Some(ObjectType::Other)
+                    && let Some(triangle) = mesh
+                        .triangles
+                        .triangle
+                        .iter()
+                        .position(Triangle::has_repeated_vertex)
+                {
+                    return Err(Error::DegenerateTriangleIndices {
+                        object_id: object.id,
+                        triangle,
+                    });
+                }
+
+                if let Some(beamlattice) = &mesh.beamlattice {
+                    beamlattice
+                        .check_vertex_indices(mesh.vertices.vertex.len())
+                        .map_err(|source| Error::BeamLattice {
+                            object_id: object.id,
+                            source,
+                        })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the properties of `object` and its triangles reference property groups
+    /// of `model`, and that indices into base materials are within the group.
+    fn validate_property_references(model: &Model, object: &Object) -> Result<(), Error> {
+        let check = |pid: usize, indices: &[usize]| {
+            if let Some(materials) = model.resources.basematerials.iter().find(|b| b.id == pid) {
+                match indices.iter().find(|&&index| index >= materials.base.len()) {
+                    Some(&index) => Err(Error::PropertyIndexOutOfBounds {
+                        object_id: object.id,
+                        pid,
+                        index,
+                    }),
+                    None => Ok(()),
+                }
+            } else if model
+                .resources
+                .unknown_resources
+                .iter()
+                .any(|resource| resource.id() == Some(pid))
+            {
+                Ok(())
+            } else {
+                Err(Error::UnknownPropertyGroup {
+                    object_id: object.id,
+                    pid,
+                })
+            }
+        };
+
+        if let Some(pid) = object.pid {
+            check(pid, object.pindex.as_slice())?;
+        }
+
+        let triangles = object.mesh.iter().flat_map(|mesh| &mesh.triangles.triangle);
+        for triangle in triangles {
+            match triangle.resolved_properties(object.pid, object.pindex) {
+                Some((pid, indices)) => check(pid, &indices)?,
+                None => {
+                    if let Some(pid) = triangle.pid {
+                        check(pid, &[])?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_extension_prefixes(&self) -> Result<(), Error> {
+        let mut sub_models: Vec<_> = self.sub_models.iter().collect();
+        sub_models.sort_by_key(|(path, _)| *path);
+        let models = std::iter::once((ROOT_MODEL_KEY, &self.root)).chain(
+            sub_models
+                .into_iter()
+                .map(|(path, model)| (path.as_str(), model)),
+        );
+
+        for (key, model) in models {
+            // models not read from a file get their namespaces declared on write
+            let Some(namespaces) = self.namespaces.get(key) else {
+                continue;
+            };
+            let extensions = [&model.requiredextensions, &model.recommendedextensions];
+            for prefix in extensions
+                .into_iter()
+                .flatten()
+                .flat_map(|extensions| extensions.split_whitespace())
+            {
+                if !namespaces
+                    .iter()
+                    .any(|ns| ns.prefix.as_deref() == Some(prefix))
+                {
+                    return Err(Error::UndeclaredExtensionPrefix(prefix.to_owned()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the thumbnail of the whole package, referenced from `_rels/.rels`.
+    ///
+    /// A previous package thumbnail is replaced, its image is removed from
+    /// [thumbnails](ThreemfPackage::thumbnails) unless another relationship still references it.
+    /// A default content type is added for the extension of `path` if it is missing.
+    ///
+    /// Returns an error if the format of the thumbnail is [ImageFormat::Unknown].
+    pub fn set_package_thumbnail(
+        &mut self,
+        path: &str,
+        thumbnail: ThumbnailHandle,
+    ) -> Result<(), Error> {
+        let content_type = match thumbnail.format {
+            ImageFormat::Png => DefaultContentTypeEnum::ImagePng,
+            ImageFormat::Jpeg => DefaultContentTypeEnum::ImageJPEG,
+            ImageFormat::Unknown => {
+                return Err(Error::ThumbnailError(format!(
+                    "Unknown image format of thumbnail {path}"
+                )));
+            }
+        };
+        let path = if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/{path}")
+        };
+
+        let rels = self
+            .relationships
+            .entry("_rels/.rels".to_owned())
+            .or_insert(Relationships {
+                relationships: vec![],
+            });
+        let previous = rels
+            .relationships
+            .iter()
+            .position(|r| r.relationship_type == RelationshipType::Thumbnail)
+            .map(|index| rels.relationships.remove(index));
+
+        let id = (0..)
+            .map(|n| format!("rel{n}"))
+            .find(|id| !rels.relationships.iter().any(|r| r.id == *id))
+            .expect("There is always an unused relationship id");
+        rels.relationships.push(Relationship {
+            id,
+            target: path.clone(),
+            relationship_type: RelationshipType::Thumbnail,
+        });
+
+        if let Some(previous) = previous {
+            let referenced = self
+                .relationships
+                .values()
+                .flat_map(|rels| &rels.relationships)
+                .any(|r| r.target == previous.target);
+            if !referenced {
+                self.thumbnails.remove(&previous.target);
+            }
+        }
+
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+        if !self
+            .content_types
+            .defaults
+            .iter()
+            .any(|d| d.extension.eq_ignore_ascii_case(&extension))
+        {
+            self.content_types.defaults.push(DefaultContentTypes {
+                extension,
+                content_type,
+            });
         }
+
+        self.thumbnails.insert(path, thumbnail);
+        Ok(())
     }
 
     pub(crate) fn new_with_namespaces_map(
@@ -96,6 +463,7 @@ impl ThreemfPackage {
         relationships: HashMap<String, Relationships>,
         content_types: ContentTypes,
         namespaces: HashMap<String, Vec<XmlNamespace>>,
+        attribute_orders: HashMap<String, Vec<String>>,
     ) -> Self {
         Self {
             root,
@@ -105,6 +473,7 @@ impl ThreemfPackage {
             relationships,
             content_types,
             namespaces,
+            attribute_orders,
         }
     }
 }
@@ -115,43 +484,216 @@ impl ThreemfPackage {
     /// Expects a well formed [ThreemfPackage] object to write the package.
     /// A well formed packaged requires atleast 1 root model and 1 relationship file along with the content types.
     pub fn write<W: Write + Seek>(&self, threemf_archive: W) -> Result<(), Error> {
+        self.write_with_options(threemf_archive, &WriteOptions::default())
+    }
+
+    /// Same as [ThreemfPackage::write] but adjusts the models according to the given
+    /// [WriteOptions] before they are serialized and compresses the parts as configured.
+    pub fn write_with_options<W: Write + Seek>(
+        &self,
+        threemf_archive: W,
+        options: &WriteOptions,
+    ) -> Result<(), Error> {
+        let mut zip = ZipWriter::new(threemf_archive);
+        let file_options = options.file_options();
+
+        self.write_parts(options, |filename, bytes| {
+            Self::archive_write_bytes(&mut zip, filename, bytes, file_options)
+        })?;
+
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Writes the parts of the package as files under `dir` instead of into an archive,
+    /// which makes them easy to inspect and diff individually.
+    ///
+    /// The files follow the directory structure of the archive written by
+    /// [ThreemfPackage::write], including `[Content_Types].xml` and the `_rels` folders.
+    /// Missing directories are created and existing files are overwritten.
+    pub fn write_to_dir(&self, dir: &std::path::Path) -> Result<(), Error> {
+        use std::path::{Component, Path};
+
+        self.write_parts(&WriteOptions::default(), |filename, bytes| {
+            // same as extracting an archive, parts must stay inside `dir`
+            let part_path = Path::new(filename);
+            if !part_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(Error::WriteError(format!(
+                    "Part /{filename} can't be written to a directory"
+                )));
+            }
+
+            let file_path = dir.join(part_path);
+            file_path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&file_path, bytes))
+                .map_err(|err| Error::from(err).writing_part(filename))
+        })
+    }
+
+    /// Writes the package into memory and returns the bytes of the archive, e.g. to upload it
+    /// or embed it in tests. See [ThreemfPackage::write].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        self.to_bytes_with_options(&WriteOptions::default())
+    }
+
+    /// Same as [ThreemfPackage::to_bytes] but writes the package according to the given
+    /// [WriteOptions], see [ThreemfPackage::write_with_options].
+    pub fn to_bytes_with_options(&self, options: &WriteOptions) -> Result<Vec<u8>, Error> {
+        let mut archive = io::Cursor::new(Vec::<u8>::new());
+        self.write_with_options(&mut archive, options)?;
+        Ok(archive.into_inner())
+    }
+
+    /// Writes a 3mf package whose parts are produced one at a time by `emit_parts`, so the
+    /// whole package never has to be held in memory.
+    ///
+    /// The content types and relationship files are written first, so they must be supplied
+    /// up front and already describe every part the closure will emit. Each part emitted
+    /// through the [PartEmitter] has to be the target of a relationship of the matching type
+    /// and can only be emitted once. Writing fails if a relationship target was not emitted
+    /// by the time the closure returns.
+    ///
+    /// Unlike [ThreemfPackage::write], the object thumbnails of the models are not checked
+    /// against the emitted thumbnails.
+    pub fn write_streaming<W, F>(
+        threemf_archive: W,
+        content_types: &ContentTypes,
+        relationships: &HashMap<String, Relationships>,
+        emit_parts: F,
+    ) -> Result<(), Error>
+    where
+        W: Write + Seek,
+        F: FnOnce(&mut PartEmitter<'_, W>) -> Result<(), Error>,
+    {
+        Self::write_streaming_with_options(
+            threemf_archive,
+            content_types,
+            relationships,
+            &WriteOptions::default(),
+            emit_parts,
+        )
+    }
+
+    /// Same as [ThreemfPackage::write_streaming] but adjusts the emitted models and
+    /// compresses all parts according to the given [WriteOptions], see
+    /// [ThreemfPackage::write_with_options]. The attribute order of the models is never
+    /// preserved.
+    pub fn write_streaming_with_options<W, F>(
+        threemf_archive: W,
+        content_types: &ContentTypes,
+        relationships: &HashMap<String, Relationships>,
+        options: &WriteOptions,
+        emit_parts: F,
+    ) -> Result<(), Error>
+    where
+        W: Write + Seek,
+        F: FnOnce(&mut PartEmitter<'_, W>) -> Result<(), Error>,
+    {
         let mut zip = ZipWriter::new(threemf_archive);
+        let file_options = options.file_options();
 
         Self::archive_write_xml_with_header(
             &mut zip,
             "[Content_Types].xml",
-            &self.content_types,
+            content_types,
             None,
+            None,
+            file_options,
         )?;
 
+        let mut pending = HashMap::new();
+        for (path, relationships) in relationships {
+            Self::archive_write_xml_with_header(
+                &mut zip,
+                utils::try_strip_leading_slash(path),
+                relationships,
+                None,
+                None,
+                file_options,
+            )?;
+
+            for relationship in &relationships.relationships {
+                pending.insert(
+                    utils::try_strip_leading_slash(&relationship.target),
+                    &relationship.relationship_type,
+                );
+            }
+        }
+
+        let mut emitter = PartEmitter {
+            zip: &mut zip,
+            options,
+            file_options,
+            pending,
+        };
+        emit_parts(&mut emitter)?;
+
+        if let Some(target) = emitter.pending.keys().min() {
+            return Err(Error::WriteError(format!(
+                "No part written for relationship target /{target}"
+            )));
+        }
+
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Serializes every part of the package according to `options` and passes it to
+    /// `write_part` with its path in the archive.
+    fn write_parts(
+        &self,
+        options: &WriteOptions,
+        mut write_part: impl FnMut(&str, &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.validate_object_thumbnails()?;
+
+        let content_types =
+            Self::xml_with_header("[Content_Types].xml", &self.content_types, None, None)?;
+        write_part("[Content_Types].xml", content_types.as_bytes())?;
+
         for (path, relationships) in &self.relationships {
-            Self::archive_write_xml_with_header(&mut zip, path, &relationships, None)?;
+            // Relationship files of the parts are keyed with a leading slash
+            // when read from a package, but zip entries are relative.
+            let rels_filename = utils::try_strip_leading_slash(path);
+            let rels = Self::xml_with_header(rels_filename, &relationships, None, None)?;
+            write_part(rels_filename, rels.as_bytes())?;
 
             for relationship in &relationships.relationships {
                 let filename = utils::try_strip_leading_slash(&relationship.target);
                 match relationship.relationship_type {
                     RelationshipType::Model => {
-                        let model = if *path == *"_rels/.rels" {
-                            &self.root
+                        let (model, key) = if rels_filename == "_rels/.rels" {
+                            (&self.root, ROOT_MODEL_KEY)
                         } else if let Some(model) = self.sub_models.get(&relationship.target) {
-                            model
+                            (model, relationship.target.as_str())
                         } else {
                             return Err(Error::WriteError(format!(
                                 "No model found for relationship target {}",
                                 relationship.target
                             )));
                         };
-                        Self::archive_write_xml_with_header(
-                            &mut zip,
+                        let attribute_order = self
+                            .attribute_orders
+                            .get(key)
+                            .filter(|_| options.preserve_attribute_order)
+                            .map(Vec::as_slice);
+                        let model = options.apply(model);
+                        let xml = Self::xml_with_header(
                             filename,
-                            model,
+                            model.as_ref(),
                             Some(model.used_namespaces()),
+                            attribute_order,
                         )?;
+                        write_part(filename, xml.as_bytes())?;
                     }
                     RelationshipType::Thumbnail => {
                         if let Some(image) = self.thumbnails.get(&relationship.target) {
-                            zip.start_file(filename, SimpleFileOptions::default())?;
-                            zip.write_all(&image.data)?;
+                            write_part(filename, &image.data)?;
                         } else {
                             return Err(Error::WriteError(format!(
                                 "No thumbnail image found for relationship target {}",
@@ -161,8 +703,7 @@ impl ThreemfPackage {
                     }
                     RelationshipType::Unknown(_) => {
                         if let Some(bytes) = self.unknown_parts.get(&relationship.target) {
-                            zip.start_file(filename, SimpleFileOptions::default())?;
-                            zip.write_all(bytes)?;
+                            write_part(filename, bytes)?;
                         } else {
                             return Err(Error::WriteError(format!(
                                 "No data found for relationship target {}",
@@ -173,34 +714,145 @@ impl ThreemfPackage {
                 }
             }
         }
-        zip.finish()?;
+
+        Ok(())
+    }
+
+    fn validate_object_thumbnails(&self) -> Result<(), Error> {
+        let models = std::iter::once(&self.root).chain(self.sub_models.values());
+        for object in models.flat_map(|model| &model.resources.object) {
+            let Some(path) = &object.thumbnail else {
+                continue;
+            };
+
+            let path_in_package = utils::try_strip_leading_slash(path);
+            let exists = self
+                .thumbnails
+                .keys()
+                .any(|key| utils::try_strip_leading_slash(key) == path_in_package);
+            if !exists {
+                return Err(Error::MissingThumbnail {
+                    object_id: object.id,
+                    path: path.clone(),
+                });
+            }
+        }
+
         Ok(())
     }
 
+    /// Writes this package to memory and compares every part against the parts in `original_bytes`.
+    /// The comparison is keyed by the path of the part in the archive and uses the uncompressed size
+    /// and CRC-32 of each part, so differences in compression are not reported.
+    /// An empty result means every part came out identical to the original archive.
+    pub fn verify_roundtrip(&self, original_bytes: &[u8]) -> Result<Vec<PartDiff>, Error> {
+        let original = Self::archive_part_digests(io::Cursor::new(original_bytes))?;
+
+        let mut written_bytes = io::Cursor::new(Vec::<u8>::new());
+        self.write(&mut written_bytes)?;
+        let mut written = Self::archive_part_digests(written_bytes)?;
+
+        let mut diffs = Vec::new();
+        for (path, original_digest) in original {
+            match written.remove(&path) {
+                Some(written_digest) if written_digest == original_digest => {}
+                Some(written_digest) => diffs.push(PartDiff::Changed {
+                    path,
+                    original: original_digest,
+                    written: written_digest,
+                }),
+                None => diffs.push(PartDiff::Missing { path }),
+            }
+        }
+        diffs.extend(written.into_keys().map(|path| PartDiff::Added { path }));
+        diffs.sort_by(|a, b| a.path().cmp(b.path()));
+
+        Ok(diffs)
+    }
+
+    fn archive_part_digests<R: Read + Seek>(
+        reader: R,
+    ) -> Result<HashMap<String, PartDigest>, Error> {
+        let mut zip = zip::ZipArchive::new(reader)?;
+        let mut digests = HashMap::new();
+        for i in 0..zip.len() {
+            let file = zip.by_index_raw(i)?;
+            if file.is_dir() {
+                continue;
+            }
+            digests.insert(
+                file.name().to_owned(),
+                PartDigest {
+                    size: file.size(),
+                    crc32: file.crc32(),
+                },
+            );
+        }
+        Ok(digests)
+    }
+
     fn archive_write_xml_with_header<W: Write + Seek, T: ToXml + ?Sized>(
         archive: &mut ZipWriter<W>,
         filename: &str,
         content: &T,
         optional_namespaces_to_keep: Option<Vec<ThreemfNamespace>>,
+        attribute_order: Option<&[String]>,
+        file_options: SimpleFileOptions,
     ) -> Result<(), Error> {
+        let xml = Self::xml_with_header(
+            filename,
+            content,
+            optional_namespaces_to_keep,
+            attribute_order,
+        )?;
+        Self::archive_write_bytes(archive, filename, xml.as_bytes(), file_options)
+    }
+
+    /// Serializes the part `filename` to XML with the XML declaration in front.
+    fn xml_with_header<T: ToXml + ?Sized>(
+        filename: &str,
+        content: &T,
+        optional_namespaces_to_keep: Option<Vec<ThreemfNamespace>>,
+        attribute_order: Option<&[String]>,
+    ) -> Result<String, Error> {
         use instant_xml::to_string;
 
         const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#;
 
-        let mut content_string = to_string(&content)?;
+        let mut content_string =
+            to_string(&content).map_err(|err| Error::from(err).writing_part(filename))?;
 
         if let Some(namespaces) = optional_namespaces_to_keep {
-            Self::filter_unused_namespaces(&mut content_string, &namespaces);
+            Self::filter_unused_namespaces(&mut content_string, &namespaces, attribute_order);
         }
 
         content_string.insert_str(0, XML_HEADER);
 
-        archive.start_file(filename, SimpleFileOptions::default())?;
-        archive.write_all(content_string.as_bytes())?;
-        Ok(())
+        Ok(content_string)
+    }
+
+    /// Writes `bytes` to the part `filename` of the archive.
+    fn archive_write_bytes<W: Write + Seek>(
+        archive: &mut ZipWriter<W>,
+        filename: &str,
+        bytes: &[u8],
+        file_options: SimpleFileOptions,
+    ) -> Result<(), Error> {
+        archive
+            .start_file(filename, file_options)
+            .map_err(Error::from)
+            .and_then(|()| Ok(archive.write_all(bytes)?))
+            .map_err(|err| err.writing_part(filename))
     }
 
-    fn filter_unused_namespaces(xml: &mut String, keep_namespaces: &[ThreemfNamespace]) {
+    /// Removes the namespace declarations not in `keep_namespaces` from the `<model>` tag.
+    /// If `attribute_order` is given, the attributes are sorted by the position of their
+    /// name in it, attributes not listed keep their relative order at the end.
+    fn filter_unused_namespaces(
+        xml: &mut String,
+        keep_namespaces: &[ThreemfNamespace],
+        attribute_order: Option<&[String]>,
+    ) {
         let keep_uris: std::collections::HashSet<_> =
             keep_namespaces.iter().map(|ns| ns.uri()).collect();
 
@@ -238,33 +890,34 @@ impl ThreemfPackage {
                 }
             }
 
-            // Build new tag
-            let mut new_tag = String::from("<model");
+            // Kept xmlns attributes first, followed by the non-xmlns attributes
+            let mut attrs = xmlns_attrs
+                .iter()
+                .filter(|ns| keep_uris.contains(ns.uri.as_str()))
+                .map(|ns| match &ns.prefix {
+                    Some(prefix) => format!(r#"xmlns:{prefix}="{}""#, ns.uri),
+                    None => format!(r#"xmlns="{}""#, ns.uri),
+                })
+                .chain(
+                    all_attrs
+                        .into_iter()
+                        .filter(|attr| !attr.starts_with("xmlns")),
+                )
+                .collect::<Vec<_>>();
 
-            // Add kept xmlns attributes
-            for ns in &xmlns_attrs {
-                if keep_uris.contains(ns.uri.as_str()) {
-                    new_tag.push(' ');
-                    let attr_name = if let Some(prefix) = &ns.prefix {
-                        prefix
-                    } else {
-                        "xmlns"
-                    };
-                    new_tag.push_str(attr_name);
-                    new_tag.push_str("=\"");
-                    new_tag.push_str(&ns.uri);
-                    new_tag.push('"');
-                }
+            if let Some(order) = attribute_order {
+                attrs.sort_by_key(|attr| {
+                    let name = attr.split('=').next().unwrap_or_default();
+                    order.iter().position(|n| n == name).unwrap_or(order.len())
+                });
             }
 
-            // Add non-xmlns attributes
-            for attr in all_attrs {
-                if !attr.starts_with("xmlns") {
-                    new_tag.push(' ');
-                    new_tag.push_str(&attr);
-                }
+            // Build new tag
+            let mut new_tag = String::from("<model");
+            for attr in attrs {
+                new_tag.push(' ');
+                new_tag.push_str(&attr);
             }
-
             new_tag.push('>');
 
             // Replace in original XML
@@ -283,7 +936,28 @@ impl ThreemfPackage {
         reader: R,
         process_sub_models: bool,
     ) -> Result<Self, Error> {
-        Self::from_reader(reader, process_sub_models, XmlDeserializer::MemoryOptimized)
+        Self::from_reader(
+            reader,
+            process_sub_models,
+            XmlDeserializer::MemoryOptimized,
+            &ReadOptions::default(),
+        )
+    }
+
+    /// Same as [ThreemfPackage::from_reader_with_memory_optimized_deserializer] but validates and
+    /// normalizes the models according to the given [ReadOptions].
+    #[cfg(feature = "io-memory-optimized-read")]
+    pub fn from_reader_with_memory_optimized_deserializer_and_options<R: Read + io::Seek>(
+        reader: R,
+        process_sub_models: bool,
+        options: &ReadOptions,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            reader,
+            process_sub_models,
+            XmlDeserializer::MemoryOptimized,
+            options,
+        )
     }
 
     #[cfg(feature = "io-speed-optimized-read")]
@@ -291,7 +965,28 @@ impl ThreemfPackage {
         reader: R,
         process_sub_models: bool,
     ) -> Result<Self, Error> {
-        Self::from_reader(reader, process_sub_models, XmlDeserializer::SpeedOptimized)
+        Self::from_reader(
+            reader,
+            process_sub_models,
+            XmlDeserializer::SpeedOptimized,
+            &ReadOptions::default(),
+        )
+    }
+
+    /// Same as [ThreemfPackage::from_reader_with_speed_optimized_deserializer] but validates and
+    /// normalizes the models according to the given [ReadOptions].
+    #[cfg(feature = "io-speed-optimized-read")]
+    pub fn from_reader_with_speed_optimized_deserializer_and_options<R: Read + io::Seek>(
+        reader: R,
+        process_sub_models: bool,
+        options: &ReadOptions,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            reader,
+            process_sub_models,
+            XmlDeserializer::SpeedOptimized,
+            options,
+        )
     }
 
     /// Reads a 3mf package from a type [Read] + [io::Seek].
@@ -302,6 +997,7 @@ impl ThreemfPackage {
         reader: R,
         process_sub_models: bool,
         deserializer: XmlDeserializer,
+        options: &ReadOptions,
     ) -> Result<Self, Error> {
         use crate::io::zip_utils;
 
@@ -322,11 +1018,12 @@ impl ThreemfPackage {
 
         let mut relationships = HashMap::<String, Relationships>::new();
 
-        let root_rels: Relationships = zip_utils::relationships_from_zip_by_name(
+        let mut root_rels: Relationships = zip_utils::relationships_from_zip_by_name(
             &mut zip,
             &root_rels_filename,
             &deserializer,
         )?;
+        options.apply_to_relationships(&mut root_rels, &root_rels_filename);
 
         let root_model_rel = root_rels
             .relationships
@@ -348,26 +1045,130 @@ impl ThreemfPackage {
             let rel_files =
                 zip_utils::discover_relationship_files(&mut zip, rels_ext, &root_rels_filename)?;
             for rel_file_path in rel_files {
-                let rels = zip_utils::relationships_from_zip_by_name(
+                let mut rels = zip_utils::relationships_from_zip_by_name(
                     &mut zip,
                     &rel_file_path[1..],
                     &deserializer,
                 )?;
+                options.apply_to_relationships(&mut rels, &rel_file_path);
                 relationships.insert(rel_file_path, rels);
             }
         }
 
         let mut processor = processor::ThreemfPackageProcessor::new(content_types, relationships);
 
-        processor.process_relationships(&mut zip, &deserializer, &root_model_path)?;
+        processor.process_relationships(&mut zip, &deserializer, &root_model_path, options)?;
+
+        processor.into_threemf_package()
+    }
+
+    /// Reads only the content types and the relationship files of a 3mf package, see [OpcView].
+    #[cfg(feature = "io-memory-optimized-read")]
+    pub fn read_opc_only_with_memory_optimized_deserializer<R: Read + io::Seek>(
+        reader: R,
+    ) -> Result<OpcView, Error> {
+        Self::read_opc_only(reader, XmlDeserializer::MemoryOptimized)
+    }
+
+    /// Reads only the content types and the relationship files of a 3mf package, see [OpcView].
+    #[cfg(feature = "io-speed-optimized-read")]
+    pub fn read_opc_only_with_speed_optimized_deserializer<R: Read + io::Seek>(
+        reader: R,
+    ) -> Result<OpcView, Error> {
+        Self::read_opc_only(reader, XmlDeserializer::SpeedOptimized)
+    }
+
+    /// Reads the content types and every relationship file of the package.
+    /// No model, thumbnail or other part is read, so a package without a root model
+    /// relationship is not an error here.
+    fn read_opc_only<R: Read + io::Seek>(
+        reader: R,
+        deserializer: XmlDeserializer,
+    ) -> Result<OpcView, Error> {
+        use crate::io::zip_utils;
+
+        let (mut zip, content_types, _, root_rels_filename) =
+            zip_utils::setup_archive_and_content_types(reader, deserializer)?;
+        let rels_ext = zip_utils::determine_relationships_extension(&content_types);
+
+        let mut relationships = HashMap::<String, Relationships>::new();
+        let root_rels = zip_utils::relationships_from_zip_by_name(
+            &mut zip,
+            &root_rels_filename,
+            &deserializer,
+        )?;
+        relationships.insert(root_rels_filename.clone(), root_rels);
+
+        let rel_files =
+            zip_utils::discover_relationship_files(&mut zip, &rels_ext, &root_rels_filename)?;
+        for rel_file_path in rel_files {
+            let rels = zip_utils::relationships_from_zip_by_name(
+                &mut zip,
+                &rel_file_path[1..],
+                &deserializer,
+            )?;
+            relationships.insert(rel_file_path, rels);
+        }
+
+        Ok(OpcView {
+            content_types,
+            relationships,
+        })
+    }
+
+    /// Returns an iterator over every part of the package together with its path.
+    ///
+    /// The parts are returned in a stable order: the content types, the relationship files,
+    /// the root model, the sub models, the thumbnails and finally the unknown parts,
+    /// each group sorted by path.
+    pub fn iter_parts(&self) -> impl Iterator<Item = Part<'_>> {
+        fn sorted<'a, T>(map: &'a HashMap<String, T>) -> Vec<(&'a str, &'a T)> {
+            let mut entries = map.iter().map(|(k, v)| (k.as_str(), v)).collect::<Vec<_>>();
+            entries.sort_by_key(|(path, _)| *path);
+            entries
+        }
+
+        let root_path = self.root_model_path();
 
-        Ok(processor.into_threemf_package())
+        let content_types = std::iter::once(Part::ContentTypes {
+            path: "[Content_Types].xml",
+            content_types: &self.content_types,
+        });
+        let relationships = sorted(&self.relationships)
+            .into_iter()
+            .map(|(path, relationships)| Part::Relationships {
+                path,
+                relationships,
+            });
+        let root = std::iter::once(Part::Model {
+            path: root_path,
+            model: &self.root,
+        });
+        let sub_models = sorted(&self.sub_models)
+            .into_iter()
+            .map(|(path, model)| Part::Model { path, model });
+        let thumbnails = sorted(&self.thumbnails)
+            .into_iter()
+            .map(|(path, thumbnail)| Part::Thumbnail { path, thumbnail });
+        let unknown_parts = sorted(&self.unknown_parts)
+            .into_iter()
+            .map(|(path, bytes)| Part::Unknown {
+                path,
+                bytes: bytes.as_slice(),
+            });
+
+        content_types
+            .chain(relationships)
+            .chain(root)
+            .chain(sub_models)
+            .chain(thumbnails)
+            .chain(unknown_parts)
     }
 
     //only exists in the loading flow and not on the writing flow
     //if a path is not set then its the root model
     pub fn get_namespaces_on_model(&self, model_path: Option<&str>) -> Option<Vec<XmlNamespace>> {
-        let path = model_path.unwrap_or("root model");
+        let path = model_path.unwrap_or(ROOT_MODEL_KEY);
 
         if self.namespaces.contains_key(path) {
             let namespaces = self.namespaces.get(path);
@@ -376,44 +1177,270 @@ impl ThreemfPackage {
             None
         }
     }
-}
 
-impl PartialEq for ThreemfPackage {
-    fn eq(&self, other: &Self) -> bool {
-        self.root == other.root
-            && self.sub_models == other.sub_models
-            && self.thumbnails == other.thumbnails
-            && self.unknown_parts == other.unknown_parts
-            && self.relationships == other.relationships
-            && self.content_types == other.content_types
-        //skip namespaces comparison altogether
+    /// Returns the names of the attributes of the `<model>` element in the order they were
+    /// read, including the namespace declarations, or `None` if the model was not read from
+    /// a file. If a path is not set then it's the root model.
+    ///
+    /// The order is used on write if [WriteOptions::preserve_attribute_order] is set.
+    pub fn get_attribute_order_on_model(&self, model_path: Option<&str>) -> Option<&[String]> {
+        self.attribute_orders
+            .get(model_path.unwrap_or(ROOT_MODEL_KEY))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the namespace uris of the extensions required by the models of the package
+    /// that this library doesn't model, see [SUPPORTED_NAMESPACES]. The content of such an
+    /// extension is dropped or only kept as raw XML, so the package can't be processed
+    /// completely.
+    ///
+    /// The prefixes in `requiredextensions` are resolved with the namespaces declared on the
+    /// model when it was read, or with the default prefixes of this library for models that
+    /// were not read from a file. A prefix that can't be resolved is returned as it is.
+    /// Every extension is listed once, those of the root model first followed by those of
+    /// the sub-models sorted by path.
+    pub fn unsupported_required_extensions(&self) -> Vec<String> {
+        let mut sub_models: Vec<_> = self.sub_models.iter().collect();
+        sub_models.sort_by_key(|(path, _)| *path);
+        let models = std::iter::once((ROOT_MODEL_KEY, &self.root)).chain(
+            sub_models
+                .into_iter()
+                .map(|(path, model)| (path.as_str(), model)),
+        );
+
+        let mut unsupported: Vec<String> = vec![];
+        for (key, model) in models {
+            let Some(required) = &model.requiredextensions else {
+                continue;
+            };
+            let namespaces = self.namespaces.get(key);
+            for prefix in required.split_whitespace() {
+                let uri = namespaces
+                    .and_then(|namespaces| {
+                        namespaces
+                            .iter()
+                            .find(|ns| ns.prefix.as_deref() == Some(prefix))
+                    })
+                    .map(|ns| ns.uri.as_str())
+                    .or_else(|| default_prefix_uri(prefix))
+                    .unwrap_or(prefix);
+                if !SUPPORTED_NAMESPACES.contains(&uri) && !unsupported.iter().any(|u| u == uri) {
+                    unsupported.push(uri.to_owned());
+                }
+            }
+        }
+
+        unsupported
     }
 }
 
+/// The Open Packaging Conventions (OPC) wiring of a 3mf package, i.e. its content types and
+/// relationship files, as returned by
+/// [ThreemfPackage::read_opc_only_with_memory_optimized_deserializer] and
+/// [ThreemfPackage::read_opc_only_with_speed_optimized_deserializer].
+///
+/// Reading it skips every model, thumbnail and other part of the package, which makes it
+/// a fast way to check the relationships and content types of many packages.
 #[cfg(any(
     feature = "io-memory-optimized-read",
     feature = "io-speed-optimized-read"
 ))]
-mod processor {
-    use zip::ZipArchive;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcView {
+    /// The content types of the package.
+    pub content_types: ContentTypes,
 
-    use crate::{
-        core::model::Model,
-        io::{
-            ThreemfPackage, XmlNamespace,
-            content_types::ContentTypes,
-            error::Error,
-            relationship::{RelationshipType, Relationships},
-            thumbnail_handle::{ImageFormat, ThumbnailHandle},
-            utils,
-            zip_utils::XmlDeserializer,
-        },
-    };
+    /// The relationship files of the package keyed by their path, see
+    /// [ThreemfPackage::relationships].
+    pub relationships: HashMap<String, Relationships>,
+}
+
+/// A single part of a [ThreemfPackage] as returned by [ThreemfPackage::iter_parts].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Part<'a> {
+    /// The content types of the package.
+    ContentTypes {
+        path: &'a str,
+        content_types: &'a ContentTypes,
+    },
+
+    /// A relationship file.
+    Relationships {
+        path: &'a str,
+        relationships: &'a Relationships,
+    },
+
+    /// The root model or a sub model.
+    Model { path: &'a str, model: &'a Model },
+
+    /// A thumbnail image.
+    Thumbnail {
+        path: &'a str,
+        thumbnail: &'a ThumbnailHandle,
+    },
+
+    /// A part with a relationship type not known to this library.
+    Unknown { path: &'a str, bytes: &'a [u8] },
+}
+
+impl Part<'_> {
+    /// Path of the part in the package.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::ContentTypes { path, .. }
+            | Self::Relationships { path, .. }
+            | Self::Model { path, .. }
+            | Self::Thumbnail { path, .. }
+            | Self::Unknown { path, .. } => path,
+        }
+    }
+}
+
+/// Size and checksum of a single part in a 3mf archive.
+#[cfg(feature = "io-write")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartDigest {
+    /// Uncompressed size of the part in bytes.
+    pub size: u64,
+    /// CRC-32 of the uncompressed part.
+    pub crc32: u32,
+}
+
+/// A difference in a single part reported by [ThreemfPackage::verify_roundtrip].
+#[cfg(feature = "io-write")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartDiff {
+    /// The part exists in the original archive but was not written.
+    Missing { path: String },
+
+    /// The part was written but does not exist in the original archive.
+    Added { path: String },
+
+    /// The part exists in both archives but the content differs.
+    Changed {
+        path: String,
+        original: PartDigest,
+        written: PartDigest,
+    },
+}
+
+#[cfg(feature = "io-write")]
+impl PartDiff {
+    /// Path of the part in the archive.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Missing { path } | Self::Added { path } | Self::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// Writes the parts produced inside [ThreemfPackage::write_streaming] into the archive.
+#[cfg(feature = "io-write")]
+pub struct PartEmitter<'a, W: Write + Seek> {
+    zip: &'a mut ZipWriter<W>,
+    options: &'a WriteOptions,
+    file_options: SimpleFileOptions,
+
+    /// Relationship type of every relationship target not emitted yet,
+    /// keyed by the path of the target in the archive.
+    pending: HashMap<&'a str, &'a RelationshipType>,
+}
+
+#[cfg(feature = "io-write")]
+impl<W: Write + Seek> PartEmitter<'_, W> {
+    /// Writes the root model or a sub model to `path`.
+    pub fn model(&mut self, path: &str, model: &Model) -> Result<(), Error> {
+        let filename = self.take_pending(path, "model", |t| *t == RelationshipType::Model)?;
+        let model = self.options.apply(model);
+        ThreemfPackage::archive_write_xml_with_header(
+            self.zip,
+            filename,
+            model.as_ref(),
+            Some(model.used_namespaces()),
+            None,
+            self.file_options,
+        )
+    }
+
+    /// Writes a thumbnail image to `path`.
+    pub fn thumbnail(&mut self, path: &str, thumbnail: &ThumbnailHandle) -> Result<(), Error> {
+        let filename =
+            self.take_pending(path, "thumbnail", |t| *t == RelationshipType::Thumbnail)?;
+        ThreemfPackage::archive_write_bytes(self.zip, filename, &thumbnail.data, self.file_options)
+    }
+
+    /// Writes the bytes of a part with a relationship type not known to this library to `path`.
+    pub fn unknown_part(&mut self, path: &str, bytes: &[u8]) -> Result<(), Error> {
+        let filename = self.take_pending(path, "unknown", |t| {
+            matches!(t, RelationshipType::Unknown(_))
+        })?;
+        ThreemfPackage::archive_write_bytes(self.zip, filename, bytes, self.file_options)
+    }
+
+    /// Returns the path of the part in the archive if it is a pending relationship target
+    /// of the expected type and marks it as emitted.
+    fn take_pending<'p>(
+        &mut self,
+        path: &'p str,
+        kind: &str,
+        is_expected: impl Fn(&RelationshipType) -> bool,
+    ) -> Result<&'p str, Error> {
+        let filename = utils::try_strip_leading_slash(path);
+        match self.pending.get(filename) {
+            Some(relationship_type) if is_expected(relationship_type) => {
+                self.pending.remove(filename);
+                Ok(filename)
+            }
+            Some(_) => Err(Error::WriteError(format!(
+                "Relationship target {path} is not a {kind} part"
+            ))),
+            None => Err(Error::WriteError(format!(
+                "{path} is not a relationship target or was already written"
+            ))),
+        }
+    }
+}
+
+impl PartialEq for ThreemfPackage {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+            && self.sub_models == other.sub_models
+            && self.thumbnails == other.thumbnails
+            && self.unknown_parts == other.unknown_parts
+            && self.relationships == other.relationships
+            && self.content_types == other.content_types
+        //skip namespaces and attribute order comparison altogether
+    }
+}
+
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+mod processor {
+    use zip::ZipArchive;
+
+    use crate::{
+        core::model::Model,
+        io::{
+            ReadOptions, ThreemfPackage, XmlNamespace,
+            content_types::ContentTypes,
+            error::Error,
+            read_options::ComplexityTotals,
+            relationship::{Relationship, RelationshipType, Relationships},
+            thumbnail_handle::{ImageFormat, ThumbnailHandle},
+            utils,
+            zip_utils::XmlDeserializer,
+        },
+    };
 
     use std::{
         collections::HashMap,
         io::{Read, Seek},
     };
+
+    use super::ROOT_MODEL_KEY;
+
     /// Temporary processor for building ThreemfPackage
     pub(crate) struct ThreemfPackageProcessor {
         root: Option<Model>,
@@ -423,6 +1450,8 @@ mod processor {
         relationships: HashMap<String, Relationships>,
         content_types: ContentTypes,
         namespaces_map: HashMap<String, Vec<XmlNamespace>>,
+        attribute_orders: HashMap<String, Vec<String>>,
+        complexity: ComplexityTotals,
     }
 
     impl ThreemfPackageProcessor {
@@ -438,19 +1467,26 @@ mod processor {
                 relationships,
                 content_types,
                 namespaces_map: HashMap::new(),
+                attribute_orders: HashMap::new(),
+                complexity: ComplexityTotals::default(),
             }
         }
 
-        pub(crate) fn into_threemf_package(self) -> ThreemfPackage {
-            ThreemfPackage::new_with_namespaces_map(
-                self.root.expect("Root model should be set"),
+        pub(crate) fn into_threemf_package(self) -> Result<ThreemfPackage, Error> {
+            let root = self
+                .root
+                .ok_or_else(|| Error::ReadError("Root model not found".to_owned()))?;
+
+            Ok(ThreemfPackage::new_with_namespaces_map(
+                root,
                 self.sub_models,
                 self.thumbnails,
                 self.unknown_parts,
                 self.relationships,
                 self.content_types,
                 self.namespaces_map,
-            )
+                self.attribute_orders,
+            ))
         }
 
         pub(crate) fn process_relationships<R: Read + Seek>(
@@ -458,69 +1494,83 @@ mod processor {
             zip: &mut ZipArchive<R>,
             deserializer: &XmlDeserializer,
             root_model_path: &str,
+            options: &ReadOptions,
         ) -> Result<(), Error> {
-            for rels in self.relationships.values() {
-                for rel in &rels.relationships {
-                    let name = utils::try_strip_leading_slash(&rel.target);
-                    let zip_file = zip.by_name(name);
-
-                    match zip_file {
-                        Ok(mut file) => {
-                            if file.is_dir() {
-                                return Err(Error::ReadError(format!(
-                                    r#"Found a folder "{:?}" instead of a file"#,
-                                    file.enclosed_name()
-                                )));
-                            }
+            let relationships = self.relationships.clone();
+            for rel in relationships.values().flat_map(|rels| &rels.relationships) {
+                self.process_part(zip, deserializer, rel, root_model_path, options)
+                    .map_err(|err| err.reading_part(&rel.target))?;
+            }
+            Ok(())
+        }
 
-                            match rel.relationship_type {
-                                RelationshipType::Thumbnail => {
-                                    let mut bytes = Vec::new();
-                                    file.read_to_end(&mut bytes)?;
-
-                                    let format = {
-                                        if let Some(filepath) = file.enclosed_name()
-                                            && let Some(os_ext) = filepath.extension()
-                                            && let Some(ext) = os_ext.to_str()
-                                        {
-                                            ImageFormat::from_ext(ext)
-                                        } else {
-                                            ImageFormat::Unknown
-                                        }
-                                    };
-
-                                    let thumbnail_rep = ThumbnailHandle {
-                                        data: bytes,
-                                        format,
-                                    };
-                                    self.thumbnails
-                                        .insert(rel.target.to_string(), thumbnail_rep);
-                                }
-                                RelationshipType::Model => {
-                                    let is_root = rel.target == root_model_path;
-
-                                    let (model, namespaces) =
-                                        deserializer.deserialize_model(&mut file)?;
-                                    if is_root {
-                                        self.root = Some(model);
-                                        self.namespaces_map
-                                            .insert("root model".to_string(), namespaces);
-                                    } else {
-                                        self.sub_models.insert(rel.target.to_string(), model);
-                                        self.namespaces_map
-                                            .insert(rel.target.to_string(), namespaces);
-                                    }
-                                }
-                                RelationshipType::Unknown(_) => {
-                                    let mut bytes = Vec::new();
-                                    file.read_to_end(&mut bytes)?;
-
-                                    self.unknown_parts.insert(rel.target.to_string(), bytes);
-                                }
-                            }
+        /// Reads the part targeted by `rel` into the package.
+        fn process_part<R: Read + Seek>(
+            &mut self,
+            zip: &mut ZipArchive<R>,
+            deserializer: &XmlDeserializer,
+            rel: &Relationship,
+            root_model_path: &str,
+            options: &ReadOptions,
+        ) -> Result<(), Error> {
+            let mut file = zip.by_name(utils::try_strip_leading_slash(&rel.target))?;
+            if file.is_dir() {
+                return Err(Error::ReadError(format!(
+                    r#"Found a folder "{:?}" instead of a file"#,
+                    file.enclosed_name()
+                )));
+            }
+
+            match rel.relationship_type {
+                RelationshipType::Thumbnail => {
+                    let mut bytes = Vec::new();
+                    file.read_to_end(&mut bytes)?;
+
+                    let format = {
+                        if let Some(filepath) = file.enclosed_name()
+                            && let Some(os_ext) = filepath.extension()
+                            && let Some(ext) = os_ext.to_str()
+                        {
+                            ImageFormat::from_ext(ext)
+                        } else {
+                            ImageFormat::Unknown
                         }
-                        Err(err) => return Err(Error::Zip(err)),
-                    }
+                    };
+
+                    let thumbnail_rep = ThumbnailHandle {
+                        data: bytes,
+                        format,
+                    };
+                    self.thumbnails
+                        .insert(rel.target.to_string(), thumbnail_rep);
+                }
+                RelationshipType::Model => {
+                    let is_root = rel.target == root_model_path;
+
+                    let mut xml_string = String::new();
+                    file.read_to_string(&mut xml_string)?;
+                    options
+                        .limits
+                        .check_model(&xml_string, &mut self.complexity)?;
+
+                    let (mut model, namespaces, attribute_order) =
+                        deserializer.deserialize_model_with_attribute_order(&xml_string)?;
+                    options.apply(&mut model, &rel.target)?;
+                    let key = if is_root {
+                        self.root = Some(model);
+                        ROOT_MODEL_KEY.to_string()
+                    } else {
+                        self.sub_models.insert(rel.target.to_string(), model);
+                        rel.target.to_string()
+                    };
+                    self.namespaces_map.insert(key.clone(), namespaces);
+                    self.attribute_orders.insert(key, attribute_order);
+                }
+                RelationshipType::Unknown(_) => {
+                    let mut bytes = Vec::new();
+                    file.read_to_end(&mut bytes)?;
+
+                    self.unknown_parts.insert(rel.target.to_string(), bytes);
                 }
             }
             Ok(())
@@ -530,36 +1580,7 @@ mod processor {
 
 impl From<Model> for ThreemfPackage {
     fn from(value: Model) -> Self {
-        let mut rels = HashMap::new();
-        rels.insert(
-            "_rels/.rels".to_owned(),
-            Relationships {
-                relationships: vec![Relationship {
-                    id: "rel0".to_owned(),
-                    target: "3D/3dmodel.model".to_owned(),
-                    relationship_type: RelationshipType::Model,
-                }],
-            },
-        );
-        Self::new(
-            value,
-            HashMap::new(),
-            HashMap::new(),
-            HashMap::new(),
-            rels,
-            ContentTypes {
-                defaults: vec![
-                    DefaultContentTypes {
-                        extension: "model".to_owned(),
-                        content_type: DefaultContentTypeEnum::Model,
-                    },
-                    DefaultContentTypes {
-                        extension: "rels".to_owned(),
-                        content_type: DefaultContentTypeEnum::Relationship,
-                    },
-                ],
-            },
-        )
+        Self::from_root_model(value)
     }
 }
 
@@ -617,6 +1638,87 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    pub fn relationship_graph_test() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            true,
+        )
+        .unwrap();
+
+        let edge = |source: &str, target: &str, relationship_type| {
+            (source.to_owned(), target.to_owned(), relationship_type)
+        };
+        assert_eq!(
+            package.relationship_graph(),
+            vec![
+                edge("/", "/3D/3dmodel.model", RelationshipType::Model),
+                edge("/", "/Metadata/thumbnail.png", RelationshipType::Thumbnail),
+                edge(
+                    "/3D/3dmodel.model",
+                    "/3D/Objects/Object.model",
+                    RelationshipType::Model
+                ),
+                edge(
+                    "/3D/3dmodel.model",
+                    "/3D/Objects/Object(2).model",
+                    RelationshipType::Model
+                ),
+                edge(
+                    "/3D/Objects/Object(2).model",
+                    "/3D/Objects/Metadata/343deb59-a82b-4e68-ad43-bcae70f89931.jpeg",
+                    RelationshipType::Thumbnail
+                ),
+                edge(
+                    "/3D/Objects/Object.model",
+                    "/3D/Objects/Metadata/e3611540-6efd-4286-9cf4-3ac5eb862ebf.jpeg",
+                    RelationshipType::Thumbnail
+                ),
+            ]
+        );
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    pub fn read_opc_only_with_memory_optimized_read_test() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+
+        let opc = ThreemfPackage::read_opc_only_with_memory_optimized_deserializer(
+            File::open(&path).unwrap(),
+        )
+        .unwrap();
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(&path).unwrap(),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(opc.content_types, package.content_types);
+        assert_eq!(opc.relationships, package.relationships);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    pub fn read_opc_only_with_speed_optimized_read_test() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+
+        let opc = ThreemfPackage::read_opc_only_with_speed_optimized_deserializer(
+            File::open(path).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(opc.content_types.defaults.len(), 3);
+        let mut rels_paths = opc.relationships.keys().collect::<Vec<_>>();
+        rels_paths.sort();
+        assert_eq!(
+            rels_paths,
+            vec!["/3D/_rels/3dmodel.model.rels", "_rels/.rels"]
+        );
+    }
+
     #[cfg(feature = "io-speed-optimized-read")]
     #[test]
     pub fn from_reader_root_model_with_speed_optimized_read_test() {
@@ -664,6 +1766,7 @@ mod tests {
                     recommendedextensions: None,
                     metadata: vec![],
                     resources: Resources {
+                        unknown_resources: vec![],
                         object: vec![Object {
                             id: 1,
                             objecttype: Some(ObjectType::Model),
@@ -707,6 +1810,7 @@ mod tests {
                             content_type: DefaultContentTypeEnum::Model,
                         },
                     ],
+                    overrides: vec![],
                 },
             );
             threemf.write(&mut writer).unwrap();
@@ -730,6 +1834,7 @@ mod tests {
                 recommendedextensions: None,
                 metadata: vec![],
                 resources: Resources {
+                    unknown_resources: vec![],
                     object: vec![],
                     basematerials: vec![],
                 },
@@ -775,6 +1880,7 @@ mod tests {
                         content_type: DefaultContentTypeEnum::Model,
                     },
                 ],
+                overrides: vec![],
             },
         );
 
@@ -815,6 +1921,7 @@ mod tests {
                 recommendedextensions: None,
                 metadata: vec![],
                 resources: Resources {
+                    unknown_resources: vec![],
                     object: vec![],
                     basematerials: vec![],
                 },
@@ -858,6 +1965,7 @@ mod tests {
                         content_type: DefaultContentTypeEnum::Model,
                     },
                 ],
+                overrides: vec![],
             },
         );
 
@@ -880,6 +1988,773 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "io-write")]
+    #[test]
+    pub fn verify_roundtrip_identical_package_test() {
+        let package = ThreemfPackage::from(Model {
+            unit: Some(model::Unit::Millimeter),
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                unknown_resources: vec![],
+                object: vec![],
+                basematerials: vec![],
+            },
+            build: Build {
+                uuid: None,
+                item: vec![],
+            },
+        });
+
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        package.write(&mut writer).unwrap();
+
+        let diffs = package.verify_roundtrip(&writer.into_inner()).unwrap();
+        assert_eq!(diffs, vec![]);
+    }
+
+    #[cfg(all(feature = "io-memory-optimized-read", feature = "io-write"))]
+    #[test]
+    pub fn verify_roundtrip_reports_changed_and_missing_parts_test() {
+        use super::PartDiff;
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+        let original_bytes = std::fs::read(path).unwrap();
+        let mut package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            Cursor::new(&original_bytes),
+            true,
+        )
+        .unwrap();
+
+        let diffs = package.verify_roundtrip(&original_bytes).unwrap();
+        assert!(
+            diffs
+                .iter()
+                .all(|diff| diff.path() != "Thumbnails/P_XPX_0702_02.png")
+        );
+
+        package
+            .relationships
+            .get_mut("_rels/.rels")
+            .unwrap()
+            .relationships
+            .retain(|rel| rel.relationship_type != RelationshipType::Thumbnail);
+
+        let diffs = package.verify_roundtrip(&original_bytes).unwrap();
+        assert!(diffs.contains(&PartDiff::Missing {
+            path: "Thumbnails/P_XPX_0702_02.png".to_owned()
+        }));
+        assert!(
+            diffs.iter().any(
+                |diff| matches!(diff, PartDiff::Changed { path, .. } if path == "_rels/.rels")
+            )
+        );
+    }
+
+    #[cfg(all(feature = "io-memory-optimized-read", feature = "io-write"))]
+    #[test]
+    fn write_streaming_test() {
+        use crate::io::{CompressionMethod, WriteOptions};
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            true,
+        )
+        .unwrap();
+
+        let options = WriteOptions {
+            compression_method: CompressionMethod::Stored,
+            ..Default::default()
+        };
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        ThreemfPackage::write_streaming_with_options(
+            &mut writer,
+            &package.content_types,
+            &package.relationships,
+            &options,
+            |emit| {
+                for (rels_path, relationships) in &package.relationships {
+                    for relationship in &relationships.relationships {
+                        let target = relationship.target.as_str();
+                        match relationship.relationship_type {
+                            RelationshipType::Model if rels_path == "_rels/.rels" => {
+                                emit.model(target, &package.root)?
+                            }
+                            RelationshipType::Model => {
+                                emit.model(target, &package.sub_models[target])?
+                            }
+                            RelationshipType::Thumbnail => {
+                                emit.thumbnail(target, &package.thumbnails[target])?
+                            }
+                            RelationshipType::Unknown(_) => {
+                                emit.unknown_part(target, &package.unknown_parts[target])?
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        // every part including the emitted ones uses the configured method
+        let mut archive = zip::ZipArchive::new(Cursor::new(writer.get_ref())).unwrap();
+        for i in 0..archive.len() {
+            let compression = archive.by_index(i).unwrap().compression();
+            assert_eq!(compression, zip::CompressionMethod::Stored);
+        }
+
+        writer.set_position(0);
+        let read =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(writer, true).unwrap();
+        assert_eq!(read.root, package.root);
+        assert_eq!(read.sub_models, package.sub_models);
+        assert_eq!(read.thumbnails, package.thumbnails);
+    }
+
+    #[cfg(feature = "io-write")]
+    #[test]
+    fn write_streaming_rejects_unexpected_and_missing_parts_test() {
+        use super::{DEFAULT_ROOT_MODEL_PATH, PartEmitter};
+        use crate::io::error::Error;
+
+        fn write(
+            package: &ThreemfPackage,
+            emit_parts: impl FnOnce(&mut PartEmitter<'_, Cursor<Vec<u8>>>) -> Result<(), Error>,
+        ) -> Result<(), Error> {
+            ThreemfPackage::write_streaming(
+                Cursor::new(Vec::<u8>::new()),
+                &package.content_types,
+                &package.relationships,
+                emit_parts,
+            )
+        }
+
+        let package = beam_lattice_package();
+        let root = &package.root;
+        assert!(write(&package, |e| e.model(DEFAULT_ROOT_MODEL_PATH, root)).is_ok());
+        assert!(write(&package, |_| Ok(())).is_err());
+        assert!(write(&package, |e| e.model("/3D/other.model", root)).is_err());
+        assert!(write(&package, |e| e.unknown_part(DEFAULT_ROOT_MODEL_PATH, &[])).is_err());
+        assert!(
+            write(&package, |e| {
+                e.model(DEFAULT_ROOT_MODEL_PATH, root)?;
+                e.model(DEFAULT_ROOT_MODEL_PATH, root)
+            })
+            .is_err()
+        );
+    }
+
+    #[cfg(feature = "io-write")]
+    #[test]
+    fn write_object_thumbnail_test() {
+        use crate::io::{
+            Error, ModelBuilder,
+            thumbnail_handle::{ImageFormat, ThumbnailHandle},
+        };
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.thumbnail("/3D/Metadata/part.png");
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+        let mut package = ThreemfPackage::from(builder.build().unwrap());
+        assert_eq!(
+            package.root.resources.object[0].thumbnail.as_deref(),
+            Some("/3D/Metadata/part.png")
+        );
+
+        let result = package.write(Cursor::new(Vec::<u8>::new()));
+        match result {
+            Err(Error::MissingThumbnail { object_id, path }) => {
+                assert_eq!(object_id, 1);
+                assert_eq!(path, "/3D/Metadata/part.png");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        package.thumbnails.insert(
+            "/3D/Metadata/part.png".to_owned(),
+            ThumbnailHandle {
+                data: vec![0u8; 4],
+                format: ImageFormat::Png,
+            },
+        );
+        package.relationships.insert(
+            "3D/_rels/3dmodel.model.rels".to_owned(),
+            Relationships {
+                relationships: vec![Relationship {
+                    id: "rel1".to_owned(),
+                    target: "/3D/Metadata/part.png".to_owned(),
+                    relationship_type: RelationshipType::Thumbnail,
+                }],
+            },
+        );
+        package.write(Cursor::new(Vec::<u8>::new())).unwrap();
+    }
+
+    #[cfg(all(feature = "io-memory-optimized-read", feature = "io-write"))]
+    #[test]
+    fn write_with_promote_recommended_to_required_test() {
+        use crate::io::{ModelBuilder, WriteOptions};
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                obj.add_triangle_sets(|sets| {
+                    sets.add_set("Face", "face", &[0], &[]);
+                });
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+        let package = ThreemfPackage::from(builder.build().unwrap());
+        assert_eq!(
+            package.root.recommendedextensions.as_deref().map(str::trim),
+            Some("t")
+        );
+
+        let options = WriteOptions {
+            promote_recommended_to_required: true,
+            ..Default::default()
+        };
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        package.write_with_options(&mut writer, &options).unwrap();
+
+        let read =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(writer, false).unwrap();
+        assert_eq!(read.root.requiredextensions.as_deref(), Some("t"));
+        assert_eq!(read.root.recommendedextensions, None);
+        assert!(
+            read.root.resources.object[0]
+                .mesh
+                .as_ref()
+                .unwrap()
+                .trianglesets
+                .is_some()
+        );
+    }
+
+    /// Zips the files under `dir` into an archive with paths relative to `dir`.
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    fn repack_dir(dir: &std::path::Path) -> Cursor<Vec<u8>> {
+        use std::io::Write;
+        use zip::{ZipWriter, write::SimpleFileOptions};
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(path) = pending.pop() {
+            if path.is_dir() {
+                pending.extend(std::fs::read_dir(&path).unwrap().map(|e| e.unwrap().path()));
+                continue;
+            }
+
+            let name = path
+                .strip_prefix(dir)
+                .unwrap()
+                .components()
+                .map(|c| c.as_os_str().to_str().unwrap())
+                .collect::<Vec<_>>()
+                .join("/");
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&std::fs::read(&path).unwrap()).unwrap();
+        }
+
+        let mut archive = zip.finish().unwrap();
+        archive.set_position(0);
+        archive
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn write_to_dir_test() {
+        use crate::io::error::Error;
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .unwrap();
+
+        let dir =
+            std::env::temp_dir().join(format!("threemf2-write-to-dir-{}", std::process::id()));
+        package.write_to_dir(&dir).unwrap();
+
+        assert!(dir.join("[Content_Types].xml").is_file());
+        assert!(dir.join("_rels/.rels").is_file());
+        assert!(dir.join("3D/3dmodel.model").is_file());
+
+        let read =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(repack_dir(&dir), false);
+
+        // parts outside of the directory are rejected
+        let escaping =
+            ThreemfPackage::from_root_model_at(package.root.clone(), "/../outside.model").unwrap();
+        let escaped = escaping.write_to_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(escaped, Err(Error::WriteError(_))));
+        assert!(!dir.with_file_name("outside.model").exists());
+
+        let read = read.unwrap();
+        assert_eq!(read.root, package.root);
+        assert_eq!(read.sub_models, package.sub_models);
+        assert_eq!(read.relationships, package.relationships);
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn to_bytes_test() {
+        use crate::io::{CompressionMethod, ModelBuilder, WriteOptions};
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        for _ in 0..2 {
+            let id = builder
+                .add_mesh_object(|obj| {
+                    obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                    obj.add_triangle(&[0, 1, 2]);
+                    Ok(())
+                })
+                .unwrap();
+            builder.add_build_item(id).unwrap();
+        }
+        let package = ThreemfPackage::from(builder.build().unwrap());
+
+        let bytes = package.to_bytes().unwrap();
+        assert!(bytes.starts_with(b"PK"));
+
+        let options = WriteOptions {
+            compression_method: CompressionMethod::Stored,
+            ..Default::default()
+        };
+        let stored = package.to_bytes_with_options(&options).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(stored)).unwrap();
+        for i in 0..archive.len() {
+            let compression = archive.by_index(i).unwrap().compression();
+            assert_eq!(compression, zip::CompressionMethod::Stored);
+        }
+
+        let read = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            Cursor::new(bytes),
+            false,
+        )
+        .unwrap();
+        assert_eq!(read.root.resources.object.len(), 2);
+        assert_eq!(read.root, package.root);
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn part_errors_name_the_part_test() {
+        use std::io::{Read, Write};
+        use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+        use crate::io::{ModelBuilder, error::Error};
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+        let model = builder.build().unwrap();
+
+        let mut package = ThreemfPackage::from_root_model(model.clone());
+        // a second relationship to the root model makes the archive write it twice
+        let root_rels = package.relationships.get_mut("_rels/.rels").unwrap();
+        let duplicate = root_rels.relationships[0].clone();
+        root_rels.relationships.push(duplicate);
+
+        let error = package.to_bytes().unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::PartWrite { path, source } if path == "/3D/3dmodel.model"
+                && matches!(**source, Error::Zip(_))
+        ));
+        assert_eq!(error.to_string(), "failed writing /3D/3dmodel.model");
+
+        // an archive missing the root model part
+        let bytes = ThreemfPackage::from_root_model(model).to_bytes().unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            if file.name() != "3D/3dmodel.model" {
+                let mut content = vec![];
+                file.read_to_end(&mut content).unwrap();
+                zip.start_file(file.name(), SimpleFileOptions::default())
+                    .unwrap();
+                zip.write_all(&content).unwrap();
+            }
+        }
+        let archive = zip.finish().unwrap();
+
+        let error = ThreemfPackage::from_reader_with_memory_optimized_deserializer(archive, false)
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::PartRead { path, .. } if path == "/3D/3dmodel.model"
+        ));
+        assert_eq!(error.to_string(), "failed reading /3D/3dmodel.model");
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn write_with_preserve_attribute_order_test() {
+        use crate::io::{WriteOptions, utils::parse_model_attribute_names};
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/component-forward-reference.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            package.get_attribute_order_on_model(None),
+            Some(&["unit", "xml:lang", "xmlns"].map(str::to_owned)[..])
+        );
+
+        let xml = written_root_model(&package, &WriteOptions::default());
+        assert_eq!(parse_model_attribute_names(&xml), vec!["xmlns", "unit"]);
+
+        let options = WriteOptions {
+            preserve_attribute_order: true,
+            ..Default::default()
+        };
+        let xml = written_root_model(&package, &options);
+        assert_eq!(parse_model_attribute_names(&xml), vec!["unit", "xmlns"]);
+
+        // packages not read from a file are written as usual
+        let package = ThreemfPackage::from_root_model(package.root.clone());
+        assert_eq!(package.get_attribute_order_on_model(None), None);
+        let xml = written_root_model(&package, &options);
+        assert_eq!(parse_model_attribute_names(&xml), vec!["xmlns", "unit"]);
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn write_preserves_build_items_test() {
+        use std::io::Read;
+
+        use crate::io::WriteOptions;
+
+        let build_section = |xml: &str| {
+            let start = xml.find("<build").unwrap();
+            let end = xml.find("</build>").unwrap();
+            xml[start..end].to_owned()
+        };
+
+        let path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/build-item-attributes.3mf");
+        let mut original = String::new();
+        zip::ZipArchive::new(File::open(&path).unwrap())
+            .unwrap()
+            .by_name("3D/3dmodel.model")
+            .unwrap()
+            .read_to_string(&mut original)
+            .unwrap();
+
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(&path).unwrap(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(package.root.build.item.len(), 2);
+
+        let written = written_root_model(&package, &WriteOptions::default());
+        assert_eq!(build_section(&written), build_section(&original));
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn from_root_model_at_test() {
+        use crate::io::{Error, ModelBuilder};
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.name("Triangle");
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+        let model = builder.build().unwrap();
+
+        for invalid in ["model/root.model", "/model/root.xml", "/model/.model"] {
+            match ThreemfPackage::from_root_model_at(model.clone(), invalid) {
+                Err(Error::InvalidModelPath(path)) => assert_eq!(path, invalid),
+                other => panic!("unexpected result {:?}", other),
+            }
+        }
+
+        let package = ThreemfPackage::from_root_model_at(model, "/model/root.model").unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        package.write(&mut writer).unwrap();
+
+        let archive = zip::ZipArchive::new(writer.clone()).unwrap();
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["[Content_Types].xml", "_rels/.rels", "model/root.model"]
+        );
+
+        let read =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(writer, false).unwrap();
+        assert!(read.sub_models.is_empty());
+        assert_eq!(
+            read.root.resources.object[0].name.as_deref(),
+            Some("Triangle")
+        );
+        assert_eq!(
+            read.relationships["_rels/.rels"].relationships[0].target,
+            "/model/root.model"
+        );
+    }
+
+    #[cfg(feature = "io-write")]
+    fn beam_lattice_package() -> ThreemfPackage {
+        use crate::io::ModelBuilder;
+
+        let mut builder = ModelBuilder::new(model::Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                obj.add_beam_lattice(|lattice| {
+                    lattice.radius(0.5).add_beam(0, 1).add_beam(1, 2);
+                });
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+        ThreemfPackage::from(builder.build().unwrap())
+    }
+
+    #[cfg(feature = "io-write")]
+    fn written_root_model(package: &ThreemfPackage, options: &crate::io::WriteOptions) -> String {
+        use std::io::Read;
+
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        package.write_with_options(&mut writer, options).unwrap();
+
+        let mut archive = zip::ZipArchive::new(writer).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("3D/3dmodel.model")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    /// Returns whether an element with the given local name is written, with or without prefix.
+    #[cfg(feature = "io-write")]
+    fn has_element(xml: &str, name: &str) -> bool {
+        [">", "/>", " "].iter().any(|end| {
+            xml.contains(&format!("<{name}{end}")) || xml.contains(&format!(":{name}{end}"))
+        })
+    }
+
+    #[cfg(all(
+        feature = "io-write",
+        any(
+            feature = "io-memory-optimized-read",
+            feature = "io-speed-optimized-read"
+        )
+    ))]
+    #[test]
+    fn write_beam_lattice_without_balls_test() {
+        use crate::io::WriteOptions;
+
+        let package = beam_lattice_package();
+        let lattice = package.root.resources.object[0]
+            .mesh
+            .as_ref()
+            .unwrap()
+            .beamlattice
+            .as_ref()
+            .unwrap();
+        assert_eq!(lattice.balls, None);
+        assert_eq!(lattice.beamsets, None);
+
+        let xml = written_root_model(&package, &WriteOptions::default());
+        assert!(has_element(&xml, "beams"));
+        assert!(!has_element(&xml, "balls"));
+        assert!(!has_element(&xml, "beamsets"));
+
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        package.write(&mut writer).unwrap();
+        let bytes = writer.into_inner();
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let read = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+                Cursor::new(&bytes),
+                false,
+            )
+            .unwrap();
+            let mesh = read.root.resources.object[0].mesh.as_ref().unwrap();
+            let lattice = mesh.beamlattice.as_ref().unwrap();
+            assert_eq!(lattice.beams.beam.len(), 2);
+            assert_eq!(lattice.balls, None);
+            assert_eq!(lattice.beamsets, None);
+        }
+
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let read = ThreemfPackage::from_reader_with_speed_optimized_deserializer(
+                Cursor::new(&bytes),
+                false,
+            )
+            .unwrap();
+            let mesh = read.root.resources.object[0].mesh.as_ref().unwrap();
+            let lattice = mesh.beamlattice.as_ref().unwrap();
+            assert_eq!(lattice.beams.beam.len(), 2);
+            assert_eq!(lattice.balls, None);
+            assert_eq!(lattice.beamsets, None);
+        }
+    }
+
+    #[cfg(feature = "io-write")]
+    #[test]
+    fn write_with_omit_empty_containers_test() {
+        use crate::{
+            core::{
+                beamlattice::{Balls, BeamSets},
+                triangle_set::TriangleSets,
+            },
+            io::WriteOptions,
+        };
+
+        let mut package = beam_lattice_package();
+        let mesh = package.root.resources.object[0].mesh.as_mut().unwrap();
+        mesh.trianglesets = Some(TriangleSets {
+            trianglesets: vec![],
+        });
+        let lattice = mesh.beamlattice.as_mut().unwrap();
+        lattice.balls = Some(Balls { ball: vec![] });
+        lattice.beamsets = Some(BeamSets { beamset: vec![] });
+
+        let xml = written_root_model(&package, &WriteOptions::default());
+        assert!(has_element(&xml, "balls"));
+        assert!(has_element(&xml, "beamsets"));
+        assert!(has_element(&xml, "trianglesets"));
+
+        let options = WriteOptions {
+            omit_empty_containers: true,
+            ..Default::default()
+        };
+        let xml = written_root_model(&package, &options);
+        assert!(has_element(&xml, "beams"));
+        assert!(!has_element(&xml, "balls"));
+        assert!(!has_element(&xml, "beamsets"));
+        assert!(!has_element(&xml, "trianglesets"));
+
+        // the package itself is left untouched
+        let mesh = package.root.resources.object[0].mesh.as_ref().unwrap();
+        assert!(mesh.trianglesets.is_some());
+    }
+
+    #[cfg(feature = "io-write")]
+    #[test]
+    fn write_with_always_emit_unit_test() {
+        use crate::io::WriteOptions;
+
+        let mut package = beam_lattice_package();
+        package.root.unit = None;
+        let options = WriteOptions {
+            always_emit_unit: true,
+            ..Default::default()
+        };
+
+        let xml = written_root_model(&package, &WriteOptions::default());
+        assert!(!xml.contains("unit="));
+
+        let xml = written_root_model(&package, &options);
+        assert!(xml.contains(r#"unit="millimeter""#));
+
+        // an explicit unit is written either way
+        package.root.unit = Some(model::Unit::Inch);
+        let xml = written_root_model(&package, &WriteOptions::default());
+        assert!(xml.contains(r#"unit="inch""#));
+        let xml = written_root_model(&package, &options);
+        assert!(xml.contains(r#"unit="inch""#));
+    }
+
+    #[cfg(all(feature = "io-write", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn write_with_compression_method_test() {
+        use crate::io::{CompressionMethod, WriteOptions};
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(package.thumbnails.len(), 1);
+
+        let write = |compression_method, compression_level| {
+            let options = WriteOptions {
+                compression_method,
+                compression_level,
+                ..Default::default()
+            };
+            let mut writer = Cursor::new(Vec::<u8>::new());
+            package.write_with_options(&mut writer, &options).unwrap();
+            writer.into_inner()
+        };
+        let stored = write(CompressionMethod::Stored, Some(9));
+        let deflated = write(CompressionMethod::Deflated, None);
+        let smallest = write(CompressionMethod::Deflated, Some(9));
+        assert!(stored.len() > deflated.len());
+        assert!(deflated.len() >= smallest.len());
+
+        let mut reads = vec![];
+        for (bytes, method) in [
+            (&stored, zip::CompressionMethod::Stored),
+            (&deflated, zip::CompressionMethod::Deflated),
+            (&smallest, zip::CompressionMethod::Deflated),
+        ] {
+            // every part including the thumbnail uses the method
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+            for i in 0..archive.len() {
+                assert_eq!(archive.by_index(i).unwrap().compression(), method);
+            }
+
+            let read = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+                Cursor::new(bytes),
+                true,
+            )
+            .unwrap();
+            assert_eq!(read.thumbnails, package.thumbnails);
+            reads.push(read);
+        }
+        assert_eq!(reads[0], reads[1]);
+        assert_eq!(reads[0], reads[2]);
+    }
+
     #[cfg(feature = "io-memory-optimized-read")]
     #[test]
     fn i_root_namespaces_tracking_test() {
@@ -917,4 +2792,266 @@ mod tests {
             Err(err) => panic!("{:?}", err),
         }
     }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn iter_parts_test() {
+        use super::Part;
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let reader = File::open(path).unwrap();
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true).unwrap();
+
+        let parts = package.iter_parts().collect::<Vec<_>>();
+        assert_eq!(
+            parts.len(),
+            1 + package.relationships.len()
+                + 1
+                + package.sub_models.len()
+                + package.thumbnails.len()
+                + package.unknown_parts.len()
+        );
+
+        assert!(matches!(parts[0], Part::ContentTypes { .. }));
+        assert_eq!(parts[0].path(), "[Content_Types].xml");
+
+        let models = parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Model { path, .. } => Some(*path),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            models,
+            vec![
+                "/3D/3dmodel.model",
+                "/3D/Objects/Object(2).model",
+                "/3D/Objects/Object.model",
+            ]
+        );
+
+        let thumbnails = parts
+            .iter()
+            .filter(|part| matches!(part, Part::Thumbnail { .. }))
+            .count();
+        assert_eq!(thumbnails, 3);
+    }
+
+    #[test]
+    fn into_parts_test() {
+        let package = ThreemfPackage::from_root_model(Model {
+            unit: Some(model::Unit::Millimeter),
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources::default(),
+            build: Build::default(),
+        });
+        let expected = package.clone();
+
+        let (root, sub_models, thumbnails, unknown_parts, relationships, content_types) =
+            package.into_parts();
+
+        assert_eq!(root, expected.root);
+        assert_eq!(sub_models, expected.sub_models);
+        assert_eq!(thumbnails, expected.thumbnails);
+        assert_eq!(unknown_parts, expected.unknown_parts);
+        assert_eq!(relationships, expected.relationships);
+        assert_eq!(content_types, expected.content_types);
+
+        let rebuilt = ThreemfPackage::new(
+            root,
+            sub_models,
+            thumbnails,
+            unknown_parts,
+            relationships,
+            content_types,
+        );
+        assert_eq!(rebuilt, expected);
+    }
+
+    #[test]
+    fn unsupported_required_extensions_test() {
+        let model = |requiredextensions: Option<&str>| Model {
+            unit: Some(model::Unit::Millimeter),
+            requiredextensions: requiredextensions.map(str::to_owned),
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources::default(),
+            build: Build::default(),
+        };
+
+        let mut package = ThreemfPackage::from_root_model(model(Some("p b2")));
+        assert!(package.unsupported_required_extensions().is_empty());
+
+        package.root.requiredextensions = Some("p m".to_owned());
+        package
+            .sub_models
+            .insert("/3D/b.model".to_owned(), model(Some("b x m")));
+        package
+            .sub_models
+            .insert("/3D/a.model".to_owned(), model(None));
+        assert_eq!(
+            package.unsupported_required_extensions(),
+            vec!["m".to_owned(), "x".to_owned()]
+        );
+    }
+
+    #[test]
+    fn validate_degenerate_triangle_indices_test() {
+        use crate::{
+            core::mesh::{Mesh, Triangle, Triangles, Vertex, Vertices},
+            io::Error,
+        };
+
+        let triangle = |v1, v2, v3| Triangle {
+            v1,
+            v2,
+            v3,
+            p1: None,
+            p2: None,
+            p3: None,
+            pid: None,
+        };
+        let mut object = Object {
+            id: 4,
+            objecttype: Some(ObjectType::Model),
+            thumbnail: None,
+            partnumber: None,
+            name: None,
+            pid: None,
+            pindex: None,
+            uuid: None,
+            mesh: Some(Mesh {
+                vertices: Vertices {
+                    vertex: vec![
+                        Vertex {
+                            x: 0.0,
+                            y: 0.0,
+                            z: 0.0
+                        };
+                        3
+                    ],
+                },
+                triangles: Triangles {
+                    triangle: vec![triangle(0, 1, 2)],
+                },
+                trianglesets: None,
+                beamlattice: None,
+            }),
+            components: None,
+        };
+        let model = |object: Object| Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources {
+                object: vec![object],
+                ..Default::default()
+            },
+            build: Build::default(),
+        };
+
+        assert!(
+            ThreemfPackage::from(model(object.clone()))
+                .validate()
+                .is_ok()
+        );
+
+        if let Some(mesh) = &mut object.mesh {
+            mesh.triangles.triangle.push(triangle(0, 0, 1));
+        }
+        assert!(matches!(
+            ThreemfPackage::from(model(object.clone())).validate(),
+            Err(Error::DegenerateTriangleIndices {
+                object_id: 4,
+                triangle: 1
+            })
+        ));
+
+        let mut empty = object.clone();
+        empty.mesh = None;
+        assert!(matches!(
+            ThreemfPackage::from(model(empty.clone())).validate(),
+            Err(Error::EmptyObject { id: 4 })
+        ));
+        empty.objecttype = Some(ObjectType::Other);
+        assert!(ThreemfPackage::from(model(empty)).validate().is_ok());
+
+        // indices outside the vertices are reported for objects of type other as well
+        object.objecttype = Some(ObjectType::Other);
+        if let Some(mesh) = &mut object.mesh {
+            mesh.triangles.triangle.insert(0, triangle(0, 1, 3));
+        }
+        assert!(matches!(
+            ThreemfPackage::from(model(object)).validate(),
+            Err(Error::TriangleIndexOutOfBounds {
+                object_id: 4,
+                triangle: 0,
+                index: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn set_package_thumbnail_test() {
+        use crate::io::{
+            Error,
+            thumbnail_handle::{ImageFormat, ThumbnailHandle},
+        };
+
+        let model = Model {
+            unit: None,
+            requiredextensions: None,
+            recommendedextensions: None,
+            metadata: vec![],
+            resources: Resources::default(),
+            build: Build::default(),
+        };
+        let mut package = ThreemfPackage::from(model);
+        let thumbnail = |format| ThumbnailHandle {
+            data: vec![1, 2, 3],
+            format,
+        };
+
+        package
+            .set_package_thumbnail("Metadata/thumbnail.png", thumbnail(ImageFormat::Png))
+            .unwrap();
+        package
+            .set_package_thumbnail("/Metadata/thumbnail.jpg", thumbnail(ImageFormat::Jpeg))
+            .unwrap();
+
+        let thumbnails = package.relationships["_rels/.rels"]
+            .relationships
+            .iter()
+            .filter(|r| r.relationship_type == RelationshipType::Thumbnail)
+            .map(|r| r.target.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(thumbnails, vec!["/Metadata/thumbnail.jpg"]);
+        assert_eq!(
+            package.thumbnails.keys().collect::<Vec<_>>(),
+            vec!["/Metadata/thumbnail.jpg"]
+        );
+        for (extension, content_type) in [
+            ("png", DefaultContentTypeEnum::ImagePng),
+            ("jpg", DefaultContentTypeEnum::ImageJPEG),
+        ] {
+            assert!(
+                package
+                    .content_types
+                    .defaults
+                    .iter()
+                    .any(|d| d.extension == extension && d.content_type == content_type)
+            );
+        }
+
+        assert!(matches!(
+            package.set_package_thumbnail("thumbnail.bmp", thumbnail(ImageFormat::Unknown)),
+            Err(Error::ThumbnailError(_))
+        ));
+    }
 }
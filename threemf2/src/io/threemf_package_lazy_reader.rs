@@ -1,16 +1,17 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::io::{Read, Seek};
+use std::io::{Cursor, Read, Seek};
 
 use once_cell::unsync::OnceCell;
 use zip::ZipArchive;
 
 use crate::core::model::Model;
 use crate::io::thumbnail_handle::{ImageFormat, ThumbnailHandle};
-use crate::io::{XmlNamespace, utils};
+use crate::io::{ReadOptions, XmlNamespace, utils};
 use crate::io::{
     content_types::{ContentTypes, DefaultContentTypeEnum},
     error::Error,
+    read_options::ComplexityTotals,
     relationship::{RelationshipType, Relationships},
     zip_utils::{self, XmlDeserializer},
 };
@@ -32,10 +33,14 @@ pub enum CachePolicy {
 /// This is ideal for memory-constrained environments or when you need to inspect package contents
 /// without loading all data.
 ///
+/// The [ReadOptions] it is created with are applied to every model when it is loaded. As the
+/// models are loaded on demand, the [ReadLimits](crate::io::ReadLimits) are checked for each
+/// model on its own instead of summed up over the package.
 pub struct ThreemfPackageLazyReader<R: Read + Seek> {
     archive: RefCell<ZipArchive<R>>,
     deserializer: XmlDeserializer,
     cache_policy: CachePolicy,
+    options: ReadOptions,
 
     // always eagerly loaded
     content_types: ContentTypes,
@@ -56,6 +61,7 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         reader: R,
         deserializer: XmlDeserializer,
         cache_policy: CachePolicy,
+        options: &ReadOptions,
     ) -> Result<Self, Error> {
         let (mut zip, content_types, _, root_rels_filename) =
             zip_utils::setup_archive_and_content_types(reader, deserializer)?;
@@ -73,11 +79,12 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         };
 
         let mut relationships = HashMap::<String, Relationships>::new();
-        let root_rels: Relationships = zip_utils::relationships_from_zip_by_name(
+        let mut root_rels: Relationships = zip_utils::relationships_from_zip_by_name(
             &mut zip,
             &root_rels_filename,
             &deserializer,
         )?;
+        options.apply_to_relationships(&mut root_rels, &root_rels_filename);
 
         let root_model_path = root_rels
             .relationships
@@ -91,11 +98,12 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         let rel_files =
             zip_utils::discover_relationship_files(&mut zip, rels_ext, &root_rels_filename)?;
         for rel_file_path in rel_files {
-            let rels = zip_utils::relationships_from_zip_by_name(
+            let mut rels = zip_utils::relationships_from_zip_by_name(
                 &mut zip,
                 &rel_file_path[1..],
                 &deserializer,
             )?;
+            options.apply_to_relationships(&mut rels, &rel_file_path);
             relationships.insert(rel_file_path, rels);
         }
 
@@ -103,6 +111,7 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
             archive: RefCell::new(zip),
             deserializer,
             cache_policy,
+            options: options.clone(),
             content_types,
             relationships,
             root_model_path,
@@ -225,6 +234,69 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         }
     }
 
+    /// Invokes `f` with the vertex positions of every triangle of every mesh object in the
+    /// model at `path`.
+    ///
+    /// This is meant for streaming the geometry of a single, possibly huge, part (e.g. into a
+    /// GPU buffer) without keeping it around. If the model is not already cached it is parsed,
+    /// walked once and dropped again regardless of the [`CachePolicy`], so the parsed model is
+    /// only held for the duration of the call. Triangles referencing vertices outside of their
+    /// mesh are skipped.
+    pub fn for_each_triangle<F>(&self, path: &str, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut([[f64; 3]; 3]),
+    {
+        let mut visit = |model: &Model| {
+            let meshes = model
+                .resources
+                .object
+                .iter()
+                .filter_map(|o| o.mesh.as_ref());
+            for mesh in meshes {
+                let vertex =
+                    |index: usize| mesh.vertices.vertex.get(index).map(|v| [v.x, v.y, v.z]);
+                for triangle in &mesh.triangles.triangle {
+                    if let (Some(a), Some(b), Some(c)) = (
+                        vertex(triangle.v1),
+                        vertex(triangle.v2),
+                        vertex(triangle.v3),
+                    ) {
+                        f([a, b, c]);
+                    }
+                }
+            }
+        };
+
+        if path == self.root_model_path
+            && let Some((model, _)) = self.root_model.get()
+        {
+            visit(model);
+            return Ok(());
+        }
+
+        if let Some((model, _)) = self.sub_models.borrow().get(path) {
+            visit(model);
+            return Ok(());
+        }
+
+        let is_model = path == self.root_model_path
+            || self
+                .relationships
+                .values()
+                .flat_map(|r| &r.relationships)
+                .any(|rel| {
+                    rel.target == path && matches!(rel.relationship_type, RelationshipType::Model)
+                });
+
+        if !is_model {
+            return Err(Error::ResourceNotFound(path.to_owned()));
+        }
+
+        let (model, _) = self.load_model_from_archive(path)?;
+        visit(&model);
+        Ok(())
+    }
+
     pub fn with_thumbnail<F, T>(&self, path: &str, f: F) -> Result<T, Error>
     where
         F: FnOnce(&ThumbnailHandle) -> T,
@@ -313,10 +385,7 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         }
 
         // Read XML directly from ZIP archive
-        let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name(utils::try_strip_leading_slash(path))?;
-        let mut xml_string = String::new();
-        file.read_to_string(&mut xml_string)?;
+        let xml_string = self.read_part_to_string(path)?;
 
         Ok(f(&xml_string))
     }
@@ -337,10 +406,7 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         }
 
         // Read relationships XML directly from ZIP
-        let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name(utils::try_strip_leading_slash(path))?;
-        let mut xml_string = String::new();
-        file.read_to_string(&mut xml_string)?;
+        let xml_string = self.read_part_to_string(path)?;
 
         Ok(f(&xml_string))
     }
@@ -351,29 +417,30 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         F: FnOnce(&str) -> T,
     {
         // Read content types XML directly from ZIP
-        let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name("[Content_Types].xml")?;
-        let mut xml_string = String::new();
-        file.read_to_string(&mut xml_string)?;
+        let xml_string = self.read_part_to_string("[Content_Types].xml")?;
 
         Ok(f(&xml_string))
     }
 
     fn load_model_from_archive(&self, path: &str) -> Result<(Model, Vec<XmlNamespace>), Error> {
-        let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name(utils::try_strip_leading_slash(path))?;
-        self.deserializer.deserialize_model(&mut file)
+        let xml_string = self.read_part_to_string(path)?;
+        self.options
+            .limits
+            .check_model(&xml_string, &mut ComplexityTotals::default())?;
+
+        let (mut model, namespaces) = self
+            .deserializer
+            .deserialize_model(&xml_string)
+            .map_err(|err| err.reading_part(path))?;
+        self.options.apply(&mut model, path)?;
+        Ok((model, namespaces))
     }
 
     fn load_thumbnail_from_archive(&self, path: &str) -> Result<ThumbnailHandle, Error> {
-        let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name(utils::try_strip_leading_slash(path))?;
-        let mut bytes: Vec<u8> = vec![];
-        file.read_to_end(&mut bytes)?;
+        let bytes = self.load_unknown_part_from_archive(path)?;
 
         let format = {
-            if let Some(filepath) = file.enclosed_name()
-                && let Some(os_ext) = filepath.extension()
+            if let Some(os_ext) = std::path::Path::new(path).extension()
                 && let Some(ext) = os_ext.to_str()
             {
                 ImageFormat::from_ext(ext)
@@ -391,11 +458,26 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
 
     fn load_unknown_part_from_archive(&self, path: &str) -> Result<Vec<u8>, Error> {
         let mut archive = self.archive.borrow_mut();
-        let mut file = archive.by_name(utils::try_strip_leading_slash(path))?;
         let mut bytes: Vec<u8> = vec![];
-        file.read_to_end(&mut bytes)?;
+        archive
+            .by_name(utils::try_strip_leading_slash(path))
+            .map_err(Error::from)
+            .and_then(|mut file| Ok(file.read_to_end(&mut bytes)?))
+            .map_err(|err| err.reading_part(path))?;
         Ok(bytes)
     }
+
+    /// Reads the part at `path` from the archive into a string.
+    fn read_part_to_string(&self, path: &str) -> Result<String, Error> {
+        let mut archive = self.archive.borrow_mut();
+        let mut xml_string = String::new();
+        archive
+            .by_name(utils::try_strip_leading_slash(path))
+            .map_err(Error::from)
+            .and_then(|mut file| Ok(file.read_to_string(&mut xml_string)?))
+            .map_err(|err| err.reading_part(path))?;
+        Ok(xml_string)
+    }
 }
 
 #[cfg(feature = "io-memory-optimized-read")]
@@ -408,7 +490,51 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         reader: R,
         cache_policy: CachePolicy,
     ) -> Result<Self, Error> {
-        Self::from_reader(reader, XmlDeserializer::MemoryOptimized, cache_policy)
+        Self::from_reader(
+            reader,
+            XmlDeserializer::MemoryOptimized,
+            cache_policy,
+            &ReadOptions::default(),
+        )
+    }
+
+    /// Same as [ThreemfPackageLazyReader::from_reader_with_memory_optimized_deserializer] but
+    /// validates and normalizes the models according to the given [ReadOptions].
+    pub fn from_reader_with_memory_optimized_deserializer_and_options(
+        reader: R,
+        cache_policy: CachePolicy,
+        options: &ReadOptions,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            reader,
+            XmlDeserializer::MemoryOptimized,
+            cache_policy,
+            options,
+        )
+    }
+}
+
+#[cfg(feature = "io-memory-optimized-read")]
+impl ThreemfPackageLazyReader<Cursor<Vec<u8>>> {
+    /// Create a pull-based package with memory-optimized deserialization from the bytes of
+    /// a 3mf archive, e.g. a file fetched in WASM.
+    ///
+    /// The package owns the buffer, so it can outlive the source of the bytes. The compressed
+    /// bytes stay resident for the lifetime of the package to load the parts on demand,
+    /// but only the parts accessed are decompressed.
+    ///
+    /// * `bytes` - The bytes of the 3mf archive
+    /// * `cache_policy` - Whether to cache loaded data (`CachePolicy::NoCache` is default)
+    pub fn from_bytes_with_memory_optimized_deserializer(
+        bytes: Vec<u8>,
+        cache_policy: CachePolicy,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            Cursor::new(bytes),
+            XmlDeserializer::MemoryOptimized,
+            cache_policy,
+            &ReadOptions::default(),
+        )
     }
 }
 
@@ -422,7 +548,51 @@ impl<R: Read + Seek> ThreemfPackageLazyReader<R> {
         reader: R,
         cache_policy: CachePolicy,
     ) -> Result<Self, Error> {
-        Self::from_reader(reader, XmlDeserializer::SpeedOptimized, cache_policy)
+        Self::from_reader(
+            reader,
+            XmlDeserializer::SpeedOptimized,
+            cache_policy,
+            &ReadOptions::default(),
+        )
+    }
+
+    /// Same as [ThreemfPackageLazyReader::from_reader_with_speed_optimized_deserializer] but
+    /// validates and normalizes the models according to the given [ReadOptions].
+    pub fn from_reader_with_speed_optimized_deserializer_and_options(
+        reader: R,
+        cache_policy: CachePolicy,
+        options: &ReadOptions,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            reader,
+            XmlDeserializer::SpeedOptimized,
+            cache_policy,
+            options,
+        )
+    }
+}
+
+#[cfg(feature = "io-speed-optimized-read")]
+impl ThreemfPackageLazyReader<Cursor<Vec<u8>>> {
+    /// Create a pull-based package with speed-optimized deserialization from the bytes of
+    /// a 3mf archive, e.g. a file fetched in WASM.
+    ///
+    /// The package owns the buffer, so it can outlive the source of the bytes. The compressed
+    /// bytes stay resident for the lifetime of the package to load the parts on demand,
+    /// but only the parts accessed are decompressed.
+    ///
+    /// * `bytes` - The bytes of the 3mf archive
+    /// * `cache_policy` - Whether to cache loaded data (`CachePolicy::NoCache` is default)
+    pub fn from_bytes_with_speed_optimized_deserializer(
+        bytes: Vec<u8>,
+        cache_policy: CachePolicy,
+    ) -> Result<Self, Error> {
+        Self::from_reader(
+            Cursor::new(bytes),
+            XmlDeserializer::SpeedOptimized,
+            cache_policy,
+            &ReadOptions::default(),
+        )
     }
 }
 
@@ -459,6 +629,40 @@ mod tests {
         assert_eq!(root_ns.len(), 3);
     }
 
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn test_pull_based_from_bytes() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+        let bytes = std::fs::read(path).unwrap();
+
+        let package = ThreemfPackageLazyReader::from_bytes_with_memory_optimized_deserializer(
+            bytes,
+            CachePolicy::NoCache,
+        )
+        .unwrap();
+
+        assert_eq!(package.relationships().len(), 2);
+        let (root_model, _) = package.root_model().unwrap();
+        assert!(!root_model.resources.object.is_empty());
+        assert!(package.with_model("/3D/midway.model", |_| true).is_ok());
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn test_pull_based_from_bytes_speed_optimized() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/P_XPX_0702_02.3mf");
+        let bytes = std::fs::read(path).unwrap();
+
+        let package = ThreemfPackageLazyReader::from_bytes_with_speed_optimized_deserializer(
+            bytes,
+            CachePolicy::NoCache,
+        )
+        .unwrap();
+
+        assert_eq!(package.relationships().len(), 2);
+        assert!(package.with_model("/3D/midway.model", |_| true).is_ok());
+    }
+
     #[cfg(feature = "io-memory-optimized-read")]
     #[test]
     fn test_pull_based_with_sub_models() {
@@ -490,6 +694,85 @@ mod tests {
         assert!(exists.is_err());
     }
 
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn test_for_each_triangle_does_not_cache() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-beamlattice-separate-model-files.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package = ThreemfPackageLazyReader::from_reader_with_memory_optimized_deserializer(
+            reader,
+            CachePolicy::CacheAll,
+        )
+        .unwrap();
+
+        let sub_model_path = "/3D/Objects/Object(3).model";
+        let mut count = 0;
+        package
+            .for_each_triangle(sub_model_path, |positions| {
+                assert!(positions.iter().flatten().all(|c| c.is_finite()));
+                count += 1;
+            })
+            .unwrap();
+        assert!(count > 0);
+        assert!(package.sub_models.borrow().is_empty());
+
+        let expected = package
+            .with_model(sub_model_path, |(model, _)| {
+                model
+                    .resources
+                    .object
+                    .iter()
+                    .filter_map(|o| o.mesh.as_ref())
+                    .map(|m| m.triangles.triangle.len())
+                    .sum::<usize>()
+            })
+            .unwrap();
+        assert_eq!(count, expected);
+
+        let result = package.for_each_triangle("/3D/DoesNotExist.model", |_| {});
+        assert!(matches!(result, Err(Error::ResourceNotFound(_))));
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn test_pull_based_applies_read_options() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/object-mesh-and-components.3mf");
+
+        let package = ThreemfPackageLazyReader::from_reader_with_memory_optimized_deserializer(
+            File::open(&path).unwrap(),
+            CachePolicy::NoCache,
+        )
+        .unwrap();
+        assert!(matches!(
+            package.root_model(),
+            Err(Error::ObjectHasMeshAndComponents { id: 2 })
+        ));
+
+        let options = ReadOptions {
+            prefer_mesh_over_components: true,
+            ..Default::default()
+        };
+        let package =
+            ThreemfPackageLazyReader::from_reader_with_memory_optimized_deserializer_and_options(
+                File::open(&path).unwrap(),
+                CachePolicy::NoCache,
+                &options,
+            )
+            .unwrap();
+        let (root_model, _) = package.root_model().unwrap();
+        let object = root_model
+            .resources
+            .object
+            .iter()
+            .find(|o| o.id == 2)
+            .unwrap();
+        assert!(object.mesh.is_some());
+        assert!(object.components.is_none());
+    }
+
     #[cfg(feature = "io-memory-optimized-read")]
     #[test]
     fn test_pull_based_thumbnails() {
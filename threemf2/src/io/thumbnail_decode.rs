@@ -0,0 +1,51 @@
+use image::DynamicImage;
+
+use crate::io::{ThreemfPackage, thumbnail_handle::ImageFormat};
+
+impl ThreemfPackage {
+    /// Decodes the thumbnail stored at `path` in [ThreemfPackage::thumbnails].
+    ///
+    /// Thumbnails are kept as raw bytes when a package is read, so they are only decoded when
+    /// requested through this method. Returns `None` if there is no thumbnail at `path` or if
+    /// its data cannot be decoded. PNG and JPEG thumbnails are supported, the two formats
+    /// allowed by the 3MF Core specification.
+    pub fn decode_thumbnail(&self, path: &str) -> Option<DynamicImage> {
+        let thumbnail = self.thumbnails.get(path)?;
+        let format = match thumbnail.format {
+            ImageFormat::Png => image::ImageFormat::Png,
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+            ImageFormat::Unknown => image::guess_format(&thumbnail.data).ok()?,
+        };
+
+        image::load_from_memory_with_format(&thumbnail.data, format).ok()
+    }
+}
+
+#[cfg(all(test, feature = "io-memory-optimized-read"))]
+mod tests {
+    use crate::io::{ThreemfPackage, thumbnail_handle::ImageFormat};
+
+    use std::{fs::File, path::PathBuf};
+
+    #[test]
+    fn decode_thumbnail_test() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mesh-composedpart-separate-model-files.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            true,
+        )
+        .unwrap();
+
+        let mut formats = vec![];
+        for (path, thumbnail) in &package.thumbnails {
+            let image = package.decode_thumbnail(path).unwrap();
+            assert!(image.width() > 0 && image.height() > 0);
+            formats.push(thumbnail.format.clone());
+        }
+        assert!(formats.contains(&ImageFormat::Png));
+        assert!(formats.contains(&ImageFormat::Jpeg));
+
+        assert!(package.decode_thumbnail("/Metadata/missing.png").is_none());
+    }
+}
@@ -0,0 +1,295 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::{
+    core::mesh::{self, Mesh},
+    io::{
+        ThreemfPackage,
+        error::Error,
+        thumbnail_handle::{ImageFormat, ThumbnailHandle},
+    },
+};
+
+/// Path the generated package thumbnail is stored at.
+const GENERATED_THUMBNAIL_PATH: &str = "/Metadata/thumbnail.png";
+
+/// Largest width and height of a generated thumbnail, in pixels.
+pub const MAX_THUMBNAIL_SIZE: u32 = 4096;
+
+/// Fraction of the image kept free around the rendered geometry.
+const MARGIN: f64 = 0.05;
+
+/// Direction towards the light in view space, i.e. right, up and towards the viewer.
+const LIGHT: [f64; 3] = [0.3, 0.5, 1.0];
+
+/// Color of a face lit head-on, darker shades are used for faces turned away from the light.
+const BASE_COLOR: [f64; 3] = [90.0, 140.0, 200.0];
+
+impl ThreemfPackage {
+    /// Renders an isometric, flat-shaded preview of the build into an image of the given size.
+    ///
+    /// The build is flattened with [ThreemfPackage::flatten_to_single_object] and scaled to
+    /// fit the image, the background is transparent. Materials are not taken into account.
+    /// If the build cannot be flattened, e.g. because an item references a missing object,
+    /// or if it holds no triangles, a fully transparent image is returned.
+    ///
+    /// The width and height are clamped to [MAX_THUMBNAIL_SIZE].
+    pub fn generate_thumbnail(&self, width: u32, height: u32) -> DynamicImage {
+        let mut image = RgbaImage::new(
+            width.min(MAX_THUMBNAIL_SIZE),
+            height.min(MAX_THUMBNAIL_SIZE),
+        );
+
+        let flattened = self.flatten_to_single_object().ok();
+        let mesh = flattened
+            .as_ref()
+            .and_then(|model| model.resources.object.first())
+            .and_then(|object| object.mesh.as_ref());
+        if let Some(mesh) = mesh {
+            render(mesh, &mut image);
+        }
+
+        DynamicImage::ImageRgba8(image)
+    }
+
+    /// Generates a thumbnail with [ThreemfPackage::generate_thumbnail] and sets it as the
+    /// package thumbnail at `/Metadata/thumbnail.png` with [ThreemfPackage::set_package_thumbnail].
+    ///
+    /// Fails with [Error::ThumbnailError] if the width or height is larger than
+    /// [MAX_THUMBNAIL_SIZE].
+    pub fn embed_generated_thumbnail(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        if width > MAX_THUMBNAIL_SIZE || height > MAX_THUMBNAIL_SIZE {
+            return Err(Error::ThumbnailError(format!(
+                "thumbnail size {width}x{height} exceeds the maximum of {MAX_THUMBNAIL_SIZE}x{MAX_THUMBNAIL_SIZE}"
+            )));
+        }
+
+        let mut data = Vec::new();
+        self.generate_thumbnail(width, height)
+            .write_to(&mut Cursor::new(&mut data), image::ImageFormat::Png)
+            .map_err(|err| Error::ThumbnailError(err.to_string()))?;
+
+        self.set_package_thumbnail(
+            GENERATED_THUMBNAIL_PATH,
+            ThumbnailHandle {
+                data,
+                format: ImageFormat::Png,
+            },
+        )
+    }
+}
+
+/// Projects a point on the isometric view, returns the horizontal and vertical position
+/// on the view plane and the distance towards the viewer.
+fn project([x, y, z]: [f64; 3]) -> [f64; 3] {
+    [
+        (x - y) / 2f64.sqrt(),
+        (2.0 * z - x - y) / 6f64.sqrt(),
+        (x + y + z) / 3f64.sqrt(),
+    ]
+}
+
+fn render(mesh: &Mesh, image: &mut RgbaImage) {
+    let (width, height) = image.dimensions();
+    let Some(pixels) = (width as usize).checked_mul(height as usize) else {
+        return;
+    };
+    let projected = mesh
+        .vertices
+        .vertex
+        .iter()
+        .map(|v| project([v.x, v.y, v.z]))
+        .collect::<Vec<_>>();
+    if projected.is_empty() || mesh.triangles.triangle.is_empty() || width == 0 || height == 0 {
+        return;
+    }
+
+    let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for p in &projected {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    let usable = [
+        width as f64 * (1.0 - 2.0 * MARGIN),
+        height as f64 * (1.0 - 2.0 * MARGIN),
+    ];
+    let extent = [
+        (max[0] - min[0]).max(f64::EPSILON),
+        (max[1] - min[1]).max(f64::EPSILON),
+    ];
+    let scale = (usable[0] / extent[0]).min(usable[1] / extent[1]);
+    let offset = [
+        (width as f64 - extent[0] * scale) / 2.0,
+        (height as f64 - extent[1] * scale) / 2.0,
+    ];
+    // pixel coordinates, the vertical axis of the image points downwards
+    let to_pixel = |p: &[f64; 3]| {
+        [
+            (p[0] - min[0]) * scale + offset[0],
+            (max[1] - p[1]) * scale + offset[1],
+            p[2],
+        ]
+    };
+
+    let light = {
+        let length = LIGHT.iter().map(|l| l * l).sum::<f64>().sqrt();
+        LIGHT.map(|l| l / length)
+    };
+
+    let mut depth = vec![f64::MIN; pixels];
+    for triangle in &mesh.triangles.triangle {
+        let (Some(a), Some(b), Some(c)) = (
+            projected.get(triangle.v1),
+            projected.get(triangle.v2),
+            projected.get(triangle.v3),
+        ) else {
+            continue;
+        };
+
+        // shade by the angle between the face normal in view space and the light
+        let normal = mesh::face_normal(*a, *b, *c);
+        let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+        if length == 0.0 {
+            continue;
+        }
+        let intensity = 0.35
+            + 0.65 * (normal[0] * light[0] + normal[1] * light[1] + normal[2] * light[2]).abs()
+                / length;
+        let color = Rgba([
+            (BASE_COLOR[0] * intensity) as u8,
+            (BASE_COLOR[1] * intensity) as u8,
+            (BASE_COLOR[2] * intensity) as u8,
+            255,
+        ]);
+
+        let [a, b, c] = [to_pixel(a), to_pixel(b), to_pixel(c)];
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        if area.abs() < f64::EPSILON {
+            continue;
+        }
+
+        let x_range = a[0].min(b[0]).min(c[0]).floor().max(0.0) as u32
+            ..(a[0].max(b[0]).max(c[0]).ceil().max(0.0) as u32).min(width);
+        let y_range = a[1].min(b[1]).min(c[1]).floor().max(0.0) as u32
+            ..(a[1].max(b[1]).max(c[1]).ceil().max(0.0) as u32).min(height);
+        for y in y_range {
+            for x in x_range.clone() {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let w0 = ((b[0] - px) * (c[1] - py) - (b[1] - py) * (c[0] - px)) / area;
+                let w1 = ((c[0] - px) * (a[1] - py) - (c[1] - py) * (a[0] - px)) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let z = w0 * a[2] + w1 * b[2] + w2 * c[2];
+                let index = y as usize * width as usize + x as usize;
+                if z > depth[index] {
+                    depth[index] = z;
+                    image.put_pixel(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        core::model::Unit,
+        io::{ModelBuilder, ThreemfPackage},
+    };
+
+    fn cube_package() -> ThreemfPackage {
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[
+                    [0.0, 0.0, 0.0],
+                    [10.0, 0.0, 0.0],
+                    [10.0, 10.0, 0.0],
+                    [0.0, 10.0, 0.0],
+                    [0.0, 0.0, 10.0],
+                    [10.0, 0.0, 10.0],
+                    [10.0, 10.0, 10.0],
+                    [0.0, 10.0, 10.0],
+                ]);
+                obj.add_triangles(&[
+                    [3, 2, 1],
+                    [1, 0, 3],
+                    [4, 5, 6],
+                    [6, 7, 4],
+                    [0, 1, 5],
+                    [5, 4, 0],
+                    [1, 2, 6],
+                    [6, 5, 1],
+                    [2, 3, 7],
+                    [7, 6, 2],
+                    [3, 0, 4],
+                    [4, 7, 3],
+                ]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(id).unwrap();
+
+        ThreemfPackage::from(builder.build().unwrap())
+    }
+
+    #[test]
+    fn generate_thumbnail_test() {
+        let image = cube_package().generate_thumbnail(64, 48).into_rgba8();
+        assert_eq!(image.dimensions(), (64, 48));
+
+        // the cube is centered and the corners stay transparent
+        assert_eq!(image.get_pixel(32, 24).0[3], 255);
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+        assert_eq!(image.get_pixel(63, 47).0[3], 0);
+
+        // the visible faces are shaded differently
+        let mut colors = image
+            .pixels()
+            .filter(|p| p.0[3] == 255)
+            .map(|p| p.0)
+            .collect::<Vec<_>>();
+        colors.sort();
+        colors.dedup();
+        assert!(colors.len() >= 2);
+    }
+
+    #[test]
+    fn embed_generated_thumbnail_test() {
+        let mut package = cube_package();
+        package.embed_generated_thumbnail(32, 32).unwrap();
+
+        let thumbnail = &package.thumbnails["/Metadata/thumbnail.png"];
+        let image = image::load_from_memory(&thumbnail.data).unwrap();
+        assert_eq!((image.width(), image.height()), (32, 32));
+        assert!(
+            package.relationships["_rels/.rels"]
+                .relationships
+                .iter()
+                .any(|r| r.target == "/Metadata/thumbnail.png")
+        );
+    }
+
+    #[test]
+    fn thumbnail_size_limit_test() {
+        use super::MAX_THUMBNAIL_SIZE;
+        use crate::io::error::Error;
+
+        let mut package = cube_package();
+        let image = package.generate_thumbnail(MAX_THUMBNAIL_SIZE + 1, 1);
+        assert_eq!((image.width(), image.height()), (MAX_THUMBNAIL_SIZE, 1));
+
+        let result = package.embed_generated_thumbnail(u32::MAX, u32::MAX);
+        assert!(matches!(result, Err(Error::ThumbnailError(_))));
+        assert!(package.thumbnails.is_empty());
+    }
+}
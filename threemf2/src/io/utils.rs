@@ -7,6 +7,44 @@ pub(crate) fn try_strip_leading_slash(target: &str) -> &str {
     }
 }
 
+/// Returns the folder of the part a relationship file belongs to, e.g. `3D` for
+/// `/3D/_rels/3dmodel.model.rels` and an empty string for the package relationships
+/// in `_rels/.rels`.
+pub(crate) fn relationships_source_folder(rels_path: &str) -> &str {
+    let path = try_strip_leading_slash(rels_path);
+    match path.rsplit_once("_rels/") {
+        Some((folder, _)) => folder.trim_end_matches('/'),
+        None => "",
+    }
+}
+
+/// Resolves `target` against the folder `base` into a package-absolute part name,
+/// e.g. `Objects/../part.model` in `/3D` becomes `/3D/part.model`.
+/// Targets starting with a slash are already absolute and ignore `base`.
+pub(crate) fn resolve_part_name(base: &str, target: &str) -> String {
+    let base = if target.starts_with('/') { "" } else { base };
+    let mut segments = vec![];
+    for segment in base.split('/').chain(target.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+/// Returns the part name of the part a relationship file belongs to, e.g.
+/// `/3D/3dmodel.model` for `/3D/_rels/3dmodel.model.rels` and `/` for the package
+/// relationships in `_rels/.rels`.
+pub(crate) fn relationships_source_part(rels_path: &str) -> String {
+    let file = rels_path.rsplit_once("_rels/").map_or("", |(_, file)| file);
+    let part = file.strip_suffix(".rels").unwrap_or(file);
+    resolve_part_name(relationships_source_folder(rels_path), part)
+}
+
 /// Extracts xmlns attribute declarations from an XML element attribute definitions
 pub fn parse_xmlns_attributes(tag_content: &str) -> Vec<XmlNamespace> {
     let mut attributes = Vec::new();
@@ -64,10 +102,443 @@ pub fn parse_xmlns_attributes(tag_content: &str) -> Vec<XmlNamespace> {
     attributes
 }
 
+/// Returns the qualified names of the attributes of the `<model>` element in document order,
+/// including the namespace declarations, e.g. `["xmlns", "unit", "xml:lang", "xmlns:p"]`.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub(crate) fn parse_model_attribute_names(xml: &str) -> Vec<String> {
+    let Some(start) = find_model_start(xml) else {
+        return vec![];
+    };
+    let Some(end) = find_tag_end(xml, start) else {
+        return vec![];
+    };
+    let name = tag_name(&xml[start + 1..]);
+
+    tag_attributes(&xml[start + 1 + name.len()..end - 1])
+        .into_iter()
+        .map(|(name, _)| name.to_owned())
+        .collect()
+}
+
+/// Returns whether the whitespace of every `<metadata>` element of the `<model>` element
+/// must be preserved according to `xml:space`, in document order.
+///
+/// `xml:space` is inherited, so a `<metadata>` element without the attribute uses the value
+/// of the `<model>` element. `xml:space="default"` and a missing attribute are `false`.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub(crate) fn parse_metadata_xml_space(xml: &str) -> Vec<bool> {
+    fn xml_space(tag: &str, name: &str) -> Option<bool> {
+        tag_attributes(&tag[1 + name.len()..])
+            .into_iter()
+            .find(|(attribute, _)| *attribute == "xml:space")
+            .map(|(_, value)| value == "preserve")
+    }
+
+    let mut preserve = Vec::new();
+
+    let Some(model_start) = find_model_start(xml) else {
+        return preserve;
+    };
+    let Some(mut pos) = find_tag_end(xml, model_start) else {
+        return preserve;
+    };
+    let model_tag = &xml[model_start..pos];
+    let model_preserve = xml_space(model_tag, tag_name(&model_tag[1..])).unwrap_or_default();
+
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+
+        let skip_to = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else if rest.starts_with("</") {
+            ">"
+        } else {
+            ""
+        };
+        if !skip_to.is_empty() {
+            match rest.find(skip_to) {
+                Some(end) => {
+                    pos = start + end + skip_to.len();
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        let Some(tag_end) = find_tag_end(xml, start) else {
+            break;
+        };
+        let name = tag_name(&rest[1..]);
+        // metadata elements precede the resources of the model
+        if local_name(name) != "metadata" {
+            break;
+        }
+        preserve.push(xml_space(&xml[start..tag_end], name).unwrap_or(model_preserve));
+        pos = tag_end;
+    }
+
+    preserve
+}
+
+/// Returns the index of the start tag of the `<model>` element, with or without a prefix.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn find_model_start(xml: &str) -> Option<usize> {
+    xml.match_indices('<')
+        .map(|(index, _)| index)
+        .find(|&index| local_name(tag_name(&xml[index + 1..])) == "model")
+}
+
+/// Returns the qualified names and values of the attributes in the content of a start tag
+/// following the element name, in document order.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn tag_attributes(tag: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag;
+    while let Some(name_start) = rest.find(|c: char| !c.is_whitespace() && c != '/' && c != '>') {
+        rest = &rest[name_start..];
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+
+        let Some(value_start) = rest.find(['"', '\'']) else {
+            break;
+        };
+        let quote = &rest[value_start..value_start + 1];
+        let Some(value_len) = rest[value_start + 1..].find(quote) else {
+            break;
+        };
+        attributes.push((name, &rest[value_start + 1..value_start + 1 + value_len]));
+        rest = &rest[value_start + value_len + 2..];
+    }
+
+    attributes
+}
+
+/// Resource elements of the core specification deserialized by this library.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+const KNOWN_RESOURCES: [&str; 2] = ["object", "basematerials"];
+
+/// Collects the child elements of `<resources>` that are not deserialized by this library
+/// as raw XML, e.g. `<m:multiproperties>` of the Materials extension.
+///
+/// The namespace declarations of the prefixes used by an element are added to it,
+/// so the element stays valid when written into a model with different declarations.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub(crate) fn extract_unknown_resources(
+    xml: &str,
+    namespaces: &[XmlNamespace],
+) -> Vec<crate::core::resources::UnknownResource> {
+    use crate::core::resources::UnknownResource;
+
+    let mut unknown = Vec::new();
+
+    let resources_start = xml
+        .match_indices('<')
+        .map(|(index, _)| index)
+        .find(|&index| local_name(tag_name(&xml[index + 1..])) == "resources");
+    let Some(resources_start) = resources_start else {
+        return unknown;
+    };
+    let Some(mut pos) = find_tag_end(xml, resources_start) else {
+        return unknown;
+    };
+    if xml[..pos].ends_with("/>") {
+        return unknown;
+    }
+
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+
+        if let Some(terminator) = markup_terminator(rest) {
+            match rest.find(terminator) {
+                Some(end) => {
+                    pos = start + end + terminator.len();
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        // closing tag of the resources element
+        if rest.starts_with("</") {
+            break;
+        }
+
+        let Some(tag_end) = find_tag_end(xml, start) else {
+            break;
+        };
+        let name = tag_name(&rest[1..]);
+        let end = if xml[..tag_end].ends_with("/>") {
+            tag_end
+        } else {
+            match find_element_end(xml, tag_end, name) {
+                Some(end) => end,
+                None => break,
+            }
+        };
+
+        if !is_known_resource(name, namespaces) {
+            unknown.push(UnknownResource(with_namespace_declarations(
+                &xml[start..end],
+                name,
+                namespaces,
+            )));
+        }
+        pos = end;
+    }
+
+    unknown
+}
+
+/// Returns the terminator of the comment, CDATA section or processing instruction at the
+/// start of `rest`, or `None` if `rest` starts with a tag.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn markup_terminator(rest: &str) -> Option<&'static str> {
+    if rest.starts_with("<!--") {
+        Some("-->")
+    } else if rest.starts_with("<![CDATA[") {
+        Some("]]>")
+    } else if rest.starts_with("<?") {
+        Some("?>")
+    } else {
+        None
+    }
+}
+
+/// Returns the index after the end tag of the element `name` whose start tag ends at
+/// `content_start`, skipping nested elements with the same name.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn find_element_end(xml: &str, content_start: usize, name: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = content_start;
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+
+        if let Some(terminator) = markup_terminator(rest) {
+            pos = start + rest.find(terminator)? + terminator.len();
+            continue;
+        }
+
+        let tag_end = find_tag_end(xml, start)?;
+        if let Some(closing) = rest.strip_prefix("</") {
+            if tag_name(closing) == name {
+                if depth == 0 {
+                    return Some(tag_end);
+                }
+                depth -= 1;
+            }
+        } else if tag_name(&rest[1..]) == name && !xml[..tag_end].ends_with("/>") {
+            depth += 1;
+        }
+        pos = tag_end;
+    }
+    None
+}
+
+/// Returns the qualified name at the start of the tag content following the `<`.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn tag_name(tag: &str) -> &str {
+    let end = tag
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(tag.len());
+    &tag[..end]
+}
+
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn local_name(name: &str) -> &str {
+    name.split_once(':').map_or(name, |(_, local)| local)
+}
+
+/// Calls `f` with the local names of the start tags in the XML in document order, e.g.
+/// `triangle` for `<t:triangle v1="0"/>`, skipping comments, CDATA sections and processing
+/// instructions. The scan stops at the first error returned by `f`.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+pub(crate) fn scan_element_local_names<'a, E>(
+    xml: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut pos = 0;
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+        if let Some(terminator) = markup_terminator(rest) {
+            match rest.find(terminator) {
+                Some(end) => pos = start + end + terminator.len(),
+                None => break,
+            }
+            continue;
+        }
+        pos = start + 1;
+        if !rest.starts_with("</") {
+            f(local_name(tag_name(&rest[1..])))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the index after the `>` closing the tag starting at `start`,
+/// ignoring any `>` within attribute values.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn find_tag_end(xml: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (index, ch) in xml[start..].char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if q == ch => quote = None,
+            (None, '>') => return Some(start + index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn is_known_resource(name: &str, namespaces: &[XmlNamespace]) -> bool {
+    use crate::threemf_namespaces::CORE_NS;
+
+    let (prefix, local) = match name.split_once(':') {
+        Some((prefix, local)) => (Some(prefix), local),
+        None => (None, name),
+    };
+    let uri = namespaces
+        .iter()
+        .find(|ns| ns.prefix.as_deref() == prefix)
+        .map_or(CORE_NS, |ns| ns.uri.as_str());
+
+    uri == CORE_NS && KNOWN_RESOURCES.contains(&local)
+}
+
+/// Adds the declarations of the namespace prefixes used within the element to its start tag,
+/// unless the element declares them already.
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+fn with_namespace_declarations(element: &str, name: &str, namespaces: &[XmlNamespace]) -> String {
+    let start_tag_end = find_tag_end(element, 0).unwrap_or(element.len());
+    let start_tag = &element[..start_tag_end];
+
+    let mut declarations = String::new();
+    let mut declared = Vec::new();
+    for namespace in namespaces {
+        let Some(prefix) = namespace.prefix.as_deref() else {
+            continue;
+        };
+        if declared.contains(&prefix) || start_tag.contains(&format!("xmlns:{prefix}=")) {
+            continue;
+        }
+
+        let used =
+            element.contains(&format!("<{prefix}:")) || element.contains(&format!(" {prefix}:"));
+        if used {
+            declarations.push_str(&format!(r#" xmlns:{prefix}="{}""#, namespace.uri));
+            declared.push(prefix);
+        }
+    }
+
+    let mut element = element.to_owned();
+    element.insert_str(1 + name.len(), &declarations);
+    element
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_relationships_source_folder() {
+        assert_eq!(relationships_source_folder("_rels/.rels"), "");
+        assert_eq!(
+            relationships_source_folder("/3D/_rels/3dmodel.model.rels"),
+            "3D"
+        );
+        assert_eq!(
+            relationships_source_folder("/3D/Objects/_rels/part.model.rels"),
+            "3D/Objects"
+        );
+    }
+
+    #[test]
+    fn test_relationships_source_part() {
+        assert_eq!(relationships_source_part("_rels/.rels"), "/");
+        assert_eq!(
+            relationships_source_part("/3D/_rels/3dmodel.model.rels"),
+            "/3D/3dmodel.model"
+        );
+        assert_eq!(
+            relationships_source_part("3D/Objects/_rels/part.model.rels"),
+            "/3D/Objects/part.model"
+        );
+    }
+
+    #[test]
+    fn test_resolve_part_name() {
+        assert_eq!(
+            resolve_part_name("", "3D/3dmodel.model"),
+            "/3D/3dmodel.model"
+        );
+        assert_eq!(
+            resolve_part_name("3D", "Objects/part.model"),
+            "/3D/Objects/part.model"
+        );
+        assert_eq!(
+            resolve_part_name("/3D/Objects", "../Metadata/./part.png"),
+            "/3D/Metadata/part.png"
+        );
+        assert_eq!(
+            resolve_part_name("3D", "/3D/Objects/part.model"),
+            "/3D/Objects/part.model"
+        );
+        assert_eq!(resolve_part_name("", "../part.model"), "/part.model");
+    }
+
     #[test]
     fn test_parse_xmlns_attributes_simple() {
         let xml = r#"<model xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" unit="millimeter">"#;
@@ -129,4 +600,117 @@ mod tests {
             ]
         );
     }
+
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    #[test]
+    fn test_parse_model_attribute_names() {
+        let xml = r#"<?xml version="1.0"?><model unit="millimeter" xml:lang='en-US' xmlns="http://core"
+            xmlns:p="http://prod" requiredextensions="p"><metadata name="a">x="1"</metadata></model>"#;
+        assert_eq!(
+            parse_model_attribute_names(xml),
+            vec!["unit", "xml:lang", "xmlns", "xmlns:p", "requiredextensions"]
+        );
+
+        let xml = r#"<m:model xmlns:m="http://core" unit="inch"><m:resources/></m:model>"#;
+        assert_eq!(parse_model_attribute_names(xml), vec!["xmlns:m", "unit"]);
+
+        assert_eq!(
+            parse_model_attribute_names(r#"<models a="1"/><model/>"#),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            parse_model_attribute_names("<resources/>"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    #[test]
+    fn test_parse_metadata_xml_space() {
+        let xml = r#"<?xml version="1.0"?><model xmlns="http://core"><metadata name="a" xml:space="preserve"> a </metadata><!-- <metadata/> --><metadata name="b"> b </metadata><metadata name="c" xml:space='default'/><resources><metadata xml:space="preserve"/></resources></model>"#;
+        assert_eq!(parse_metadata_xml_space(xml), vec![true, false, false]);
+
+        let xml = r#"<model xml:space="preserve"><metadata name="a"><![CDATA[ <a> ]]></metadata><metadata name="b" xml:space="default"/><build/></model>"#;
+        assert_eq!(parse_metadata_xml_space(xml), vec![true, false]);
+
+        assert_eq!(parse_metadata_xml_space("<model/>"), Vec::<bool>::new());
+    }
+
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    #[test]
+    fn test_scan_element_local_names() {
+        let xml = r#"<?xml version="1.0"?><model><!-- <object/> --><m:colorgroup><m:color/></m:colorgroup><metadata><![CDATA[<triangle/>]]></metadata><object/></model>"#;
+        let mut names = vec![];
+        scan_element_local_names(xml, |name| {
+            names.push(name);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+        assert_eq!(
+            names,
+            vec!["model", "colorgroup", "color", "metadata", "object"]
+        );
+
+        let mut scanned = 0;
+        let result = scan_element_local_names(xml, |name| {
+            scanned += 1;
+            if name == "colorgroup" {
+                Err(name)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err("colorgroup"));
+        assert_eq!(scanned, 2);
+    }
+
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    #[test]
+    fn test_extract_unknown_resources() {
+        let xml = r##"<model xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02"><resources><basematerials id="1"><base name="Red" displaycolor="#FF0000"/></basematerials><!-- <m:ignored/> --><m:colorgroup id="2"><m:color color="#00FF00"/></m:colorgroup><m:multiproperties id="3" pids="1 2"/><object id="4" name="a>b"><mesh></mesh></object></resources><build></build></model>"##;
+        let namespaces = parse_xmlns_attributes(xml);
+
+        let unknown = extract_unknown_resources(xml, &namespaces)
+            .into_iter()
+            .map(|r| r.0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unknown,
+            vec![
+                r##"<m:colorgroup xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02" id="2"><m:color color="#00FF00"/></m:colorgroup>"##,
+                r#"<m:multiproperties xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02" id="3" pids="1 2"/>"#,
+            ]
+        );
+
+        // already declared namespaces are not added again
+        let again = format!("<model><resources>{}</resources></model>", unknown[0]);
+        let extracted = extract_unknown_resources(&again, &parse_xmlns_attributes(&again));
+        assert_eq!(extracted[0].0, unknown[0]);
+
+        // nested elements with the same name and closing tags sharing a prefix of the name
+        let xml = r#"<model xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:x="urn:example"><resources><x:group id="1"><x:group><x:item/></x:group><x:item/></x:group><x:color id="2"><x:colorgroup></x:colorgroup></x:color></resources></model>"#;
+        let unknown = extract_unknown_resources(xml, &parse_xmlns_attributes(xml))
+            .into_iter()
+            .map(|r| r.0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unknown,
+            vec![
+                r#"<x:group xmlns:x="urn:example" id="1"><x:group><x:item/></x:group><x:item/></x:group>"#,
+                r#"<x:color xmlns:x="urn:example" id="2"><x:colorgroup></x:colorgroup></x:color>"#,
+            ]
+        );
+    }
 }
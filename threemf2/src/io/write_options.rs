@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use zip::write::SimpleFileOptions;
+
+use crate::core::{
+    mesh::Mesh,
+    model::{Model, Unit},
+};
+
+/// Options that control how the models of a 3mf package are adjusted while writing.
+///
+/// The [Default] options write the models as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Some consumers ignore `recommendedextensions` and then fail to render the data
+    /// that depends on them, e.g. triangle sets. If set to true, the recommended extension
+    /// prefixes of every model are moved into its `requiredextensions` on write.
+    pub promote_recommended_to_required: bool,
+
+    /// The schemas require at least one child in `<trianglesets>`, `<balls>` and `<beamsets>`.
+    /// If set to true, such containers without children are left out on write, so models
+    /// read from other producers or assembled by hand don't emit them as empty elements.
+    /// [ModelBuilder](crate::io::ModelBuilder) never creates empty containers.
+    pub omit_empty_containers: bool,
+
+    /// If set to true, the attributes and namespace declarations of the `<model>` element of
+    /// models read from a file are written in the order they were read, see
+    /// [ThreemfPackage::get_attribute_order_on_model](crate::io::ThreemfPackage::get_attribute_order_on_model).
+    /// Useful to diff the written parts against the source file. Attributes the library
+    /// doesn't write, e.g. `xml:lang`, are still dropped.
+    pub preserve_attribute_order: bool,
+
+    /// Models without a unit are in millimeters and don't write the `unit` attribute.
+    /// If set to true, `unit="millimeter"` is written for them, for strict consumers
+    /// requiring the attribute. Models with a unit always write it.
+    pub always_emit_unit: bool,
+
+    /// How every part of the package is compressed in the archive, including the
+    /// thumbnails and the parts unknown to this library.
+    pub compression_method: CompressionMethod,
+
+    /// Compression level of [CompressionMethod::Deflated], from 0 (fastest) to 9 (smallest).
+    /// `None` uses the default level of the deflate encoder. The level is ignored for
+    /// [CompressionMethod::Stored], an out of range level fails the write.
+    pub compression_level: Option<i64>,
+}
+
+/// How the parts of a 3mf package are compressed in the archive, see
+/// [WriteOptions::compression_method].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompressionMethod {
+    /// The parts are stored uncompressed, the fastest to write and read.
+    Stored,
+
+    /// The parts are compressed with deflate, which every 3mf consumer supports.
+    #[default]
+    Deflated,
+}
+
+impl WriteOptions {
+    /// Returns the model to be written according to these options.
+    pub(crate) fn apply<'a>(&self, model: &'a Model) -> Cow<'a, Model> {
+        let mut model = Cow::Borrowed(model);
+
+        if self.always_emit_unit && model.unit.is_none() {
+            model.to_mut().unit = Some(Unit::Millimeter);
+        }
+
+        if self.promote_recommended_to_required && model.recommendedextensions.is_some() {
+            promote_recommended_to_required(model.to_mut());
+        }
+
+        if self.omit_empty_containers
+            && model
+                .resources
+                .object
+                .iter()
+                .filter_map(|o| o.mesh.as_ref())
+                .any(has_empty_containers)
+        {
+            for mesh in model
+                .to_mut()
+                .resources
+                .object
+                .iter_mut()
+                .filter_map(|o| o.mesh.as_mut())
+            {
+                remove_empty_containers(mesh);
+            }
+        }
+
+        model
+    }
+
+    /// Returns the options of the archive entries of the parts.
+    pub(crate) fn file_options(&self) -> SimpleFileOptions {
+        let (method, level) = match self.compression_method {
+            CompressionMethod::Stored => (zip::CompressionMethod::Stored, None),
+            CompressionMethod::Deflated => {
+                (zip::CompressionMethod::Deflated, self.compression_level)
+            }
+        };
+        SimpleFileOptions::default()
+            .compression_method(method)
+            .compression_level(level)
+    }
+}
+
+fn promote_recommended_to_required(model: &mut Model) {
+    let mut required = model
+        .requiredextensions
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+
+    if let Some(recommended) = model.recommendedextensions.take() {
+        for prefix in recommended.split_whitespace() {
+            if !required.iter().any(|p| p == prefix) {
+                required.push(prefix.to_owned());
+            }
+        }
+    }
+
+    model.requiredextensions = if required.is_empty() {
+        None
+    } else {
+        Some(required.join(" "))
+    };
+}
+
+fn has_empty_containers(mesh: &Mesh) -> bool {
+    mesh.trianglesets
+        .as_ref()
+        .is_some_and(|sets| sets.trianglesets.is_empty())
+        || mesh.beamlattice.as_ref().is_some_and(|lattice| {
+            lattice.balls.as_ref().is_some_and(|b| b.ball.is_empty())
+                || lattice
+                    .beamsets
+                    .as_ref()
+                    .is_some_and(|s| s.beamset.is_empty())
+        })
+}
+
+fn remove_empty_containers(mesh: &mut Mesh) {
+    if mesh
+        .trianglesets
+        .as_ref()
+        .is_some_and(|sets| sets.trianglesets.is_empty())
+    {
+        mesh.trianglesets = None;
+    }
+
+    if let Some(lattice) = &mut mesh.beamlattice {
+        if lattice.balls.as_ref().is_some_and(|b| b.ball.is_empty()) {
+            lattice.balls = None;
+        }
+        if lattice
+            .beamsets
+            .as_ref()
+            .is_some_and(|s| s.beamset.is_empty())
+        {
+            lattice.beamsets = None;
+        }
+    }
+}
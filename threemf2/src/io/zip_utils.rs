@@ -6,9 +6,10 @@ use crate::io::{
     error::Error,
     parse_xmlns_attributes,
     relationship::Relationships,
+    utils,
 };
 
-use crate::core::model::Model;
+use crate::core::{metadata::Preserve, model::Model};
 
 use std::ffi::OsStr;
 use std::io::{Read, Seek};
@@ -68,23 +69,42 @@ impl XmlDeserializer {
         }
     }
 
-    pub(crate) fn deserialize_model<R: Read>(
+    pub(crate) fn deserialize_model(
         &self,
-        reader: &mut R,
+        xml_string: &str,
     ) -> Result<(Model, Vec<XmlNamespace>), Error> {
-        let mut xml_string = String::new();
-        reader.read_to_string(&mut xml_string)?;
+        self.deserialize_model_with_attribute_order(xml_string)
+            .map(|(model, namespaces, _)| (model, namespaces))
+    }
 
-        let namespaces = parse_xmlns_attributes(&xml_string);
+    /// Same as [XmlDeserializer::deserialize_model] but also returns the names of the
+    /// attributes of the `<model>` element in their original order.
+    pub(crate) fn deserialize_model_with_attribute_order(
+        &self,
+        xml_string: &str,
+    ) -> Result<(Model, Vec<XmlNamespace>, Vec<String>), Error> {
+        let namespaces = parse_xmlns_attributes(xml_string);
 
-        let model = match self {
+        let mut model = match self {
             #[cfg(feature = "io-memory-optimized-read")]
-            XmlDeserializer::MemoryOptimized => instant_xml::from_str::<Model>(&xml_string)?,
+            XmlDeserializer::MemoryOptimized => instant_xml::from_str::<Model>(xml_string)?,
             #[cfg(feature = "io-speed-optimized-read")]
-            XmlDeserializer::SpeedOptimized => serde_roxmltree::from_str::<Model>(&xml_string)?,
+            XmlDeserializer::SpeedOptimized => serde_roxmltree::from_str::<Model>(xml_string)?,
         };
+        model.resources.unknown_resources =
+            utils::extract_unknown_resources(xml_string, &namespaces);
+        let xml_space = utils::parse_metadata_xml_space(xml_string);
+        for (index, metadata) in model.metadata.iter_mut().enumerate() {
+            // kept as `preserve="1"` so the whitespace survives writing the model again
+            if metadata.preserve.is_none() && xml_space.get(index).copied().unwrap_or_default() {
+                metadata.preserve = Some(Preserve(true));
+            }
+            metadata.normalize_value();
+        }
+
+        let attribute_order = utils::parse_model_attribute_names(xml_string);
 
-        Ok((model, namespaces))
+        Ok((model, namespaces, attribute_order))
     }
 }
 
@@ -106,19 +126,19 @@ fn parse_content_types<R: Read + Seek>(
     zip: &mut ZipArchive<R>,
     deserializer: XmlDeserializer,
 ) -> Result<(ContentTypes, String), Error> {
-    let content_types_file = zip.by_name("[Content_Types].xml");
-    match content_types_file {
-        Ok(mut file) => {
-            let mut xml_string = String::new();
-            file.read_to_string(&mut xml_string)?;
-            let content_types = deserializer.deserialize_content_types(xml_string.as_bytes())?;
-            Ok((content_types, xml_string))
-        }
-        Err(err) => Err(Error::Zip(err)),
-    }
+    const CONTENT_TYPES_FILENAME: &str = "[Content_Types].xml";
+
+    let mut parse = || -> Result<_, Error> {
+        let mut file = zip.by_name(CONTENT_TYPES_FILENAME)?;
+        let mut xml_string = String::new();
+        file.read_to_string(&mut xml_string)?;
+        let content_types = deserializer.deserialize_content_types(xml_string.as_bytes())?;
+        Ok((content_types, xml_string))
+    };
+    parse().map_err(|err| err.reading_part(CONTENT_TYPES_FILENAME))
 }
 
-fn determine_relationships_extension(content_types: &ContentTypes) -> String {
+pub(crate) fn determine_relationships_extension(content_types: &ContentTypes) -> String {
     content_types
         .defaults
         .iter()
@@ -127,7 +147,10 @@ fn determine_relationships_extension(content_types: &ContentTypes) -> String {
         .unwrap_or_else(|| "rels".to_string())
 }
 
-/// Find all relationship files in the archive (excluding the root relationships file)
+/// Find all relationship files in the archive (excluding the root relationships file).
+///
+/// Relationship files of a part live in the `_rels` folder next to it, so only files with
+/// the relationships extension placed directly in a `_rels` folder are returned.
 pub(crate) fn discover_relationship_files<R: Read + Seek>(
     zip: &mut ZipArchive<R>,
     rels_ext: &str,
@@ -141,6 +164,7 @@ pub(crate) fn discover_relationship_files<R: Read + Seek>(
         if file.is_file()
             && let Some(path) = file.enclosed_name()
             && path.extension() == Some(OsStr::new(rels_ext))
+            && path.parent().and_then(Path::file_name) == Some(OsStr::new("_rels"))
             && path != Path::new(root_rels_filename)
         {
             if let Some(path_str) = path.to_str() {
@@ -168,9 +192,8 @@ pub(crate) fn relationships_from_zip_by_name<R: Read + Seek>(
     zip_filename: &str,
     deserializer: &XmlDeserializer,
 ) -> Result<Relationships, Error> {
-    let rels_file = zip.by_name(zip_filename);
-    match rels_file {
-        Ok(file) => relationships_from_zipfile(file, deserializer),
-        Err(err) => Err(Error::Zip(err)),
-    }
+    zip.by_name(zip_filename)
+        .map_err(Error::from)
+        .and_then(|file| relationships_from_zipfile(file, deserializer))
+        .map_err(|err| err.reading_part(zip_filename))
 }
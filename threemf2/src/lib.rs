@@ -19,7 +19,25 @@
 //! [3MF specification]: https://3mf.io/specification/
 //! [Open Packaging Conventions]: https://standards.iso.org/ittf/PubliclyAvailableStandards/c061796_ISO_IEC_29500-2_2012.zip
 
+//!
+//! ## `no_std` support
+//!
+//! The [`core`] types can be used in `no_std` environments with an allocator by disabling the
+//! default features. Without `std` the following remain available:
+//! - all type definitions of the [`core`] module and [`threemf_namespaces`],
+//! - [`core::transform::Transform`] parsing and formatting of its specification string,
+//! - the geometry helpers [`core::mesh::Mesh::triangle_normal`] and [`core::mesh::Mesh::bounding_box`].
+//!
+//! The following require the `std` feature, which is implied by every other feature:
+//! - the `write`, `memory-optimized-read` and `speed-optimized-read` (de)serialization features,
+//! - the [`io`] module and all `io-*` features,
+//! - `Mesh::vertex_normals`, since normalizing needs the floating point square root of `std`.
+//! - `Transform::from_rotation_axis`, since it needs the trigonometric functions of `std`.
+
 #![forbid(unsafe_code)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 /// [`core`] module maps and defines the elements in the 3MF Specifications to Rust Types.
 /// The Serialization and Deserialization implementations are also provided in this module.
@@ -40,3 +58,8 @@ pub mod threemf_namespaces;
     feature = "io-lazy-read"
 ))]
 pub mod io;
+
+/// [`interop`] module converts the [`core`] types to other file formats, like the meshes to STL
+/// with the `stl-export` feature.
+#[cfg(feature = "stl-export")]
+pub mod interop;
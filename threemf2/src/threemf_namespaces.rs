@@ -1,3 +1,5 @@
+use alloc::{format, string::String};
+
 /////////////////////////////////////////////////////////////////////////////////////
 ///  Namespaces & Prefixes related to the Core specification and its extensions
 pub const CORE_NS: &str = "http://schemas.microsoft.com/3dmanufacturing/core/2015/02";
@@ -22,6 +24,27 @@ pub const BEAM_LATTICE_BALLS_NS: &str =
     "http://schemas.microsoft.com/3dmanufacturing/beamlattice/balls/2020/07";
 pub const BEAM_LATTICE_BALLS_PREFIX: &str = "b2";
 
+/// Namespace Uris of the 3MF specifications modeled by this library.
+/// The content of other extensions is not deserialized.
+pub const SUPPORTED_NAMESPACES: [&str; 5] = [
+    CORE_NS,
+    CORE_TRIANGLESET_NS,
+    PROD_NS,
+    BEAM_LATTICE_NS,
+    BEAM_LATTICE_BALLS_NS,
+];
+
+/// Returns the namespace Uri of a prefix used by default by this library.
+pub fn default_prefix_uri(prefix: &str) -> Option<&'static str> {
+    match prefix {
+        CORE_TRIANGLESET_PREFIX => Some(CORE_TRIANGLESET_NS),
+        PROD_PREFIX => Some(PROD_NS),
+        BEAM_LATTICE_PREFIX => Some(BEAM_LATTICE_NS),
+        BEAM_LATTICE_BALLS_PREFIX => Some(BEAM_LATTICE_BALLS_NS),
+        _ => None,
+    }
+}
+
 /// Enum representing the different 3MF specifications supported by this library
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ThreemfNamespace {
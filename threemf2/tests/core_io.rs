@@ -101,6 +101,813 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_stored_threemf_package_memory_optimized() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::get_mesh_objects;
+
+        let path = PathBuf::from("./tests/data/mesh-stored.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(get_mesh_objects(&package).count(), 2);
+        assert_eq!(package.root.build.item.len(), 1);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn read_stored_threemf_package_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::get_mesh_objects;
+
+        let path = PathBuf::from("./tests/data/mesh-stored.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(get_mesh_objects(&package).count(), 2);
+        assert_eq!(package.root.build.item.len(), 1);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_unknown_resources_memory_optimized() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::get_mesh_objects;
+
+        let path = PathBuf::from("./tests/data/multiproperties.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        let unknown = &package.root.resources.unknown_resources;
+        assert_eq!(unknown.len(), 2);
+        assert!(unknown[0].0.starts_with(
+            r#"<m:colorgroup xmlns:m="http://schemas.microsoft.com/3dmanufacturing/material/2015/02" id="2">"#
+        ));
+        assert!(unknown[1].0.starts_with("<m:multiproperties"));
+        assert!(unknown[1].0.ends_with("</m:multiproperties>"));
+        assert_eq!(package.root.resources.basematerials.len(), 1);
+        assert_eq!(get_mesh_objects(&package).count(), 1);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn read_unknown_resources_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::get_mesh_objects;
+
+        let path = PathBuf::from("./tests/data/multiproperties.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        let unknown = &package.root.resources.unknown_resources;
+        assert_eq!(unknown.len(), 2);
+        assert!(unknown[0].0.starts_with("<m:colorgroup"));
+        assert!(unknown[1].0.starts_with("<m:multiproperties"));
+        assert_eq!(get_mesh_objects(&package).count(), 1);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_nested_unknown_resources() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::get_mesh_objects;
+
+        let path = PathBuf::from("./tests/data/nested-unknown-resources.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        // nested elements with the same name and names sharing a prefix stay in one resource
+        let unknown = &package.root.resources.unknown_resources;
+        assert_eq!(unknown.len(), 2);
+        assert!(
+            unknown[0]
+                .0
+                .starts_with(r#"<x:group xmlns:x="urn:example:resources" id="1">"#)
+        );
+        assert!(unknown[0].0.ends_with("<x:item />\n    </x:group>"));
+        assert!(
+            unknown[1]
+                .0
+                .starts_with(r#"<x:color xmlns:x="urn:example:resources" id="2">"#)
+        );
+        assert!(unknown[1].0.ends_with("</x:colorgroup>\n    </x:color>"));
+        assert_eq!(get_mesh_objects(&package).count(), 1);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_base_materials_and_color_group() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::object_material_report;
+
+        let path = PathBuf::from("./tests/data/basematerials-and-colorgroup.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        // color groups are not modeled and kept as unknown resources
+        let unknown = &package.root.resources.unknown_resources;
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown[0].0.starts_with("<m:colorgroup"));
+
+        // only the properties referencing the base materials resolve to materials
+        let report = object_material_report(&package);
+        assert_eq!(report.len(), 1);
+        let names = report[0]
+            .materials
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Red", "Blue"]);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn validate_property_references() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/basematerials-and-colorgroup.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+        // the triangles reference both the base materials 1 and the color group 2
+        assert!(package.validate().is_ok());
+
+        let mut dangling = package.clone();
+        dangling.root.resources.object[0].pid = Some(9);
+        assert!(matches!(
+            dangling.validate(),
+            Err(Error::UnknownPropertyGroup {
+                object_id: 3,
+                pid: 9
+            })
+        ));
+
+        let mut dangling = package.clone();
+        let mesh = dangling.root.resources.object[0].mesh.as_mut().unwrap();
+        mesh.triangles.triangle[0].pid = Some(4);
+        assert!(matches!(
+            dangling.validate(),
+            Err(Error::UnknownPropertyGroup {
+                object_id: 3,
+                pid: 4
+            })
+        ));
+
+        // the base materials have two materials
+        let mut out_of_bounds = package.clone();
+        let mesh = out_of_bounds.root.resources.object[0]
+            .mesh
+            .as_mut()
+            .unwrap();
+        mesh.triangles.triangle[1].p1 = Some(2);
+        assert!(matches!(
+            out_of_bounds.validate(),
+            Err(Error::PropertyIndexOutOfBounds {
+                object_id: 3,
+                pid: 1,
+                index: 2
+            })
+        ));
+
+        // indices into the color group aren't checked
+        let mut color_index = package;
+        let mesh = color_index.root.resources.object[0].mesh.as_mut().unwrap();
+        mesh.triangles.triangle[0].p1 = Some(20);
+        assert!(color_index.validate().is_ok());
+    }
+
+    #[cfg(any(
+        feature = "io-memory-optimized-read",
+        feature = "io-speed-optimized-read"
+    ))]
+    fn assert_scientific_notation_package(package: &threemf2::io::ThreemfPackage) {
+        let vertices = &package.root.resources.object[0]
+            .mesh
+            .as_ref()
+            .unwrap()
+            .vertices
+            .vertex;
+        let coords = vertices.iter().map(|v| [v.x, v.y, v.z]).collect::<Vec<_>>();
+        assert_eq!(
+            coords,
+            vec![
+                [0.0, 0.0, 0.0012345],
+                [100.0, -25.0, 0.0],
+                [0.5, 10.0, -0.0375]
+            ]
+        );
+
+        let transform = package.root.build.item[0].transform.as_ref().unwrap();
+        assert_eq!(
+            transform.0,
+            [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 15.0, -0.2, 0.0]
+        );
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_scientific_notation_memory_optimized() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/scientific-notation.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        assert_scientific_notation_package(&package);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn read_scientific_notation_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/scientific-notation.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert_scientific_notation_package(&package);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_object_with_mesh_and_components_fails_by_default() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/object-mesh-and-components.3mf");
+        let reader = File::open(path).unwrap();
+
+        let result = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false);
+
+        match result {
+            Err(Error::ObjectHasMeshAndComponents { id }) => assert_eq!(id, 2),
+            Err(err) => panic!("unexpected error {:?}", err),
+            Ok(_) => panic!("read should fail for an object with mesh and components"),
+        }
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_object_with_mesh_and_components_prefers_mesh() {
+        use threemf2::io::query::{ObjectKind, get_objects};
+        use threemf2::io::{ReadOptions, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/object-mesh-and-components.3mf");
+        let reader = File::open(path).unwrap();
+
+        let options = ReadOptions {
+            prefer_mesh_over_components: true,
+            ..Default::default()
+        };
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer_and_options(
+            reader, false, &options,
+        )
+        .unwrap();
+
+        let object = get_objects(&package).find(|r| r.object.id == 2).unwrap();
+        assert_eq!(object.kind(), ObjectKind::Mesh);
+        assert!(object.object.components.is_none());
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_relative_paths_fails_by_default() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/relative-paths.3mf");
+        let reader = File::open(path).unwrap();
+
+        let result = ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_relative_paths_normalized() {
+        use threemf2::io::query::objects_in_build_order;
+        use threemf2::io::{ReadOptions, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/relative-paths.3mf");
+        let reader = File::open(path).unwrap();
+
+        let options = ReadOptions {
+            normalize_paths: true,
+            ..Default::default()
+        };
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer_and_options(
+            reader, true, &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            package.sub_models.keys().collect::<Vec<_>>(),
+            vec!["/3D/Objects/part.model"]
+        );
+        assert_eq!(
+            package.thumbnails.keys().collect::<Vec<_>>(),
+            vec!["/3D/Objects/Metadata/part.png"]
+        );
+
+        let mut targets = package
+            .relationships
+            .values()
+            .flat_map(|rels| &rels.relationships)
+            .map(|rel| rel.target.as_str())
+            .collect::<Vec<_>>();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                "/3D/3dmodel.model",
+                "/3D/Objects/Metadata/part.png",
+                "/3D/Objects/part.model",
+            ]
+        );
+
+        let items = objects_in_build_order(&package);
+        assert_eq!(items.len(), 2);
+        for (item, object) in items {
+            assert_eq!(item.path(), Some("/3D/Objects/part.model"));
+            assert_eq!(object.unwrap().path, Some("/3D/Objects/part.model"));
+        }
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_with_complexity_limits() {
+        use threemf2::io::{ComplexityLimit, Error, ReadLimits, ReadOptions, ThreemfPackage};
+
+        let read = |limits: ReadLimits| {
+            let path = PathBuf::from("./tests/data/mesh-composedpart.3mf");
+            let options = ReadOptions {
+                limits,
+                ..Default::default()
+            };
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer_and_options(
+                File::open(path).unwrap(),
+                false,
+                &options,
+            )
+        };
+
+        let package = read(ReadLimits {
+            max_objects: Some(4),
+            max_triangles_total: Some(23308),
+        })
+        .unwrap();
+        assert_eq!(package.root.resources.object.len(), 4);
+
+        assert!(matches!(
+            read(ReadLimits {
+                max_objects: Some(3),
+                ..Default::default()
+            }),
+            Err(Error::ComplexityLimitExceeded {
+                kind: ComplexityLimit::Objects,
+                limit: 3
+            })
+        ));
+        assert!(matches!(
+            read(ReadLimits {
+                max_triangles_total: Some(1000),
+                ..Default::default()
+            }),
+            Err(Error::ComplexityLimitExceeded {
+                kind: ComplexityLimit::Triangles,
+                limit: 1000
+            })
+        ));
+    }
+
+    #[cfg(all(feature = "io-lazy-read", feature = "io-memory-optimized-read"))]
+    #[test]
+    fn read_object_with_mesh_and_components_lazy() {
+        use threemf2::io::query::{ObjectKind, ObjectRef};
+        use threemf2::io::{CachePolicy, Error, ReadOptions, ThreemfPackageLazyReader};
+
+        let open = |options: &ReadOptions| {
+            let path = PathBuf::from("./tests/data/object-mesh-and-components.3mf");
+            ThreemfPackageLazyReader::from_reader_with_memory_optimized_deserializer_and_options(
+                File::open(path).unwrap(),
+                CachePolicy::NoCache,
+                options,
+            )
+            .unwrap()
+        };
+
+        let package = open(&ReadOptions::default());
+        assert!(matches!(
+            package.root_model(),
+            Err(Error::ObjectHasMeshAndComponents { id: 2 })
+        ));
+
+        let package = open(&ReadOptions {
+            prefer_mesh_over_components: true,
+            ..Default::default()
+        });
+        let (model, _) = package.root_model().unwrap();
+        let object = model.resources.object.iter().find(|o| o.id == 2).unwrap();
+        let object_ref = ObjectRef { object, path: None };
+        assert_eq!(object_ref.kind(), ObjectKind::Mesh);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn validate_triangles_without_vertices() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/triangles-without-vertices.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        match package.validate() {
+            Err(Error::TrianglesWithoutVertices { object_id }) => assert_eq!(object_id, 1),
+            Err(err) => panic!("unexpected error {:?}", err),
+            Ok(_) => panic!("validate should fail for a mesh with triangles but no vertices"),
+        }
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn validate_triangles_without_vertices_speed_optimized() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/triangles-without-vertices.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert!(matches!(
+            package.validate(),
+            Err(Error::TrianglesWithoutVertices { object_id: 1 })
+        ));
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn validate_other_object_type() {
+        use threemf2::core::object::ObjectType;
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/other-object-type.3mf");
+        let reader = File::open(path).unwrap();
+
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(
+            package.root.resources.object[1].objecttype,
+            Some(ObjectType::Other)
+        );
+        assert!(package.validate().is_ok());
+
+        package.root.resources.object[1].objecttype = Some(ObjectType::Model);
+        assert!(matches!(
+            package.validate(),
+            Err(Error::DegenerateTriangleIndices {
+                object_id: 2,
+                triangle: 0
+            })
+        ));
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn validate_other_object_type_speed_optimized() {
+        use threemf2::core::object::ObjectType;
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/other-object-type.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(
+            package.root.resources.object[1].objecttype,
+            Some(ObjectType::Other)
+        );
+        assert!(package.validate().is_ok());
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn validate_undeclared_extension_prefix() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/undeclared-extension-prefix.3mf");
+        let reader = File::open(path).unwrap();
+
+        let mut package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(package.root.recommendedextensions.as_deref(), Some("ts m"));
+        assert!(matches!(
+            package.validate(),
+            Err(Error::UndeclaredExtensionPrefix(prefix)) if prefix == "m"
+        ));
+
+        package.root.recommendedextensions = Some("ts".to_owned());
+        assert!(package.validate().is_ok());
+        package.root.requiredextensions = Some("p".to_owned());
+        assert!(matches!(
+            package.validate(),
+            Err(Error::UndeclaredExtensionPrefix(prefix)) if prefix == "p"
+        ));
+
+        let path = PathBuf::from("./tests/data/recommended-extensions.3mf");
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .unwrap();
+        assert!(package.validate().is_ok());
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn validate_undeclared_extension_prefix_speed_optimized() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/undeclared-extension-prefix.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert!(matches!(
+            package.validate(),
+            Err(Error::UndeclaredExtensionPrefix(prefix)) if prefix == "m"
+        ));
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_unsupported_required_extensions() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/mgx-core-prod-beamlattice-material.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        // the beam lattice is modeled, the displacement extension is not
+        assert_eq!(
+            package.unsupported_required_extensions(),
+            vec!["http://schemas.3mf.io/3dmanufacturing/displacement/2023/10".to_owned()]
+        );
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_recommended_extensions() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/recommended-extensions.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(package.root.requiredextensions, None);
+        assert_eq!(package.root.recommendedextensions.as_deref(), Some("bl ts"));
+        let mesh = package.root.resources.object[0].mesh.as_ref().unwrap();
+        let identifiers = mesh
+            .trianglesets
+            .as_ref()
+            .unwrap()
+            .trianglesets
+            .iter()
+            .map(|set| set.identifier.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(identifiers, vec!["base", "sides"]);
+        assert_eq!(mesh.beamlattice.as_ref().unwrap().beams.beam.len(), 3);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn read_recommended_extensions_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/recommended-extensions.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert_eq!(package.root.requiredextensions, None);
+        assert_eq!(package.root.recommendedextensions.as_deref(), Some("bl ts"));
+        let mesh = package.root.resources.object[0].mesh.as_ref().unwrap();
+        let identifiers = mesh
+            .trianglesets
+            .as_ref()
+            .unwrap()
+            .trianglesets
+            .iter()
+            .map(|set| set.identifier.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(identifiers, vec!["base", "sides"]);
+        assert_eq!(mesh.beamlattice.as_ref().unwrap().beams.beam.len(), 3);
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn read_metadata_xml_space() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/metadata-xml-space.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        let values = package
+            .root
+            .metadata
+            .iter()
+            .map(|m| {
+                (
+                    m.name.as_str(),
+                    m.value.as_deref(),
+                    m.preserves_whitespace(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                ("Title", Some("  Spaced Title  "), true),
+                ("Designer", Some("Trimmed Designer"), false),
+                ("Description", Some("Default Description"), false),
+                ("Padding", Some("   "), true),
+            ]
+        );
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn read_metadata_xml_space_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+
+        let path = PathBuf::from("./tests/data/metadata-xml-space.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        let values = package
+            .root
+            .metadata
+            .iter()
+            .map(|m| {
+                (
+                    m.name.as_str(),
+                    m.value.as_deref(),
+                    m.preserves_whitespace(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                ("Title", Some("  Spaced Title  "), true),
+                ("Designer", Some("Trimmed Designer"), false),
+                ("Description", Some("Default Description"), false),
+                ("Padding", Some("   "), true),
+            ]
+        );
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn validate_beam_vertex_out_of_range() {
+        use threemf2::core::beamlattice::{BeamLatticeError, LatticeElementKind};
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/beam-vertex-out-of-range.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+
+        match package.validate() {
+            Err(Error::BeamLattice { object_id, source }) => {
+                assert_eq!(object_id, 6);
+                assert_eq!(
+                    source,
+                    BeamLatticeError::VertexIndexOutOfRange {
+                        kind: LatticeElementKind::Beam,
+                        index: 2,
+                    }
+                );
+            }
+            Err(err) => panic!("unexpected error {:?}", err),
+            Ok(_) => panic!("validate should fail for a beam referencing a missing vertex"),
+        }
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn validate_beam_vertex_out_of_range_speed_optimized() {
+        use threemf2::io::{Error, ThreemfPackage};
+
+        let path = PathBuf::from("./tests/data/beam-vertex-out-of-range.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        assert!(matches!(
+            package.validate(),
+            Err(Error::BeamLattice { object_id: 6, .. })
+        ));
+    }
+
+    #[cfg(feature = "io-memory-optimized-read")]
+    #[test]
+    fn resolve_component_declared_after_composed_object() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::{get_components_objects, resolve_component};
+
+        let path = PathBuf::from("./tests/data/component-forward-reference.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, false).unwrap();
+        package.validate().unwrap();
+
+        let composed = get_components_objects(&package).collect::<Vec<_>>();
+        assert_eq!(composed.len(), 1);
+        assert_eq!(composed[0].id, 1);
+
+        let targets = composed[0]
+            .components()
+            .map(|comp| resolve_component(&package, &comp).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(targets.len(), 2);
+        for target in targets {
+            assert_eq!(target.object.id, 5);
+            assert_eq!(target.object.name.as_deref(), Some("Triangle"));
+            assert_eq!(target.path, None);
+        }
+
+        let flattened = package.flatten_to_single_object().unwrap();
+        let mesh = flattened.resources.object[0].mesh.as_ref().unwrap();
+        assert_eq!(mesh.vertices.vertex.len(), 6);
+        assert_eq!(mesh.triangles.triangle.len(), 2);
+        assert_eq!(mesh.vertices.vertex[3].z, 10.0);
+    }
+
+    #[cfg(feature = "io-speed-optimized-read")]
+    #[test]
+    fn resolve_component_declared_after_composed_object_speed_optimized() {
+        use threemf2::io::ThreemfPackage;
+        use threemf2::io::query::{get_components_objects, resolve_component};
+
+        let path = PathBuf::from("./tests/data/component-forward-reference.3mf");
+        let reader = File::open(path).unwrap();
+
+        let package =
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, false).unwrap();
+
+        let composed = get_components_objects(&package).next().unwrap();
+        for comp in composed.components() {
+            let target = resolve_component(&package, &comp).unwrap();
+            assert_eq!(target.object.id, 5);
+        }
+        assert_eq!(
+            package.flatten_to_single_object().unwrap().resources.object[0]
+                .mesh
+                .as_ref()
+                .unwrap()
+                .triangles
+                .triangle
+                .len(),
+            2
+        );
+    }
+
     #[cfg(all(feature = "io-lazy-read", feature = "io-memory-optimized-read"))]
     #[test]
     fn read_threemf_package_lazy_memory_optimized() {
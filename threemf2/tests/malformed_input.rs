@@ -0,0 +1,174 @@
+#[cfg(any(
+    feature = "io-memory-optimized-read",
+    feature = "io-speed-optimized-read"
+))]
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use threemf2::io::{Error, ThreemfPackage};
+    use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+    const MODEL_PATH: &str = "3D/3dmodel.model";
+
+    /// Small deterministic xorshift generator, so failures can be reproduced.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next() % bound as u64) as usize
+        }
+    }
+
+    fn fixture() -> Vec<u8> {
+        std::fs::read("./tests/data/component-forward-reference.3mf").unwrap()
+    }
+
+    fn parts(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut file = archive.by_index(i).unwrap();
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).unwrap();
+                (file.name().to_owned(), data)
+            })
+            .collect()
+    }
+
+    /// Returns the fixture archive with the root model replaced by `model`.
+    fn with_model(model: &[u8]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in parts(&fixture()) {
+            writer
+                .start_file(&name, SimpleFileOptions::default())
+                .unwrap();
+            if name == MODEL_PATH {
+                writer.write_all(model).unwrap();
+            } else {
+                writer.write_all(&data).unwrap();
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn root_model() -> String {
+        let (_, data) = parts(&fixture())
+            .into_iter()
+            .find(|(name, _)| name == MODEL_PATH)
+            .unwrap();
+        String::from_utf8(data).unwrap()
+    }
+
+    /// Reads the bytes with every enabled deserializer, any panic fails the calling test.
+    fn read_all(bytes: &[u8]) -> Vec<Result<ThreemfPackage, Error>> {
+        let mut results = vec![];
+        #[cfg(feature = "io-memory-optimized-read")]
+        results.push(
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+                Cursor::new(bytes),
+                true,
+            ),
+        );
+        #[cfg(feature = "io-speed-optimized-read")]
+        results.push(
+            ThreemfPackage::from_reader_with_speed_optimized_deserializer(Cursor::new(bytes), true),
+        );
+        results
+    }
+
+    #[test]
+    fn read_unmodified_fixture() {
+        for result in read_all(&fixture()) {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn read_truncated_archive_never_panics() {
+        let bytes = fixture();
+        for len in 0..bytes.len() {
+            read_all(&bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn read_corrupted_archive_never_panics() {
+        let bytes = fixture();
+        let mut rng = XorShift(0x3f7a_9c12_55e1_0b4d);
+        for _ in 0..1000 {
+            let mut corrupted = bytes.clone();
+            for _ in 0..=rng.below(4) {
+                let index = rng.below(corrupted.len());
+                corrupted[index] = rng.next() as u8;
+            }
+            read_all(&corrupted);
+        }
+    }
+
+    #[test]
+    fn read_truncated_model_never_panics() {
+        let model = root_model();
+        for len in 0..model.len() {
+            read_all(&with_model(&model.as_bytes()[..len]));
+        }
+    }
+
+    #[test]
+    fn read_corrupted_model_never_panics() {
+        let model = root_model().into_bytes();
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..1000 {
+            let mut corrupted = model.clone();
+            for _ in 0..=rng.below(4) {
+                let index = rng.below(corrupted.len());
+                corrupted[index] = b"<>/=\"' 0-.eE:xa\x00\xff"[rng.below(17)];
+            }
+            read_all(&with_model(&corrupted));
+        }
+    }
+
+    #[test]
+    fn read_invalid_attribute_values_never_panics() {
+        let model = root_model();
+        let values = [
+            "",
+            "x",
+            "-1",
+            "1e999",
+            "NaN",
+            "18446744073709551616",
+            "1 0 0",
+            "1 0 0 0 1 0 0 0 1 0 0 10 1",
+        ];
+
+        let value_starts = model.match_indices("=\"").map(|(index, _)| index + 2);
+        for start in value_starts {
+            let end = start + model[start..].find('"').unwrap();
+            for value in values {
+                let mutated = format!("{}{value}{}", &model[..start], &model[end..]);
+                read_all(&with_model(mutated.as_bytes()));
+            }
+        }
+    }
+
+    #[test]
+    fn read_invalid_transform_returns_error() {
+        let model = root_model().replace(
+            r#"transform="1 0 0 0 1 0 0 0 1 0 0 10""#,
+            r#"transform="1 0 0 0 1 0 0 0 1 0 0""#,
+        );
+        assert!(model.contains(r#"transform="1 0 0 0 1 0 0 0 1 0 0""#));
+
+        for result in read_all(&with_model(model.as_bytes())) {
+            assert!(result.is_err());
+        }
+    }
+}
@@ -74,6 +74,7 @@ mod tests {
                 recommendedextensions: None,
                 metadata: vec![],
                 resources: Resources {
+                    unknown_resources: vec![],
                     object: vec![Object {
                         id: 1,
                         objecttype: Some(ObjectType::Model),
@@ -123,6 +124,7 @@ mod tests {
                         content_type: DefaultContentTypeEnum::Model,
                     },
                 ],
+                overrides: vec![],
             },
         );
 
@@ -180,4 +182,744 @@ mod tests {
             assert!(obj.mesh.is_some());
         }
     }
+
+    #[test]
+    fn roundtrip_large_object_ids_test() {
+        use threemf2::io::ModelBuilder;
+
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.set_next_object_id(2_000_000_000).unwrap();
+        builder.add_build(None).unwrap();
+        let mesh_id = builder
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        let assembly_id = builder
+            .add_components_object(|obj| {
+                obj.add_component(mesh_id);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(assembly_id).unwrap();
+
+        let write_package = ThreemfPackage::from(builder.build().unwrap());
+
+        let mut buf = Cursor::new(Vec::new());
+        write_package
+            .write(&mut buf)
+            .expect("Error writing package");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading package");
+            assert_eq!(package.root, write_package.root);
+            assert_eq!(package.root.resources.object[0].id, 2_000_000_000);
+            assert_eq!(package.root.build.item[0].objectid, 2_000_000_001);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading package");
+            assert_eq!(package.root, write_package.root);
+        }
+    }
+
+    #[test]
+    fn roundtrip_base_materials_test() {
+        use threemf2::io::ModelBuilder;
+
+        let mut builder = ModelBuilder::new(Unit::Millimeter, true);
+        builder.add_build(None).unwrap();
+        let materials = builder
+            .add_base_materials(|m| {
+                m.add_material("Red", "#FF0000FF")
+                    .add_material("Green", "#00FF00");
+            })
+            .unwrap();
+        let mesh_id = builder
+            .add_mesh_object(|obj| {
+                obj.pid(materials).pindex(0);
+                obj.add_vertices(&[
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [0.0, 0.0, 1.0],
+                ]);
+                obj.add_triangles(&[[0, 2, 1], [0, 1, 3]]);
+                obj.add_triangle_with_properties(&[1, 2, 3], materials, &[1, 1, 1]);
+                Ok(())
+            })
+            .unwrap();
+        builder.add_build_item(mesh_id).unwrap();
+
+        let write_package = ThreemfPackage::from(builder.build().unwrap());
+
+        let mut buf = Cursor::new(Vec::new());
+        write_package
+            .write(&mut buf)
+            .expect("Error writing package");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading package");
+            assert_eq!(package.root, write_package.root);
+            assert_eq!(package.root.resources.basematerials[0].base.len(), 2);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading package");
+            assert_eq!(package.root, write_package.root);
+        }
+    }
+
+    #[test]
+    fn roundtrip_sub_model_thumbnail_test() {
+        use std::{
+            fs::File,
+            io::{Read, Seek},
+            path::PathBuf,
+        };
+
+        fn read<R: Read + Seek>(reader: R) -> ThreemfPackage {
+            #[cfg(feature = "io-memory-optimized-read")]
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true);
+            #[cfg(not(feature = "io-memory-optimized-read"))]
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, true);
+
+            package.expect("Error reading package")
+        }
+
+        let path = PathBuf::from("./tests/data/submodel-thumbnail.3mf");
+        let package = read(File::open(path).unwrap());
+        assert!(
+            package
+                .relationships
+                .contains_key("/3D/Objects/_rels/part.model.rels")
+        );
+        assert!(
+            package
+                .thumbnails
+                .contains_key("/3D/Objects/Metadata/part.png")
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        let archive = zip::ZipArchive::new(&mut buf).unwrap();
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "3D/3dmodel.model",
+                "3D/Objects/Metadata/part.png",
+                "3D/Objects/_rels/part.model.rels",
+                "3D/Objects/part.model",
+                "3D/_rels/3dmodel.model.rels",
+                "[Content_Types].xml",
+                "_rels/.rels",
+            ]
+        );
+
+        let reread = read(&mut buf);
+        assert_eq!(reread, package);
+    }
+
+    #[test]
+    fn roundtrip_nested_relationships_test() {
+        use std::{
+            fs::File,
+            io::{Read, Seek},
+            path::PathBuf,
+        };
+
+        fn read<R: Read + Seek>(reader: R) -> ThreemfPackage {
+            #[cfg(feature = "io-memory-optimized-read")]
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true);
+            #[cfg(not(feature = "io-memory-optimized-read"))]
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, true);
+
+            package.expect("Error reading package")
+        }
+
+        let path = PathBuf::from("./tests/data/nested-rels.3mf");
+        let package = read(File::open(path).unwrap());
+
+        let mut rels_paths = package.relationships.keys().collect::<Vec<_>>();
+        rels_paths.sort();
+        assert_eq!(
+            rels_paths,
+            vec![
+                "/3D/Objects/Metadata/_rels/part.png.rels",
+                "/3D/Objects/_rels/part.model.rels",
+                "/3D/_rels/3dmodel.model.rels",
+                "_rels/.rels",
+            ]
+        );
+        assert_eq!(
+            package.relationships["/3D/Objects/Metadata/_rels/part.png.rels"].relationships,
+            vec![Relationship {
+                id: "rel0".to_owned(),
+                target: "/3D/Objects/Metadata/part.xml".to_owned(),
+                relationship_type: RelationshipType::Unknown(
+                    "http://example.com/relationships/source".to_owned()
+                ),
+            }]
+        );
+        assert!(
+            package
+                .unknown_parts
+                .contains_key("/3D/Objects/Metadata/part.xml")
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        let archive = zip::ZipArchive::new(&mut buf).unwrap();
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "3D/3dmodel.model",
+                "3D/Objects/Metadata/_rels/part.png.rels",
+                "3D/Objects/Metadata/part.png",
+                "3D/Objects/Metadata/part.xml",
+                "3D/Objects/_rels/part.model.rels",
+                "3D/Objects/part.model",
+                "3D/_rels/3dmodel.model.rels",
+                "[Content_Types].xml",
+                "_rels/.rels",
+            ]
+        );
+
+        let reread = read(&mut buf);
+        assert_eq!(reread, package);
+    }
+
+    #[test]
+    fn roundtrip_package_builder_sub_model_thumbnail_test() {
+        use threemf2::io::{
+            ModelBuilder, PackageBuilder,
+            thumbnail_handle::{ImageFormat, ThumbnailHandle},
+        };
+
+        let mut root = ModelBuilder::new(Unit::Millimeter, true);
+        root.add_build(None).unwrap();
+        let root_id = root
+            .add_mesh_object(|obj| {
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        root.add_build_item(root_id).unwrap();
+
+        let mut part = ModelBuilder::new(Unit::Millimeter, false);
+        part.add_mesh_object(|obj| {
+            obj.thumbnail("/3D/Objects/Metadata/part.png");
+            obj.add_vertices(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]]);
+            obj.add_triangle(&[0, 1, 2]);
+            Ok(())
+        })
+        .unwrap();
+
+        let thumbnail = ThumbnailHandle {
+            data: std::fs::read("./tests/data/test_thumbnail.png").unwrap(),
+            format: ImageFormat::Png,
+        };
+
+        let mut builder = PackageBuilder::new(root.build().unwrap());
+        builder
+            .add_sub_model("3D/Objects/part.model", part.build().unwrap())
+            .unwrap();
+        builder
+            .add_model_thumbnail(
+                "3D/Objects/part.model",
+                "3D/Objects/Metadata/part.png",
+                thumbnail,
+            )
+            .unwrap();
+        let write_package = builder.build();
+
+        let mut buf = Cursor::new(Vec::new());
+        write_package
+            .write(&mut buf)
+            .expect("Error writing package");
+
+        let archive = zip::ZipArchive::new(&mut buf).unwrap();
+        assert!(
+            archive
+                .file_names()
+                .any(|name| name == "3D/Objects/_rels/part.model.rels")
+        );
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, true)
+                    .expect("Error reading package");
+            assert_eq!(package, write_package);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, true)
+                    .expect("Error reading package");
+            assert_eq!(package, write_package);
+        }
+    }
+
+    #[test]
+    fn roundtrip_object_and_package_thumbnail_test() {
+        use std::{
+            fs::File,
+            io::{Read, Seek},
+            path::PathBuf,
+        };
+
+        fn read<R: Read + Seek>(reader: R) -> ThreemfPackage {
+            #[cfg(feature = "io-memory-optimized-read")]
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true);
+            #[cfg(not(feature = "io-memory-optimized-read"))]
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, true);
+
+            package.expect("Error reading package")
+        }
+
+        fn thumbnail_targets(package: &ThreemfPackage, rels_path: &str) -> Vec<String> {
+            package.relationships[rels_path]
+                .relationships
+                .iter()
+                .filter(|r| r.relationship_type == RelationshipType::Thumbnail)
+                .map(|r| r.target.clone())
+                .collect()
+        }
+
+        let path = PathBuf::from("./tests/data/object-and-package-thumbnail.3mf");
+        let package = read(File::open(path).unwrap());
+
+        let mut keys = package.thumbnails.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["/3D/Thumbnails/object1.png", "/Metadata/thumbnail.png"]
+        );
+        assert_eq!(
+            package.root.resources.object[0].thumbnail.as_deref(),
+            Some("/3D/Thumbnails/object1.png")
+        );
+        assert_eq!(
+            thumbnail_targets(&package, "_rels/.rels"),
+            vec!["/Metadata/thumbnail.png"]
+        );
+        assert_eq!(
+            thumbnail_targets(&package, "/3D/_rels/3dmodel.model.rels"),
+            vec!["/3D/Thumbnails/object1.png"]
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        let mut archive = zip::ZipArchive::new(&mut buf).unwrap();
+        let mut read_entry = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        let root_rels = read_entry("_rels/.rels");
+        assert!(root_rels.contains(r#"Target="/Metadata/thumbnail.png""#));
+        assert!(!root_rels.contains("object1.png"));
+        let model_rels = read_entry("3D/_rels/3dmodel.model.rels");
+        assert!(model_rels.contains(r#"Target="/3D/Thumbnails/object1.png""#));
+        assert!(!model_rels.contains("/Metadata/thumbnail.png"));
+        drop(archive);
+
+        let reread = read(&mut buf);
+        assert_eq!(reread, package);
+    }
+
+    #[test]
+    fn roundtrip_package_builder_object_and_package_thumbnail_test() {
+        use threemf2::io::{
+            ModelBuilder, PackageBuilder,
+            thumbnail_handle::{ImageFormat, ThumbnailHandle},
+        };
+
+        let thumbnail = || ThumbnailHandle {
+            data: std::fs::read("./tests/data/test_thumbnail.png").unwrap(),
+            format: ImageFormat::Png,
+        };
+
+        let mut root = ModelBuilder::new(Unit::Millimeter, true);
+        root.add_build(None).unwrap();
+        let object_id = root
+            .add_mesh_object(|obj| {
+                obj.thumbnail("/3D/Thumbnails/object1.png");
+                obj.add_vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+                obj.add_triangle(&[0, 1, 2]);
+                Ok(())
+            })
+            .unwrap();
+        root.add_build_item(object_id).unwrap();
+
+        let mut builder = PackageBuilder::new(root.build().unwrap());
+        builder
+            .add_package_thumbnail("Metadata/thumbnail.png", thumbnail())
+            .unwrap();
+        builder
+            .add_model_thumbnail("3D/3dmodel.model", "3D/Thumbnails/object1.png", thumbnail())
+            .unwrap();
+        let write_package = builder.build();
+
+        let mut buf = Cursor::new(Vec::new());
+        write_package
+            .write(&mut buf)
+            .expect("Error writing package");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        let package =
+            ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, true);
+        #[cfg(not(feature = "io-memory-optimized-read"))]
+        let package = ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, true);
+        let package = package.expect("Error reading package");
+
+        assert_eq!(package, write_package);
+        assert_eq!(package.thumbnails.len(), 2);
+        assert!(
+            package.relationships["/3D/_rels/3dmodel.model.rels"]
+                .relationships
+                .iter()
+                .any(|r| r.target == "/3D/Thumbnails/object1.png")
+        );
+    }
+
+    #[test]
+    fn roundtrip_unknown_resources_test() {
+        use std::{fs::File, io::Read, path::PathBuf};
+
+        let path = PathBuf::from("./tests/data/multiproperties.3mf");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        #[cfg(not(feature = "io-memory-optimized-read"))]
+        let package = ThreemfPackage::from_reader_with_speed_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        assert_eq!(package.root.resources.unknown_resources.len(), 2);
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        let mut model_xml = String::new();
+        zip::ZipArchive::new(&mut buf)
+            .unwrap()
+            .by_name("3D/3dmodel.model")
+            .unwrap()
+            .read_to_string(&mut model_xml)
+            .unwrap();
+        let multiproperties = model_xml.find("<m:multiproperties").unwrap();
+        let object = model_xml.find("<object").unwrap();
+        assert!(multiproperties < object);
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, package.root);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, package.root);
+        }
+    }
+    #[test]
+    fn roundtrip_content_type_overrides_test() {
+        use std::{fs::File, path::PathBuf};
+
+        use threemf2::io::content_types::OverrideContentTypes;
+
+        let path = PathBuf::from("./tests/data/content-type-override.3mf");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        #[cfg(not(feature = "io-memory-optimized-read"))]
+        let package = ThreemfPackage::from_reader_with_speed_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        assert_eq!(
+            package.content_types.overrides,
+            vec![OverrideContentTypes {
+                part_name: "/3D/3dmodel.model".to_owned(),
+                content_type: DefaultContentTypeEnum::Model,
+            }]
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.content_types, package.content_types);
+            assert_eq!(
+                reread
+                    .content_types
+                    .content_type_for_part("/3D/3dmodel.model"),
+                Some(&DefaultContentTypeEnum::Model)
+            );
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.content_types, package.content_types);
+            assert_eq!(
+                reread
+                    .content_types
+                    .content_type_for_part("/3D/3dmodel.model"),
+                Some(&DefaultContentTypeEnum::Model)
+            );
+        }
+    }
+    #[test]
+    fn roundtrip_item_path_to_root_model_test() {
+        use std::{fs::File, io::Read, path::PathBuf};
+
+        use threemf2::io::query::get_items;
+
+        let path = PathBuf::from("./tests/data/item-path-root-model.3mf");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        #[cfg(not(feature = "io-memory-optimized-read"))]
+        let package = ThreemfPackage::from_reader_with_speed_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        assert_eq!(
+            package.root.build.item[0].path.as_deref(),
+            Some("/3D/3dmodel.model")
+        );
+        assert_eq!(package.root.build.item[1].path, None);
+        assert_eq!(
+            get_items(&package)
+                .map(|item| item.path())
+                .collect::<Vec<_>>(),
+            vec![Some("/3D/3dmodel.model"), None]
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+
+        let mut model_xml = String::new();
+        zip::ZipArchive::new(&mut buf)
+            .unwrap()
+            .by_name("3D/3dmodel.model")
+            .unwrap()
+            .read_to_string(&mut model_xml)
+            .unwrap();
+        assert_eq!(
+            model_xml.matches(r#"p:path="/3D/3dmodel.model""#).count(),
+            1
+        );
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, package.root);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, package.root);
+        }
+    }
+
+    #[test]
+    fn export_item_test() {
+        use std::{fs::File, path::PathBuf};
+
+        use threemf2::io::query::get_items;
+
+        let path = PathBuf::from("./tests/data/mesh-composedpart.3mf");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        let package = ThreemfPackage::from_reader_with_memory_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        #[cfg(not(feature = "io-memory-optimized-read"))]
+        let package = ThreemfPackage::from_reader_with_speed_optimized_deserializer(
+            File::open(path).unwrap(),
+            false,
+        )
+        .expect("Error reading package");
+        assert_eq!(package.root.build.item.len(), 2);
+
+        let composed_part = get_items(&package)
+            .find(|item| item.objectid() == 4)
+            .unwrap();
+        let exported = package.export_item(&composed_part).unwrap();
+        assert_eq!(
+            exported.root.build.item,
+            vec![package.root.build.item[1].clone()]
+        );
+        let mut ids = exported
+            .root
+            .resources
+            .object
+            .iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec![2, 3, 4]);
+        // the color groups of the objects are kept
+        assert_eq!(exported.root.resources.unknown_resources.len(), 3);
+        assert!(exported.sub_models.is_empty());
+
+        let pyramid = get_items(&package)
+            .find(|item| item.objectid() == 1)
+            .unwrap();
+        let exported_pyramid = package.export_item(&pyramid).unwrap();
+        assert_eq!(
+            exported_pyramid
+                .root
+                .resources
+                .object
+                .iter()
+                .map(|o| o.name.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("Pyramid")]
+        );
+
+        let mut buf = Cursor::new(Vec::new());
+        exported.write(&mut buf).expect("Error writing package");
+
+        #[cfg(feature = "io-memory-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, exported.root);
+        }
+        #[cfg(feature = "io-speed-optimized-read")]
+        {
+            let reread =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(&mut buf, false)
+                    .expect("Error reading written package");
+            assert_eq!(reread.root, exported.root);
+        }
+    }
+
+    #[test]
+    fn roundtrip_preserves_object_ids_test() {
+        use std::{
+            fs::File,
+            io::{Read, Seek},
+            path::PathBuf,
+        };
+
+        use threemf2::io::query::{get_components_objects, get_items, get_objects};
+
+        fn read<R: Read + Seek>(reader: R) -> ThreemfPackage {
+            #[cfg(feature = "io-memory-optimized-read")]
+            let package =
+                ThreemfPackage::from_reader_with_memory_optimized_deserializer(reader, true);
+            #[cfg(not(feature = "io-memory-optimized-read"))]
+            let package =
+                ThreemfPackage::from_reader_with_speed_optimized_deserializer(reader, true);
+
+            package.expect("Error reading package")
+        }
+
+        // every id referencing or identifying an object, with the model it lives in
+        fn ids(package: &ThreemfPackage) -> Vec<(Option<String>, usize)> {
+            let objects = get_objects(package).map(|o| (o.path.map(str::to_owned), o.object.id));
+            let components = get_components_objects(package)
+                .flat_map(|o| o.components().collect::<Vec<_>>())
+                .map(|c| (c.path_to_look_for, c.objectid));
+            let items = get_items(package).map(|i| (i.path().map(str::to_owned), i.objectid()));
+            let mut ids = objects.chain(components).chain(items).collect::<Vec<_>>();
+            ids.sort();
+            ids
+        }
+
+        let path = PathBuf::from("./tests/data/mesh-composedpart-separate-model-files.3mf");
+        let mut package = read(File::open(path).unwrap());
+        let ids_before = ids(&package);
+
+        let (path, object) = package
+            .sub_models
+            .iter_mut()
+            .flat_map(|(path, model)| model.resources.object.iter_mut().map(move |o| (path, o)))
+            .find(|(_, o)| o.components.is_some())
+            .expect("the fixture has a composed object in a sub-model");
+        let (path, id) = (path.clone(), object.id);
+        object.name = Some("Renamed".to_owned());
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).expect("Error writing package");
+        let reread = read(&mut buf);
+
+        assert_eq!(ids(&reread), ids_before);
+        let renamed = reread.sub_models[&path]
+            .resources
+            .object
+            .iter()
+            .find(|o| o.id == id)
+            .unwrap();
+        assert_eq!(renamed.name.as_deref(), Some("Renamed"));
+        assert_eq!(reread.root, package.root);
+        assert_eq!(reread.sub_models, package.sub_models);
+    }
 }